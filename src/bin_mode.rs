@@ -1,40 +1,20 @@
 //! Module defining the Mode enum used by the executable binary. Kept separate
 //!     from `main.rs` since it is more code than definition.
 
-use tengwar::*;
-
-
-fn convert<M: TengwarMode + Default, T: FromIterator<Token>>(
-    input: impl ToTengwar,
-    settings: TranscriberSettings,
-) -> T {
-    input.transcriber::<M>().with_settings(settings).collect()
-}
-
-
-#[derive(Clone, Copy, Debug, ValueEnum)]
-pub enum Language {
-    /// The High Elven of the Noldorin Exiles.
-    #[value(alias = "q", alias = "qya")]
-    Quenya,
-    /// The Grey Elven of Beleriand.
-    #[value(alias = "s", alias = "sjn")]
-    Sindarin,
-    /*/// The language of the Angles.
-    #[value(alias = "e", alias = "eng")]
-    English,*/
-}
-
-#[allow(dead_code)]
-impl Language {
-    pub const fn mode(&self) -> Mode {
-        match self {
-            Self::Quenya => Mode::Classical,
-            Self::Sindarin => Mode::Gondor,
-            /*Self::English => Mode::English,*/
-        }
-    }
-}
+use std::{io::Write, iter::once};
+use tengwar::{*, characters::encoding::Encoding, mode::{
+    auto::{Auto, Unit},
+    markup::{MarkupError, Syntax},
+    Directed,
+    DynMode,
+    Language,
+    Markup,
+    ModeRegistry,
+}, policy::Font, span::Mapped, trace::{describe, Explanation, Trace}};
+#[cfg(feature = "mode-custom")]
+use tengwar::mode::{custom::base::BaseMode, CustomMode};
+#[cfg(feature = "policy-custom")]
+use tengwar::policy::data::{DataPolicy, PolicyFile};
 
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
@@ -45,44 +25,281 @@ pub enum Mode {
     /// The Mode of Gondor, used for Sindarin.
     #[value(alias = "g")]
     Gondor,
+    /// The "Full" Mode of Gondor, used for Sindarin.
+    #[value(alias = "gf")]
+    GondorFull,
     /// The Mode of Beleriand, used for Sindarin.
     #[value(alias = "b")]
     Beleriand,
-    /*/// An orthographic "general" mode for English.
+    /// An orthographic "general" mode for English.
     #[value(alias = "e")]
-    English,*/
+    English,
+    /// The Angerthas Daeron, used for writing the Cirth.
+    #[value(alias = "daeron")]
+    AngerthasDaeron,
+    /// The Angerthas Moria, used for writing the Cirth.
+    #[value(alias = "moria")]
+    AngerthasMoria,
+    /// Detect Quenya or Sindarin for each word, and use its primary mode.
+    #[value(alias = "a")]
+    Auto,
+    /// Detect Quenya or Sindarin for each line, and use its primary mode.
+    #[value(alias = "al")]
+    AutoLine,
 }
 
 impl Mode {
     #[allow(dead_code)]
     pub const DEFAULT: Self = Self::Classical;
 
-    pub fn convert<T: FromIterator<Token>>(
-        &self,
-        input: impl ToTengwar,
-        settings: TranscriberSettings,
-    ) -> T {
+    /// Return the built-in mode that a mode file may extend, if this mode has
+    ///     one.
+    #[cfg(feature = "mode-custom")]
+    pub const fn base(&self) -> Option<BaseMode> {
         match self {
-            Self::Classical => convert::<Quenya, T>(input, settings),
-            Self::Gondor => convert::<Gondor, T>(input, settings),
-            Self::Beleriand => convert::<Beleriand, T>(input, settings),
-            /*Self::English => convert::<English, T>(input, settings),*/
+            Self::Classical => Some(BaseMode::Quenya),
+            Self::Gondor => Some(BaseMode::Gondor),
+            Self::Beleriand => Some(BaseMode::Beleriand),
+            _ => None,
         }
     }
+
+    /// Return the automatic mode that this mode represents, if it is one.
+    pub const fn auto(&self) -> Option<Auto> {
+        match self {
+            Self::Auto => Some(Auto::new(Unit::Word)),
+            Self::AutoLine => Some(Auto::new(Unit::Line)),
+            _ => None,
+        }
+    }
+
+    /// Return the primary mode of a language.
+    pub fn of_language(language: Language) -> Self {
+        clap::ValueEnum::from_str(language.primary_mode(), true)
+            .expect("primary mode of language is not defined")
+    }
+
+    /// Return the name of this mode, as used by the [`ModeRegistry`].
+    pub fn name(&self) -> String {
+        let value = clap::ValueEnum::to_possible_value(self).expect("mode has no name");
+        value.get_name().to_owned()
+    }
+
+    /// Create an instance of this mode, from a [`ModeRegistry`].
+    pub fn create(&self, registry: &ModeRegistry) -> Box<dyn DynMode> {
+        registry.get(&self.name()).expect("mode is not registered")
+    }
 }
 
 
 pub struct Runner {
-    pub mode: Mode,
+    pub name: String,
+    pub mode: Box<dyn DynMode>,
+    pub registry: ModeRegistry,
+    pub auto: Option<Auto>,
+    pub markup: Option<Syntax>,
     pub settings: TranscriberSettings,
+    pub encoding: Encoding,
+    pub source: Option<Encoding>,
+    pub font: Option<Font>,
+    #[cfg(feature = "policy-custom")]
+    pub policy: Option<DataPolicy>,
 }
 
 impl Runner {
-    pub const fn new(mode: Mode, settings: TranscriberSettings) -> Self {
-        Self { mode, settings }
+    pub fn new(mode: Mode, settings: TranscriberSettings) -> Self {
+        let registry = ModeRegistry::new();
+
+        Self {
+            name: mode.name(),
+            mode: mode.create(&registry),
+            registry,
+            auto: mode.auto(),
+            markup: None,
+            settings,
+            encoding: Encoding::DEFAULT,
+            source: None,
+            font: None,
+            #[cfg(feature = "policy-custom")]
+            policy: None,
+        }
+    }
+
+    /// Use a mode loaded from a file, instead of the built-in mode.
+    #[cfg(feature = "mode-custom")]
+    pub fn with_custom(mut self, name: String, custom: CustomMode) -> Self {
+        self.name = name;
+        self.mode = Box::new(custom);
+        self.auto = None;
+        self
+    }
+
+    /// Allow spans of the input to be marked for transcription in other modes.
+    pub fn with_markup(mut self, syntax: Syntax) -> Self {
+        self.markup = Some(syntax);
+        self.mode = Box::new(Markup::new(self.mode).with_syntax(syntax));
+        self
+    }
+
+    /// Read inline directives from the input. This should be done after any
+    ///     markup is allowed, so that the directives of every span are read.
+    pub fn with_directives(mut self) -> Self {
+        self.mode = Box::new(Directed(self.mode));
+        self
+    }
+
+    /// Find any problems with the markup of the input, if markup is allowed.
+    pub fn check(&self, input: &str) -> Vec<MarkupError> {
+        match &self.markup {
+            Some(syntax) => syntax.parse(input, &self.registry).1,
+            None => Vec::new(),
+        }
+    }
+
+    /// Write the Tengwar of the output in a different encoding.
+    pub const fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Read text to be recoded from a specific encoding, rather than from either
+    ///     block of the Tengwar.
+    pub const fn with_source(mut self, source: Encoding) -> Self {
+        self.source = Some(source);
+        self
     }
 
-    pub fn convert<T: FromIterator<Token>>(&self, input: impl ToTengwar) -> T {
-        self.mode.convert::<T>(input, self.settings)
+    /// Render the output under the policy of a specific font.
+    pub const fn with_font(mut self, font: Font) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Render the output under the policy defined by a file, rather than that
+    ///     of a built-in font. The file is kept for the rest of the program.
+    #[cfg(feature = "policy-custom")]
+    pub fn with_policy(mut self, policy: PolicyFile) -> Self {
+        self.policy = Some(DataPolicy::new(Box::leak(Box::new(policy))));
+        self
+    }
+
+    /// Transcribe the input, under the policy of the font or file, if there is
+    ///     one.
+    fn render(&self, input: &str) -> String {
+        #[cfg(feature = "policy-custom")]
+        if let Some(policy) = self.policy {
+            return TokenIter::new(self.mode.boxed_tokenizer_in(input, policy, false))
+                .with_settings(self.settings)
+                .collect();
+        }
+
+        match self.font {
+            Some(font) => font.render(self.mode.boxed_tokenizer(input), self.settings),
+            None => self.mode.boxed_transcriber(input, self.settings).collect(),
+        }
+    }
+
+    pub fn convert(&self, input: impl AsRef<str>) -> String {
+        let text: String = self.render(input.as_ref());
+
+        if self.encoding == Encoding::DEFAULT {
+            text
+        } else {
+            self.encoding.recode(&text)
+        }
+    }
+
+    /// Convert text that is already written in the Tengwar into the encoding
+    ///     of the output.
+    pub fn recode(&self, input: impl AsRef<str>) -> String {
+        match self.source {
+            Some(source) => self.encoding.recode(&source.import(input.as_ref())),
+            None => self.encoding.recode(input.as_ref()),
+        }
+    }
+
+    /// Report the language detected for each part of the input, if the mode is
+    ///     automatic.
+    pub fn report(&self, input: &str, out: &mut impl Write) -> std::io::Result<()> {
+        if let Some(auto) = &self.auto {
+            for segment in auto.segments(input) {
+                let text: &str = input[segment.range].trim_end();
+
+                if segment.score == 0 {
+                    writeln!(out, "{} (assumed): {text:?}", segment.language)?;
+                } else {
+                    writeln!(out, "{} ({:+}): {text:?}", segment.language, segment.score)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+    /// Write a table explaining how each token of the output was produced.
+    pub fn explain(&self, input: &str, out: &mut impl Write) -> std::io::Result<()> {
+        const HEADER: [&str; 5] = ["INPUT", "OUTPUT", "TOKEN", "CHANGES", "STEPS"];
+
+        let trace = Trace::shared();
+        let mapped: Vec<Mapped> = self.mode
+            .boxed_explained(input, self.settings, &trace)
+            .collect();
+
+        //  Directives are never returned by a transcriber, so they must also be
+        //      skipped here to line up the explanations with the tokens.
+        let explained: Vec<Explanation> = trace.borrow().explain()
+            .into_iter()
+            .filter(|exp| !matches!(exp.token, Token::Directive(_)))
+            .collect();
+
+        let rows: Vec<[String; 5]> = mapped.iter().zip(&explained).map(|(mapped, exp)| {
+            let output: Vec<String> = self.recode(mapped.token.to_string()).chars()
+                .map(|c| format!("U+{:04X}", c as u32))
+                .collect();
+
+            let mut changes: Vec<String> = Vec::new();
+
+            if let Some(token) = &exp.finalized {
+                changes.push(format!("finalize -> {}", describe(token)));
+            }
+
+            for (rule, token) in &exp.rules {
+                changes.push(format!("{rule} -> {}", describe(token)));
+            }
+
+            let steps: Vec<String> = exp.steps.iter()
+                .map(|(chunk, action)| format!("{chunk:?} {action}"))
+                .collect();
+
+            [
+                format!("{:?}", &input[mapped.input.bytes.clone()]),
+                output.join(" "),
+                describe(&exp.token),
+                if changes.is_empty() { "-".into() } else { changes.join("; ") },
+                steps.join(", "),
+            ]
+        }).collect();
+
+        let mut widths: [usize; 5] = HEADER.map(str::len);
+
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        for row in once(HEADER.map(String::from)).chain(rows) {
+            let [cells @ .., steps] = &row;
+            let mut line = String::new();
+
+            for (cell, &width) in cells.iter().zip(&widths) {
+                line.push_str(cell);
+                line.extend(std::iter::repeat_n(' ', width - cell.chars().count() + 2));
+            }
+
+            line.push_str(steps);
+            writeln!(out, "{}", line.trim_end())?;
+        }
+
+        Ok(())
     }
 }
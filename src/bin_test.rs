@@ -2,11 +2,14 @@
 #![cfg(test)]
 
 use std::iter::once;
+use tengwar::characters::encoding::Encoding;
 use crate::Command;
 
 
 const ARG_Q: &str = "eleni sílar";
 const ARG_S: &str = "giliath sílar";
+const ARG_E: &str = "the stars shine";
+const ARG_K: &str = "balin fundinul";
 
 
 /// Run the CLI self-check.
@@ -26,9 +29,37 @@ fn test_modes() {
     run(["--quenya", ARG_Q]);
     run(["--gondor", ARG_S]);
     run(["--beleriand", ARG_S]);
+    run(["--english", ARG_E]);
     run(["-Q", ARG_Q]);
     run(["-G", ARG_S]);
     run(["-B", ARG_S]);
+    run(["-E", ARG_E]);
+
+    //  Language options.
+    run(["--sindarin", ARG_S]);
+    run(["-S", ARG_S]);
+    run(["--language", "quenya", ARG_Q]);
+    run(["--language=Sindarin", ARG_S]);
+    run(["-L", "eng", ARG_E]);
+    run(["-Lq", ARG_Q]);
+    run(["-Ls", ARG_S]);
+    assert_eq!(run(["-S", ARG_S]), run(["-G", ARG_S]));
+    assert_eq!(run(["-E", ARG_E]), run(["-Me", ARG_E]));
+
+    //  Language options, with a compatible mode.
+    run(["-Q", "-Mc", ARG_Q]);
+    run(["-S", "-Mgf", ARG_S]);
+    run(["-S", "-B", ARG_S]);
+    run(["-S", "-Mdaeron", ARG_K]);
+    run(["-E", "-Me", ARG_E]);
+    run(["-E", "-Mmoria", ARG_K]);
+
+    //  Automatic modes.
+    assert_eq!(run(["-Ma", ARG_Q]), run(["-Q", ARG_Q]));
+    assert_eq!(run(["-Ma", ARG_S]), run(["-S", ARG_S]));
+    assert_eq!(run(["-Mal", ARG_S]), run(["-S", ARG_S]));
+    run(["-Ls", "-G", ARG_S]);
+    assert_eq!(run(["-S", "-B", ARG_S]), run(["-B", ARG_S]));
 
     //  Mode option, separate.
     run(["--mode", "classical", ARG_Q]);
@@ -42,6 +73,18 @@ fn test_modes() {
     run(["--mode", "beleriand", ARG_S]);
     run(["--mode", "B", ARG_S]);
     run(["--mode", "b", ARG_S]);
+    run(["--mode", "gondor-full", ARG_S]);
+    run(["--mode", "GF", ARG_S]);
+    run(["--mode", "gf", ARG_S]);
+    run(["--mode", "english", ARG_E]);
+    run(["--mode", "E", ARG_E]);
+    run(["--mode", "e", ARG_E]);
+    run(["--mode", "angerthas-daeron", ARG_K]);
+    run(["--mode", "daeron", ARG_K]);
+    run(["--mode", "angerthas-moria", ARG_K]);
+    run(["--mode", "Moria", ARG_K]);
+    run(["--mode", "auto", ARG_Q]);
+    run(["--mode", "auto-line", ARG_S]);
     //  Short.
     run(["-M", "classical", ARG_Q]);
     run(["-M", "c", ARG_Q]);
@@ -52,6 +95,11 @@ fn test_modes() {
     run(["-M", "beleriand", ARG_S]);
     run(["-M", "B", ARG_S]);
     run(["-M", "b", ARG_S]);
+    run(["-M", "gondor-full", ARG_S]);
+    run(["-M", "gf", ARG_S]);
+    run(["-M", "english", ARG_E]);
+    run(["-M", "E", ARG_E]);
+    run(["-M", "e", ARG_E]);
 
     //  Mode option, joined.
     run(["--mode=classical", ARG_Q]);
@@ -98,19 +146,216 @@ fn test_modes_invalid() {
     run_err(["-M", "--", ARG_Q]);
 
     //  Conflicting mode options.
-    run_err(["-QG", ARG_S]);
-    run_err(["-QB", ARG_S]);
     run_err(["-GB", ARG_S]);
     run_err(["-QGB", ARG_S]);
-    run_err(["-Q", "-Mc", ARG_Q]);
-    run_err(["-Q", "-Mg", ARG_Q]);
-    run_err(["-Q", "-Mb", ARG_Q]);
     run_err(["-G", "-Mc", ARG_S]);
     run_err(["-G", "-Mg", ARG_S]);
     run_err(["-G", "-Mb", ARG_S]);
     run_err(["-B", "-Mc", ARG_S]);
     run_err(["-B", "-Mg", ARG_S]);
     run_err(["-B", "-Mb", ARG_S]);
+    run_err(["-G", "-Mgf", ARG_S]);
+
+    //  Conflicting language options.
+    run_err(["-QE", ARG_E]);
+    run_err(["-QS", ARG_S]);
+    run_err(["-SE", ARG_E]);
+    run_err(["-Q", "-Lq", ARG_Q]);
+    run_err(["--language", ARG_Q]);
+    run_err(["--language", "klingon", ARG_Q]);
+
+    //  Languages with modes that are not used for them.
+    run_err(["-QG", ARG_S]);
+    run_err(["-QB", ARG_S]);
+    run_err(["-GE", ARG_E]);
+    run_err(["-BE", ARG_E]);
+    run_err(["-Q", "-Mg", ARG_Q]);
+    run_err(["-Q", "-Mb", ARG_Q]);
+    run_err(["-Q", "-Mdaeron", ARG_Q]);
+    run_err(["-S", "-Mc", ARG_S]);
+    run_err(["-S", "-Me", ARG_S]);
+    run_err(["-E", "-Mc", ARG_E]);
+    run_err(["-E", "-Mdaeron", ARG_E]);
+    run_err(["-Q", "-Mauto", ARG_Q]);
+    run_err(["-S", "-Mauto-line", ARG_S]);
+
+    let err = run_err(["-Q", "-Mb", ARG_Q]).to_string();
+    assert!(err.contains("the mode `beleriand` cannot be used for Quenya"), "{err}");
+}
+
+
+/// Test the report of detected languages.
+#[test]
+fn test_verbose() {
+    let report = |args: &[&str], text: &str| -> String {
+        let exec: Command = clap::Parser::try_parse_from(once("tengwar").chain(args.iter().copied()))
+            .unwrap();
+        let mut out: Vec<u8> = Vec::new();
+        exec.runner().unwrap().report(text, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+
+    assert_eq!(
+        report(&["-v", "-Mauto"], "namárië Ennyn Durin"),
+        "quenya (-3): \"namárië\"\nsindarin (+5): \"Ennyn Durin\"\n",
+    );
+    assert_eq!(
+        report(&["-v", "-Mauto-line"], "namárië Ennyn Durin"),
+        "sindarin (+2): \"namárië Ennyn Durin\"\n",
+    );
+    assert_eq!(report(&["-v", "-Mauto"], "aran"), "quenya (assumed): \"aran\"\n");
+    assert_eq!(report(&["-v", "-Q"], "namárië"), "");
+}
+
+
+/// Test inline markup to change modes.
+#[test]
+fn test_markup() {
+    let marked = format!("{ARG_S} {{q:{ARG_Q}}}");
+    let expected = format!("{}{}", run(["-S", &format!("{ARG_S} ")]), run(["-Q", ARG_Q]));
+
+    assert_eq!(run(["-S", "--markup", &marked]), expected);
+    assert_eq!(run(["-S", "--markup=[|]", &format!("{ARG_S} [q|{ARG_Q}]")]), expected);
+    assert_eq!(run(["--markup", &format!("{{s:{ARG_S} }}{ARG_Q}")]), expected);
+
+    //  Without the option, markup is transcribed as text.
+    assert_ne!(run(["-S", &marked]), expected);
+    assert_eq!(run(["-S", "--markup", ARG_S]), run(["-S", ARG_S]));
+
+    //  Invalid syntax.
+    run_err(["--markup=ab:", ARG_Q]);
+    run_err(["--markup=[|", ARG_Q]);
+    run_err(["--markup=\\|]", ARG_Q]);
+
+    //  Problems with markup are reported, but do not prevent transcription.
+    let exec: Command = clap::Parser::try_parse_from(["tengwar", "--markup", "{x:a} {q:b"]).unwrap();
+    let errors: Vec<String> = exec.runner().unwrap().check("{x:a} {q:b")
+        .into_iter()
+        .map(|err| err.to_string())
+        .collect();
+    assert_eq!(errors, [
+        "unknown mode `x` at byte 0",
+        "span of mode `q` at byte 6 is not closed",
+    ]);
+}
+
+
+/// Test the table explaining each token.
+#[test]
+fn test_explain() {
+    let explain = |args: &[&str], text: &str| -> Vec<String> {
+        let exec: Command = clap::Parser::try_parse_from(once("tengwar").chain(args.iter().copied()))
+            .unwrap();
+        let mut out: Vec<u8> = Vec::new();
+        exec.runner().unwrap().explain(text, &mut out).unwrap();
+        String::from_utf8(out).unwrap().lines().map(String::from).collect()
+    };
+
+    let lines = explain(&["--explain", "-S"], "ered");
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("INPUT"), "{lines:?}");
+    assert!(lines[1].starts_with("\"er\""), "{lines:?}");
+    assert!(lines[1].contains("finalize -> rómen + e"), "{lines:?}");
+    assert!(lines[2].starts_with("\"ed\""), "{lines:?}");
+
+    let lines = explain(&["--explain", "-a"], "na");
+    assert!(lines[1].contains("alt_a -> númen + yanta"), "{lines:?}");
+
+    //  A directive does not have a row of its own.
+    let lines = explain(&["--explain", "--directives"], "{-nuquerna}essë");
+    assert_eq!(lines.len(), 3);
+    assert!(lines[2].starts_with("\"ssë\""), "{lines:?}");
+    assert!(!lines[2].contains("nuquerna"), "{lines:?}");
+
+    assert_eq!(explain(&["--explain"], ""), ["INPUT  OUTPUT  TOKEN  CHANGES  STEPS"]);
+
+    //  The table does not follow the rules of a font.
+    let conflict = |args: &[&str]| -> bool {
+        let parsed: Result<Command, clap::Error> = clap::Parser::try_parse_from(
+            once("tengwar").chain(args.iter().copied()),
+        );
+        parsed.is_err_and(|err| err.kind() == clap::error::ErrorKind::ArgumentConflict)
+    };
+    assert!(conflict(&["--explain", "--font", "annatar"]));
+    #[cfg(feature = "policy-custom")]
+    assert!(conflict(&["--explain", "--policy-file", "rules.toml"]));
+}
+
+
+/// Test the encoding of the output.
+#[test]
+fn test_encoding() {
+    let csur = run(["--encoding=csur", ARG_Q]);
+    let smp = run(["--encoding", "smp", ARG_Q]);
+    let default = run([ARG_Q]);
+
+    assert_ne!(csur, smp);
+    assert_eq!(default, Encoding::DEFAULT.recode(&csur));
+    assert_eq!(run(["--encoding=unicode", ARG_Q]), smp);
+    assert_eq!(run(["--encoding=PUA", ARG_Q]), csur);
+    run_err(["--encoding=utf8", ARG_Q]);
+
+    //  Existing text is converted, rather than transliterated.
+    let recode = |args: &[&str], text: &str| -> String {
+        let exec: Command = clap::Parser::try_parse_from(once("tengwar").chain(args.iter().copied()))
+            .unwrap();
+        exec.runner().unwrap().recode(text)
+    };
+
+    assert_eq!(recode(&["--recode", "--encoding=smp"], &csur), smp);
+    assert_eq!(recode(&["--recode", "--encoding=csur"], &smp), csur);
+    assert_eq!(recode(&["--recode"], ARG_Q), ARG_Q);
+    run_err(["--recode", "--markup", ARG_Q]);
+
+    //  The Dan Smith layout is written, and read only when selected.
+    let ds = run(["--encoding=dan-smith", ARG_Q]);
+
+    assert!(ds.is_ascii());
+    assert_eq!(run(["--encoding=ds", ARG_Q]), ds);
+    assert_eq!(recode(&["--recode", "--encoding=ds"], &smp), ds);
+    assert_eq!(recode(&["--recode", "--from=dan-smith"], &ds), default);
+    assert_eq!(recode(&["--recode", "--from=ds", "--encoding=smp"], &ds), smp);
+    assert_eq!(recode(&["--recode"], &ds), ds);
+    run_err(["--from=ds", ARG_Q]);
+}
+
+
+/// Test the selection of a font profile.
+#[test]
+fn test_font() {
+    let plain = run(["lómë"]);
+
+    //  Each font changes the default style.
+    assert_ne!(run(["--font=telcontar", "istar"]), run(["istar"]));
+    assert_eq!(run(["--font=telcontar", "istar"]), run(["-s", "-z", "istar"]));
+    assert_eq!(run(["--font", "formal", ARG_Q]), run(["--font=formal-csur", ARG_Q]));
+    assert_eq!(run(["--font=FreeMono", "-z", "istar"]), run(["istar"]));
+    run_err(["--font=telcontarr", ARG_Q]);
+
+    //  Options given explicitly are still applied.
+    assert_eq!(run(["--font=free-mono", "--long=doubled", "lómë"]), plain);
+    assert_ne!(run(["--font=free-mono", "lómë"]), plain);
+
+    //  A style of the font can be turned off.
+    assert_ne!(run(["--font=alcarin", "lucs"]), run(["--font=alcarin", "--alt-rince=false", "lucs"]));
+    assert_eq!(
+        run(["--font=alcarin", "--alt-rince=false", "--ligate-short=false", "lucs"]),
+        run(["-zzz", "lucs"]),
+    );
+    assert_eq!(run(["--font=telcontar", "-s=false", "istar"]), run(["-z", "istar"]));
+    assert_eq!(run(["--no-nuquernar=false", ARG_Q]), run([ARG_Q]));
+    assert_eq!(run(["--alt-a=true", ARG_Q]), run(["-a", ARG_Q]));
+    assert_eq!(run(["-as", ARG_Q]), run(["-a", "-s", ARG_Q]));
+    run_err(["--alt-a=maybe", ARG_Q]);
+
+    //  A font in the Dan Smith layout changes the default encoding.
+    let ds = run(["--font=annatar", ARG_Q]);
+    assert!(ds.is_ascii());
+    assert_eq!(
+        run(["--font=annatar", "--encoding=csur", ARG_Q]),
+        run(["--long=s", "--encoding=csur", ARG_Q]),
+    );
+    assert_eq!(run(["--font=annatar", "--encoding=ds", ARG_Q]), ds);
 }
 
 
@@ -185,17 +430,218 @@ fn test_styles() {
 }
 
 
+/// Test loading a custom mode from a file.
+#[test]
+#[cfg(feature = "mode-custom")]
+fn test_mode_file() {
+    let dir = std::env::temp_dir();
+    let valid = dir.join(format!("tengwar-{}-valid.toml", std::process::id()));
+    let invalid = dir.join(format!("tengwar-{}-invalid.toml", std::process::id()));
+
+    std::fs::write(&valid, concat!(
+        "chunks = 1\n",
+        "checks_mod = [\"Vowel\"]\n",
+        "checks_new = [\"Consonant\"]\n",
+        "[consonants]\n",
+        "t = \"\\uE000\"\n",
+        "[vowels]\n",
+        "a = \"\\uE040\"\n",
+    )).unwrap();
+    std::fs::write(&invalid, "chunks = 1\nchecks_mod = 1\n").unwrap();
+
+    let valid_str = valid.to_str().unwrap();
+    let invalid_str = invalid.to_str().unwrap();
+
+    assert_eq!(run(["--mode-file", valid_str, "tata"]), "\u{E000}\u{E040}\u{E000}\u{E040}");
+    let err = run_err(["--mode-file", invalid_str, "tata"]).to_string();
+    assert!(err.contains("line 2, column 14: at `checks_mod`"), "{err}");
+
+    run_err(["--mode-file", "/nonexistent/mode.toml", "tata"]);
+    run_err(["-Q", "--mode-file", valid_str, "tata"]);
+    run_err(["-Mc", "--mode-file", valid_str, "tata"]);
+
+    std::fs::remove_file(valid).ok();
+    std::fs::remove_file(invalid).ok();
+}
+
+
+/// Test loading a typographic policy from a file.
+#[test]
+#[cfg(feature = "policy-custom")]
+fn test_policy_file() {
+    let dir = std::env::temp_dir();
+    let valid = dir.join(format!("tengwar-{}-policy.toml", std::process::id()));
+    let invalid = dir.join(format!("tengwar-{}-policy-invalid.toml", std::process::id()));
+    let rince = dir.join(format!("tengwar-{}-policy-rince.toml", std::process::id()));
+
+    std::fs::write(&valid, concat!(
+        "extends = \"alcarin\"\n",
+        "rince_final = \"\"\n",
+    )).unwrap();
+    std::fs::write(&invalid, "extends = \"alcarin\"\nrince = 1\n").unwrap();
+    std::fs::write(&rince, "extends = \"telcontar\"\nrince = \"\\uE000\"\n").unwrap();
+
+    let valid_str = valid.to_str().unwrap();
+    let invalid_str = invalid.to_str().unwrap();
+    let rince_str = rince.to_str().unwrap();
+
+    //  Rules defined by the file replace those of the font it extends.
+    assert_ne!(run(["--font=alcarin", "-r", "lucs"]), run(["-r", "lucs"]));
+    assert_eq!(run(["--policy-file", valid_str, "-r", "lucs"]), run(["-r", "lucs"]));
+
+    //  Rules not defined by the file are those of the font, but the style of
+    //      the font is only used if it is selected.
+    assert_eq!(run(["--policy-file", valid_str, "-zzz", "istar"]), run(["-zzz", "istar"]));
+    assert_eq!(
+        run(["--policy-file", valid_str, "--font=alcarin", "istar"]),
+        run(["--font=alcarin", "istar"]),
+    );
+
+    //  A sa-rincë is only placed where the file allows it, in any mode.
+    let text: String = run(["--policy-file", rince_str, "ocso"]);
+    assert_eq!(run(["--policy-file", rince_str, "otso"]), run(["otso"]));
+    assert_ne!(text, run(["ocso"]));
+    let csur: String = tengwar::characters::encoding::Encoding::Csur.recode(&text);
+    assert!(!csur.contains(tengwar::characters::SA_RINCE));
+    assert_eq!(run(["--policy-file", rince_str, "--markup", "{q:ocso}"]), text);
+
+    let err = run_err(["--policy-file", invalid_str, ARG_Q]).to_string();
+    assert!(err.contains("line 2, column 9: at `rince`"), "{err}");
+    run_err(["--policy-file", "/nonexistent/policy.toml", ARG_Q]);
+
+    std::fs::remove_file(valid).ok();
+    std::fs::remove_file(invalid).ok();
+    std::fs::remove_file(rince).ok();
+}
+
+
+#[test]
+#[cfg(feature = "mode-custom")]
+fn test_check_mode() {
+    let path = std::env::temp_dir()
+        .join(format!("tengwar-{}-check.toml", std::process::id()));
+
+    std::fs::write(&path, concat!(
+        "extends = \"quenya\"\n",
+        "[[test]]\n",
+        "input = \"ta\"\n",
+        "expected = \"\\uE000\\uE040\"\n",
+        "[[test]]\n",
+        "input = \"ta\"\n",
+        "expected = \"\\uE000\\uE040\"\n",
+        "settings = { alt_a = true }\n",
+    )).unwrap();
+
+    let check = |args: &[&str]| -> Result<(bool, String), clap::Error> {
+        let exec: Command = clap::Parser::try_parse_from(once("tengwar").chain(args.iter().copied()))?;
+        let mut out: Vec<u8> = Vec::new();
+        let success = exec.action.expect("no action parsed").run(&mut out)?;
+        Ok((success, String::from_utf8(out).unwrap()))
+    };
+
+    let (success, report) = check(&["check-mode", path.to_str().unwrap()]).unwrap();
+    assert!(!success);
+    assert!(report.contains("test 1 (\"ta\"): ok"), "{report}");
+    assert!(report.contains("test 2 (\"ta\"): FAILED"), "{report}");
+    assert!(report.contains("glyph 0: expected \"\u{E000}\u{E040}\" (U+E000 U+E040), \
+        received \"\u{E000}\u{E055}\" (U+E000 U+E055)"), "{report}");
+    assert!(report.ends_with("2 tests, 1 failed\n"), "{report}");
+
+    assert!(check(&["check-mode", "/nonexistent/mode.toml"]).is_err());
+    assert!(check(&["check-mode", "-Q", path.to_str().unwrap()]).is_err());
+
+    //  Words can still be transliterated normally.
+    assert_eq!(run(["check", "mode"]), run(["--", "check", "mode"]));
+
+    std::fs::remove_file(path).ok();
+}
+
+#[test]
+#[cfg(feature = "mode-custom")]
+fn test_dump_mode() {
+    use tengwar::mode::custom::file::ModeFile;
+
+    let dump = |args: &[&str]| -> Result<String, clap::Error> {
+        let exec: Command = clap::Parser::try_parse_from(once("tengwar").chain(args.iter().copied()))?;
+        let mut out: Vec<u8> = Vec::new();
+        exec.action.expect("no action parsed").run(&mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    };
+
+    let path = std::env::temp_dir()
+        .join(format!("tengwar-{}-dump.toml", std::process::id()));
+    let path_str: &str = path.to_str().unwrap();
+
+    for mode in ["classical", "c", "gondor", "G", "beleriand", "b"] {
+        let text = dump(&["dump-mode", mode]).unwrap();
+        let file = ModeFile::from_toml_str(&text).unwrap();
+        assert!(!file.consonants.is_empty(), "{mode}: {text}");
+        assert!(!text.contains(|c| ('\u{E000}'..='\u{E07F}').contains(&c)), "{mode}: {text}");
+
+        //  The exported file transcribes in the same way as the mode itself.
+        std::fs::write(&path, &text).unwrap();
+
+        for input in ["lhûg nef rhaw iant nædh", "hyarmen ylf axë", "mellon ithil"] {
+            assert_eq!(
+                run(["--mode-file", path_str, input]),
+                run(["--mode", mode, input]),
+                "{mode}: {input:?}",
+            );
+        }
+    }
+
+    std::fs::remove_file(&path).ok();
+
+    let err = dump(&["dump-mode", "english"]).unwrap_err().to_string();
+    assert!(err.contains("the mode `english` cannot be exported"), "{err}");
+    assert!(dump(&["dump-mode", "nonexistent"]).is_err());
+}
+
+#[test]
+#[cfg(feature = "font-coverage")]
+fn test_check_font() {
+    use tengwar::{policy::Font, TranscriberSettings};
+
+    let path = std::env::temp_dir()
+        .join(format!("tengwar-{}-font.ttf", std::process::id()));
+    std::fs::write(&path, "not a font").unwrap();
+
+    let check = |args: &[&str]| -> Result<bool, clap::Error> {
+        let exec: Command = clap::Parser::try_parse_from(once("tengwar").chain(args.iter().copied()))?;
+        exec.action.expect("no action parsed").run(&mut Vec::new())
+    };
+
+    let err = check(&["check-font", path.to_str().unwrap()]).unwrap_err().to_string();
+    assert!(err.contains("invalid font file"), "{err}");
+    assert!(check(&["check-font", "/nonexistent/font.ttf"]).is_err());
+
+    //  The options reported for a font select the same settings.
+    for settings in [
+        Font::Telcontar.settings(),
+        Font::FreeMono.settings(),
+        TranscriberSettings { ligate_zwj: 3, nuquerna: false, ..Font::FormalCsur.settings() },
+    ] {
+        let options: Vec<String> = crate::options(&settings);
+        let args: Vec<&str> = options.iter().map(String::as_str).chain([ARG_Q]).collect();
+        let exec: Command = clap::Parser::try_parse_from(once("tengwar").chain(args)).unwrap();
+
+        assert_eq!(exec.settings(), settings, "{options:?}");
+    }
+
+    std::fs::remove_file(path).ok();
+}
+
 fn try_run<'s, I, T>(input: I) -> Result<String, clap::Error> where
     I: IntoIterator<Item=&'s T>,
     T: AsRef<str> + ?Sized + 's,
 {
     const BIN: &str = env!("CARGO_BIN_NAME");
 
-    let args: _ = once(BIN).chain(input.into_iter().map(|s| s.as_ref()));
+    let args = once(BIN).chain(input.into_iter().map(|s| s.as_ref()));
     let exec: Command = clap::Parser::try_parse_from(args)?;
     let text: String = exec.text.join(" ");
 
-    Ok(exec.runner().convert(text))
+    Ok(exec.runner()?.convert(text))
 }
 
 fn run<'s>(input: impl AsRef<[&'s str]>) -> String {
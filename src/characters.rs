@@ -7,8 +7,9 @@
 //!     [`Glyph`], [`Numeral`], and [`Tengwa`] types.
 //  TODO: Document EVERY ITEM in this module.
 
-// #[cfg_attr(feature = "csur", path = "characters/consts_csur.rs")]
 pub mod consts;
+pub mod dan_smith;
+pub mod encoding;
 pub mod glyph;
 pub mod numeral;
 pub mod tehta;
@@ -393,6 +393,62 @@ pub const TENGWA_WAIA: char = '';
 pub const ZWJ: char = '‍';
 
 
+/// Return the traditional name of a tengwa, if it has one.
+pub const fn tengwa_name(c: char) -> Option<&'static str> {
+    Some(match c {
+        TENGWA_TINCO => "tinco",
+        TENGWA_ANDO => "ando",
+        TENGWA_THULE => "thúlë",
+        TENGWA_ANTO => "anto",
+        TENGWA_NUMEN => "númen",
+        TENGWA_ORE => "órë",
+
+        TENGWA_PARMA => "parma",
+        TENGWA_UMBAR => "umbar",
+        TENGWA_FORMEN => "formen",
+        TENGWA_AMPA => "ampa",
+        TENGWA_MALTA => "malta",
+        TENGWA_VALA => "vala",
+
+        TENGWA_CALMA => "calma",
+        TENGWA_ANGA => "anga",
+        TENGWA_AHA => "aha",
+        TENGWA_ANCA => "anca",
+        TENGWA_NOLDO => "ñoldo",
+        TENGWA_ANNA => "anna",
+
+        TENGWA_QESSE => "quessë",
+        TENGWA_UNGWE => "ungwë",
+        TENGWA_HWESTA => "hwesta",
+        TENGWA_UNQUE => "unquë",
+        TENGWA_NWALME => "ñwalmë",
+        TENGWA_WILYA => "wilya",
+
+        TENGWA_ROMEN => "rómen",
+        TENGWA_ARDA => "arda",
+        TENGWA_LAMBE => "lambë",
+        TENGWA_ALDA => "alda",
+        TENGWA_SILME => "silmë",
+        TENGWA_SILME_NUQ => "silmë nuquernë",
+        TENGWA_ESSE => "essë",
+        TENGWA_ESSE_NUQ => "essë nuquernë",
+        TENGWA_HYARMEN => "hyarmen",
+        TENGWA_HWESTA_SINDARINWA => "hwesta sindarinwa",
+        TENGWA_YANTA => "yanta",
+        TENGWA_URE => "úrë",
+        TENGWA_TELCO => "telco",
+        TENGWA_ARA => "ára",
+        TENGWA_HALLA => "halla",
+        TENGWA_TELCO_LIG => "telco (ligating)",
+        TENGWA_OSSE => "ossë",
+        TENGWA_OSSE_REV => "ossë (reversed)",
+        TENGWA_MALTA_HOOKED => "malta (hooked)",
+        TENGWA_VALA_HOOKED => "vala (hooked)",
+        _ => return None,
+    })
+}
+
+
 pub const fn width(c: char) -> Option<usize> {
     match c {
         DC_OVER_DOT_3..=DC_UNDER_LINE_V
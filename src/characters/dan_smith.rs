@@ -0,0 +1,297 @@
+//! This module defines the keyboard layout used by the Tengwar fonts of Dan
+//!     Smith, and by many fonts made after them, such as Tengwar Annatar,
+//!     Tengwar Parmaitë and Tengwar Quenya.
+//!
+//! These fonts predate the ConScript Unicode Registry, and draw the Tengwar in
+//!     place of the characters of ASCII and Latin-1. Every tengwa is found on a
+//!     key of its own, but a diacritic does not combine with the tengwa before
+//!     it; Instead, each tehta is found on several keys, each of which draws it
+//!     at a different distance to the left, to suit a different [`Width`] of
+//!     tengwa. Text is rendered in this layout by choosing the key of each
+//!     diacritic from the base character that precedes it.
+//!
+//! The layout has no equivalent for some of the characters in the CSUR block,
+//!     such as the rarer tengwar and punctuation, and these are left unchanged.
+//!     A [zero-width joiner](ZWJ) has no meaning in the layout, and is removed.
+//!
+//! # Example
+//! ```
+//! use tengwar::{characters::{dan_smith, encoding::Encoding}, Quenya, TengwarMode};
+//!
+//! let text: String = Quenya::transcribe("tinco");
+//! let keys: String = dan_smith::encode(&text);
+//!
+//! assert_eq!(keys, "1GfY");
+//! assert_eq!(dan_smith::decode(&keys), Encoding::Csur.recode(&text));
+//! ```
+
+use super::{*, encoding::Encoding};
+
+
+/// The width of a base character, which decides the key used for a diacritic
+///     placed on it. The discriminant of each width is the index of its key in
+///     the result of [`diacritic`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Width {
+    /// A tengwa with two bows, such as Ando or Númen.
+    Wide = 1,
+    /// A tengwa with one bow, such as Tinco or Órë, or a numeral.
+    Narrow = 2,
+    /// A carrier, which is only a stem.
+    Carrier = 3,
+}
+
+impl Width {
+    /// Return the width of a base character.
+    pub const fn of(base: char) -> Self {
+        match base {
+            TENGWA_ANDO..=TENGWA_UNGWE
+            | TENGWA_ANTO..=TENGWA_NWALME
+            | TENGWA_ALDA
+            => Self::Wide,
+
+            TENGWA_TELCO
+            | TENGWA_TELCO_LIG
+            | TENGWA_ARA
+            | TENGWA_HALLA
+            => Self::Carrier,
+
+            _ if TEMA_TINCO.double_ex as u32 <= base as u32
+                && base as u32 <= TEMA_QESSE.double_ex as u32
+            => Self::Wide,
+
+            _ => Self::Narrow,
+        }
+    }
+}
+
+
+/// Return the key of a base character, which does not depend on the
+///     characters around it.
+pub const fn key(c: char) -> Option<char> {
+    Some(match c {
+        TENGWA_TINCO => '1',
+        TENGWA_PARMA => 'q',
+        TENGWA_CALMA => 'a',
+        TENGWA_QESSE => 'z',
+        TENGWA_ANDO => '2',
+        TENGWA_UMBAR => 'w',
+        TENGWA_ANGA => 's',
+        TENGWA_UNGWE => 'x',
+        TENGWA_THULE => '3',
+        TENGWA_FORMEN => 'e',
+        TENGWA_AHA => 'd',
+        TENGWA_HWESTA => 'c',
+        TENGWA_ANTO => '4',
+        TENGWA_AMPA => 'r',
+        TENGWA_ANCA => 'f',
+        TENGWA_UNQUE => 'v',
+        TENGWA_NUMEN => '5',
+        TENGWA_MALTA => 't',
+        TENGWA_NOLDO => 'g',
+        TENGWA_NWALME => 'b',
+        TENGWA_ORE => '6',
+        TENGWA_VALA => 'y',
+        TENGWA_ANNA => 'h',
+        TENGWA_WILYA => 'n',
+
+        c if c == TEMA_TINCO.single_ex => '!',
+        c if c == TEMA_PARMA.single_ex => 'Q',
+        c if c == TEMA_CALMA.single_ex => 'A',
+        c if c == TEMA_QESSE.single_ex => 'Z',
+        c if c == TEMA_TINCO.double_ex => '@',
+        c if c == TEMA_PARMA.double_ex => 'W',
+        c if c == TEMA_CALMA.double_ex => 'S',
+        c if c == TEMA_QESSE.double_ex => 'X',
+
+        TENGWA_ROMEN => '7',
+        TENGWA_ARDA => 'u',
+        TENGWA_LAMBE => 'j',
+        TENGWA_ALDA => 'm',
+        TENGWA_SILME => '8',
+        TENGWA_SILME_NUQ => 'i',
+        TENGWA_ESSE => 'k',
+        TENGWA_ESSE_NUQ => ',',
+        TENGWA_HYARMEN => '9',
+        TENGWA_HWESTA_SINDARINWA => 'o',
+        TENGWA_YANTA => 'l',
+        TENGWA_URE => '.',
+
+        TENGWA_TELCO | TENGWA_TELCO_LIG => '`',
+        TENGWA_ARA => '~',
+        TENGWA_HALLA => '½',
+
+        NUM_0 => 'ð',
+        NUM_1 => 'ñ',
+        NUM_2 => 'ò',
+        NUM_3 => 'ó',
+        NUM_4 => 'ô',
+        NUM_5 => 'õ',
+        NUM_6 => 'ö',
+        NUM_7 => '÷',
+        NUM_8 => 'ø',
+        NUM_9 => 'ù',
+        NUM_A => 'ú',
+        NUM_B => 'û',
+
+        PUNCT_DOT_1 => '=',
+        PUNCT_DOT_2 => '-',
+        PUNCT_EXCLAM => 'Á',
+        PUNCT_INTERR => 'À',
+
+        _ => return None,
+    })
+}
+
+
+/// Return the keys of a diacritic, indexed by [`Width`]. The first key of each
+///     is placed for a tengwa wider than any that are written by this crate,
+///     and is only read by [`decode`].
+///
+/// A diacritic that is drawn at the same place on every tengwa has the same
+///     key for every width.
+pub const fn diacritic(c: char) -> Option<[char; 4]> {
+    Some(match c {
+        DC_OVER_DOT_3 => ['#', 'E', 'D', 'C'],
+        DC_OVER_ACUTE_1 => ['$', 'R', 'F', 'V'],
+        DC_OVER_DOT_1 => ['%', 'T', 'G', 'B'],
+        DC_OVER_HOOK_R_1 => ['^', 'Y', 'H', 'N'],
+        DC_OVER_HOOK_L_1 => ['&', 'U', 'J', 'M'],
+        DC_OVER_WAVE => ['è', 'é', 'ê', 'ë'],
+
+        DC_UNDER_DOT_1 => ['È', 'É', 'Ê', 'Ë'],
+        DC_UNDER_DOT_2 => ['Ì', 'Í', 'Î', 'Ï'],
+
+        DC_OVER_LINE => ['p'; 4],
+        DC_UNDER_LINE_H => [';'; 4],
+        SA_RINCE => ['|'; 4],
+        SA_RINCE_FINAL => ['£'; 4],
+
+        _ => return None,
+    })
+}
+
+
+/// Return the single form of a doubled diacritic, which the layout does not
+///     have, and the number of times it must be written.
+const fn undouble(c: char) -> (char, usize) {
+    match c {
+        DC_OVER_ACUTE_2 => (DC_OVER_ACUTE_1, 2),
+        DC_OVER_HOOK_L_2 => (DC_OVER_HOOK_L_1, 2),
+        DC_OVER_HOOK_R_2 => (DC_OVER_HOOK_R_1, 2),
+        c => (c, 1),
+    }
+}
+
+
+/// Convert a single character from the CSUR block into the layout, without
+///     the context of the base character before it. A diacritic is given the
+///     key for a [narrow](Width::Narrow) tengwa, and a doubled diacritic is
+///     given the key of its single form.
+pub const fn encode_char(c: char) -> char {
+    if let Some(key) = key(c) {
+        key
+    } else if let Some(keys) = diacritic(undouble(c).0) {
+        keys[Width::Narrow as usize]
+    } else {
+        c
+    }
+}
+
+
+/// Convert text written in the Tengwar into the layout. The text may be written
+///     in either block, or in both.
+pub fn encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut width = Width::Narrow;
+
+    for c in text.chars().map(|c| Encoding::Smp.decode(c)) {
+        if let Some(key) = key(c) {
+            width = Width::of(c);
+            out.push(key);
+        } else if c == ZWJ {
+            continue;
+        } else {
+            let (single, count) = undouble(c);
+
+            match diacritic(single) {
+                Some(keys) => for _ in 0..count {
+                    out.push(keys[width as usize]);
+                }
+                None => out.push(c),
+            }
+        }
+    }
+
+    out
+}
+
+
+/// Add the keys of a character from the CSUR block to a table of [`DECODED`],
+///     unless another character has already been given the same key.
+const fn insert(mut table: [Option<char>; 256], t: char) -> [Option<char>; 256] {
+    let mut keys: [Option<char>; 4] = [key(t), None, None, None];
+
+    if let Some([a, b, c, d]) = diacritic(t) {
+        keys = [Some(a), Some(b), Some(c), Some(d)];
+    }
+
+    let mut i = 0;
+
+    while i < keys.len() {
+        if let Some(k) = keys[i] {
+            if (k as usize) < table.len() && table[k as usize].is_none() {
+                table[k as usize] = Some(t);
+            }
+        }
+
+        i += 1;
+    }
+
+    table
+}
+
+
+/// The character of the CSUR block read from each key of the layout, indexed
+///     by the codepoint of the key. Every key is found in ASCII or Latin-1.
+const DECODED: [Option<char>; 256] = {
+    let mut table: [Option<char>; 256] = [None; 256];
+    let Some(block) = Encoding::Csur.block() else { unreachable!() };
+    let mut code: u32 = *block.start() as u32;
+
+    while code <= *block.end() as u32 {
+        if let Some(t) = char::from_u32(code) {
+            table = insert(table, t);
+        }
+
+        code += 1;
+    }
+
+    table = insert(table, PUNCT_DOT_1);
+    insert(table, PUNCT_DOT_2)
+};
+
+
+/// Convert a single character of the layout into the CSUR block. Every key of
+///     a diacritic is read as the same character, and any character that is
+///     not part of the layout is returned unchanged.
+///
+/// The layout draws the Tengwar in place of letters, digits and punctuation,
+///     so the import is lossy: Any of these that were meant as plain text are
+///     converted as well, and only the characters that are not keys, such as
+///     whitespace, are kept.
+pub const fn decode_char(c: char) -> char {
+    if (c as usize) < DECODED.len() {
+        if let Some(t) = DECODED[c as usize] {
+            return t;
+        }
+    }
+
+    c
+}
+
+
+/// Convert text written in the layout into the CSUR block.
+pub fn decode(text: &str) -> String {
+    text.chars().map(decode_char).collect()
+}
@@ -0,0 +1,312 @@
+//! This module defines the [`Encoding`] of the Tengwar in rendered text.
+//!
+//! All of the codepoints in the [`consts`] module belong to the block of the
+//!     ConScript Unicode Registry, in the Private Use Area, which is supported
+//!     by the fonts of the Free Tengwar Font Project. A block of the
+//!     Supplementary Multilingual Plane has been proposed for the Tengwar, but
+//!     it has not yet been approved. Rendered text can be written in either
+//!     block; Each character of the CSUR block is moved to the codepoint given
+//!     for it by [`smp`], and any other character, including the Cirth, is
+//!     left unchanged.
+//!
+//! Rendered text can also be written in the [Dan Smith](super::dan_smith)
+//!     layout, which is used by many older fonts in place of ASCII, and text
+//!     written in any encoding can be imported into the CSUR block.
+//!
+//! The encoding used when rendering [`Token`](crate::Token)s is chosen at
+//!     compile time, by [`Encoding::DEFAULT`]. Text that has already been
+//!     rendered can be converted at runtime, with [`Encoding::recode`] and
+//!     [`Encoding::import`].
+//!
+//! # Example
+//! ```
+//! use tengwar::{characters::{encoding::Encoding, TENGWA_TINCO}, Quenya, TengwarMode};
+//!
+//! assert_eq!(Encoding::Csur.encode(TENGWA_TINCO), '\u{E000}');
+//! assert_eq!(Encoding::Smp.encode(TENGWA_TINCO), '\u{16080}');
+//!
+//! let csur: String = Encoding::Csur.recode(&Quenya::transcribe::<String>("tinco"));
+//! let smp: String = Encoding::Smp.recode(&csur);
+//!
+//! assert_eq!(Encoding::detect(&smp), Some(Encoding::Smp));
+//! assert_eq!(Encoding::Csur.recode(&smp), csur);
+//!
+//! let ascii: String = Encoding::DanSmith.recode(&smp);
+//! assert_eq!(Encoding::DanSmith.import(&ascii), csur);
+//! ```
+
+use std::ops::RangeInclusive;
+use super::{*, dan_smith};
+
+
+/// A block of codepoints in which the Tengwar may be written.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Encoding {
+    /// The block of the ConScript Unicode Registry, in the Private Use Area.
+    #[value(alias = "pua")]
+    Csur,
+    /// The proposed block of the Supplementary Multilingual Plane.
+    #[value(alias = "unicode")]
+    Smp,
+    /// The keyboard layout of the fonts of Dan Smith, in ASCII and Latin-1.
+    #[value(alias = "ds")]
+    DanSmith,
+}
+
+impl Encoding {
+    /// The encoding used when rendering. This is the SMP block if the `smp`
+    ///     feature is enabled, unless the `csur` feature is also enabled, and
+    ///     the CSUR block otherwise.
+    pub const DEFAULT: Self = if cfg!(all(feature = "smp", not(feature = "csur"))) {
+        Self::Smp
+    } else {
+        Self::Csur
+    };
+
+    /// Return the range of codepoints that the Tengwar occupy in this encoding.
+    ///     Returns `None` for the Dan Smith layout, which is not a block.
+    pub const fn block(self) -> Option<RangeInclusive<char>> {
+        match self {
+            Self::Csur => Some('\u{E000}'..='\u{E07F}'),
+            Self::Smp => Some('\u{16080}'..='\u{160FF}'),
+            Self::DanSmith => None,
+        }
+    }
+
+    /// Return the position of a character within this block, if it is in it.
+    const fn offset(self, c: char) -> Option<u32> {
+        let Some(block) = self.block() else { return None; };
+        let (start, end) = (*block.start() as u32, *block.end() as u32);
+        let c = c as u32;
+
+        if start <= c && c <= end { Some(c - start) } else { None }
+    }
+
+    /// Move a character from the CSUR block into this encoding. Any other
+    ///     character is returned unchanged.
+    ///
+    /// In the Dan Smith layout, the key of a diacritic depends on the tengwa
+    ///     before it, which is not known here; See [`dan_smith::encode_char`].
+    pub const fn encode(self, c: char) -> char {
+        match self {
+            Self::Csur => c,
+            Self::Smp => match smp(c) {
+                Some(new) => new,
+                None => c,
+            },
+            Self::DanSmith => dan_smith::encode_char(c),
+        }
+    }
+
+    /// Move a character from this encoding into the CSUR block. Any other
+    ///     character is returned unchanged.
+    pub const fn decode(self, c: char) -> char {
+        match self {
+            Self::Csur => c,
+            Self::Smp => match Self::Smp.offset(c) {
+                Some(offset) => match FROM_SMP[offset as usize] {
+                    Some(old) => old,
+                    None => c,
+                },
+                None => c,
+            },
+            Self::DanSmith => dan_smith::decode_char(c),
+        }
+    }
+
+    /// Convert text containing the Tengwar, in either block, into this
+    ///     encoding.
+    pub fn recode(self, text: &str) -> String {
+        match self {
+            Self::DanSmith => dan_smith::encode(text),
+            _ => text.chars().map(|c| self.encode(Self::Smp.decode(c))).collect(),
+        }
+    }
+
+    /// Convert text written in this encoding into the CSUR block. Unlike the
+    ///     blocks, text in the Dan Smith layout cannot be detected, so it must
+    ///     be imported before it can be recoded.
+    pub fn import(self, text: &str) -> String {
+        text.chars().map(|c| self.decode(c)).collect()
+    }
+
+    /// Find the block of the first character of the Tengwar in some text.
+    ///     Returns `None` if there are none.
+    pub fn detect(text: &str) -> Option<Self> {
+        text.chars().find_map(|c| [Self::Csur, Self::Smp]
+            .into_iter()
+            .find(|encoding| encoding.offset(c).is_some()))
+    }
+}
+
+impl Default for Encoding {
+    fn default() -> Self { Self::DEFAULT }
+}
+
+
+/// Return the codepoint proposed for a character of the CSUR block, in the
+///     block of the SMP. Returns `None` for any character that has none.
+pub const fn smp(c: char) -> Option<char> {
+    //  The dots of punctuation may be taken from outside the CSUR block.
+    if Encoding::Csur.offset(c).is_none() {
+        return None;
+    }
+
+    Some(match c {
+        TENGWA_TINCO => '\u{16080}',
+        TENGWA_PARMA => '\u{16081}',
+        TENGWA_CALMA => '\u{16082}',
+        TENGWA_QESSE => '\u{16083}',
+        TENGWA_ANDO => '\u{16084}',
+        TENGWA_UMBAR => '\u{16085}',
+        TENGWA_ANGA => '\u{16086}',
+        TENGWA_UNGWE => '\u{16087}',
+        TENGWA_THULE => '\u{16088}',
+        TENGWA_FORMEN => '\u{16089}',
+        TENGWA_AHA => '\u{1608A}',
+        TENGWA_HWESTA => '\u{1608B}',
+        TENGWA_ANTO => '\u{1608C}',
+        TENGWA_AMPA => '\u{1608D}',
+        TENGWA_ANCA => '\u{1608E}',
+        TENGWA_UNQUE => '\u{1608F}',
+        TENGWA_NUMEN => '\u{16090}',
+        TENGWA_MALTA => '\u{16091}',
+        TENGWA_NOLDO => '\u{16092}',
+        TENGWA_NWALME => '\u{16093}',
+        TENGWA_ORE => '\u{16094}',
+        TENGWA_VALA => '\u{16095}',
+        TENGWA_ANNA => '\u{16096}',
+        TENGWA_WILYA => '\u{16097}',
+
+        c if c == TEMA_TINCO.single_ex => '\u{16098}',
+        c if c == TEMA_PARMA.single_ex => '\u{16099}',
+        c if c == TEMA_CALMA.single_ex => '\u{1609A}',
+        c if c == TEMA_QESSE.single_ex => '\u{1609B}',
+        c if c == TEMA_TINCO.double_ex => '\u{1609C}',
+        c if c == TEMA_PARMA.double_ex => '\u{1609D}',
+        c if c == TEMA_CALMA.double_ex => '\u{1609E}',
+        c if c == TEMA_QESSE.double_ex => '\u{1609F}',
+
+        TENGWA_ROMEN => '\u{160A0}',
+        TENGWA_ARDA => '\u{160A1}',
+        TENGWA_LAMBE => '\u{160A2}',
+        TENGWA_ALDA => '\u{160A3}',
+        TENGWA_SILME => '\u{160A4}',
+        TENGWA_SILME_NUQ => '\u{160A5}',
+        TENGWA_ESSE => '\u{160A6}',
+        TENGWA_ESSE_NUQ => '\u{160A7}',
+        TENGWA_HYARMEN => '\u{160A8}',
+        TENGWA_HWESTA_SINDARINWA => '\u{160A9}',
+        TENGWA_YANTA => '\u{160AA}',
+        TENGWA_URE => '\u{160AB}',
+        TENGWA_ARA => '\u{160AC}',
+        TENGWA_HALLA => '\u{160AD}',
+        TENGWA_TELCO => '\u{160AE}',
+
+        TENGWA_OSSE_REV => '\u{160B0}',
+        TENGWA_BOMBADIL_W => '\u{160B1}',
+        TENGWA_OSSE => '\u{160B2}',
+        TENGWA_TELCO_LIG => '\u{160B4}',
+        TENGWA_ANNA_OPEN => '\u{160B6}',
+        TENGWA_CHRISTOPHER_QU => '\u{160B7}',
+        TENGWA_BOMBADIL_HW => '\u{160B9}',
+        TENGWA_MALTA_HOOKED => '\u{160BA}',
+        TENGWA_VALA_HOOKED => '\u{160BB}',
+        TENGWA_LOWDHAM_HW => '\u{160BC}',
+        TENGWA_WAIA => '\u{160BD}',
+
+        DC_OVER_DOT_3 => '\u{160C0}',
+        DC_UNDER_DOT_3 => '\u{160C1}',
+        DC_OVER_DOT_2 => '\u{160C2}',
+        DC_UNDER_DOT_2 => '\u{160C3}',
+        DC_OVER_DOT_1 => '\u{160C4}',
+        DC_UNDER_DOT_1 => '\u{160C5}',
+        DC_OVER_ACUTE_1 => '\u{160C6}',
+        DC_UNDER_ACUTE_1 => '\u{160C7}',
+        DC_OVER_ACUTE_2 => '\u{160C8}',
+        DC_UNDER_ACUTE_2 => '\u{160C9}',
+        DC_OVER_HOOK_R_1 => '\u{160CA}',
+        DC_UNDER_HOOK_R_1 => '\u{160CB}',
+        DC_OVER_HOOK_L_1 => '\u{160CC}',
+        DC_UNDER_HOOK_L_1 => '\u{160CD}',
+        DC_OVER_HOOK_R_2 => '\u{160CE}',
+        DC_OVER_HOOK_L_2 => '\u{160CF}',
+        DC_OVER_LINE => '\u{160D0}',
+        DC_UNDER_LINE_H => '\u{160D1}',
+        DC_OVER_WAVE => '\u{160D2}',
+        DC_OVER_BREVE => '\u{160D3}',
+        DC_OVER_GRAVE => '\u{160D4}',
+        DC_OVER_CIRCUMFLEX => '\u{160D5}',
+        DC_OVER_DOT_3_INV => '\u{160D6}',
+        DC_UNDER_LINE_V => '\u{160D7}',
+        SA_RINCE_FINAL => '\u{160D8}',
+        SA_RINCE => '\u{160D9}',
+        DC_INNER_DOT_1 => '\u{160DA}',
+
+        PUNCT_DOT_1 => '\u{160E0}',
+        PUNCT_DOT_2 => '\u{160E1}',
+        PUNCT_DOT_3 => '\u{160E2}',
+        PUNCT_DOT_4 => '\u{160E3}',
+        PUNCT_DOT_5 => '\u{160E4}',
+        PUNCT_EXCLAM => '\u{160E5}',
+        PUNCT_INTERR => '\u{160E6}',
+        PUNCT_PAREN => '\u{160E7}',
+        PUNCT_LINE_1 => '\u{160E8}',
+        PUNCT_LINE_2 => '\u{160E9}',
+        PUNCT_PAREN_L => '\u{160EA}',
+        PUNCT_PAREN_R => '\u{160EB}',
+        PUNCT_THORIN => '\u{160EC}',
+
+        NUM_0 => '\u{160F0}',
+        NUM_1 => '\u{160F1}',
+        NUM_2 => '\u{160F2}',
+        NUM_3 => '\u{160F3}',
+        NUM_4 => '\u{160F4}',
+        NUM_5 => '\u{160F5}',
+        NUM_6 => '\u{160F6}',
+        NUM_7 => '\u{160F7}',
+        NUM_8 => '\u{160F8}',
+        NUM_9 => '\u{160F9}',
+        NUM_A => '\u{160FA}',
+        NUM_B => '\u{160FB}',
+        NUM_C => '\u{160FC}',
+        DC_UNDER_RING => '\u{160FD}',
+
+        _ => return None,
+    })
+}
+
+
+/// The character of the CSUR block moved to each codepoint of the SMP block by
+///     [`smp`], indexed by its position within the block.
+const FROM_SMP: [Option<char>; 128] = {
+    let mut table: [Option<char>; 128] = [None; 128];
+    let mut code: u32 = 0xE000;
+
+    while code <= 0xE07F {
+        if let Some(c) = char::from_u32(code) {
+            if let Some(new) = smp(c) {
+                if let Some(offset) = Encoding::Smp.offset(new) {
+                    table[offset as usize] = Some(c);
+                }
+            }
+        }
+
+        code += 1;
+    }
+
+    table
+};
+
+
+/// An extension of [`Write`](std::fmt::Write), for writing the Tengwar in the
+///     [default encoding](Encoding::DEFAULT).
+pub(crate) trait WriteEncoded: std::fmt::Write {
+    /// Write a character from the CSUR block, in the default encoding.
+    fn write_encoded(&mut self, c: char) -> std::fmt::Result {
+        self.write_char(Encoding::DEFAULT.encode(c))
+    }
+}
+
+impl<W: std::fmt::Write + ?Sized> WriteEncoded for W {}
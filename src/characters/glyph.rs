@@ -1,6 +1,6 @@
-use std::{fmt::{Display, Formatter, Write}, marker::PhantomData};
+use std::{fmt::{Display, Formatter}, marker::PhantomData};
 use crate::policy::{Policy, Standard};
-use super::*;
+use super::{*, encoding::WriteEncoded};
 
 
 /// An optional base [`Tengwa`] paired with an optional diacritical tehta.
@@ -84,10 +84,10 @@ impl TehtaChar {
 
 fn write_tehta(f: &mut Formatter<'_>, tehta: char, double: bool) -> std::fmt::Result {
     if double {
-        f.write_char(tehta)?;
-        f.write_char(tehta)?;
+        f.write_encoded(tehta)?;
+        f.write_encoded(tehta)?;
     } else {
-        f.write_char(tehta)?;
+        f.write_encoded(tehta)?;
     }
 
     Ok(())
@@ -102,6 +102,9 @@ fn write_tehta(f: &mut Formatter<'_>, tehta: char, double: bool) -> std::fmt::Re
 pub struct Glyph<P: Policy = Standard> {
     /// A base character.
     pub base: Option<char>,
+    /// A base character to be used instead, if the glyph is found to be at the
+    ///     end of a word when it is finalized by its mode.
+    pub base_final: Option<char>,
     /// The primary diacritical marking over the base character.
     pub tehta: Option<Tehta>,
     /// Indicates whether the [`Tehta`] should use its alternate "long" form.
@@ -151,6 +154,14 @@ pub struct Glyph<P: Policy = Standard> {
 
     /// Phantom field to carry the [`Policy`] parameter.
     pub _p: PhantomData<P>,
+
+    /// A value of the [`Policy`], whose rules are followed instead of those of
+    ///     its type, if one is set. A [`Tokenizer`] that is given a value of
+    ///     its policy sets it on every glyph.
+    ///
+    /// [`Tokenizer`]: crate::mode::Tokenizer
+    #[cfg_attr(feature = "serde", serde(skip, default = "Option::default"))]
+    pub policy: Option<P>,
 }
 
 /// Public: Construction and modification.
@@ -159,6 +170,7 @@ impl<P: Policy> Glyph<P> {
     pub const fn new() -> Self {
         Self {
             base: None,
+            base_final: None,
             tehta: None,
             tehta_alt: false,
             tehta_first: false,
@@ -179,6 +191,7 @@ impl<P: Policy> Glyph<P> {
             ligate_short: false,
             ligate_zwj: 0,
             _p: PhantomData,
+            policy: None,
         }
     }
 
@@ -212,6 +225,7 @@ impl<P: Policy> Glyph<P> {
     pub const fn change_policy<Q: Policy>(&self) -> Glyph<Q> {
         Glyph {
             base: self.base,
+            base_final: self.base_final,
             tehta: self.tehta,
             tehta_alt: self.tehta_alt,
             tehta_first: self.tehta_first,
@@ -229,15 +243,28 @@ impl<P: Policy> Glyph<P> {
             ligate_short: self.ligate_short,
             ligate_zwj: self.ligate_zwj,
             _p: PhantomData,
+            policy: None,
         }
     }
 
+    /// Switch the glyph to use a different [`Policy`] implementor, following
+    ///     the rules of a value of it, if one is given.
+    pub const fn with_policy<Q: Policy>(&self, policy: Option<Q>) -> Glyph<Q> {
+        Glyph { policy, ..self.change_policy() }
+    }
+
     /// Change the base [`char`].
     pub const fn with_tengwa(mut self, tengwa: char) -> Self {
         self.base = Some(tengwa);
         self
     }
 
+    /// Change the base [`char`] used at the end of a word.
+    pub const fn with_base_final(mut self, tengwa: char) -> Self {
+        self.base_final = Some(tengwa);
+        self
+    }
+
     /// Change the [`Tehta`] to be used.
     pub const fn with_tehta(mut self, tehta: Tehta) -> Self {
         self.tehta = Some(tehta);
@@ -283,9 +310,16 @@ impl<P: Policy> Glyph<P> {
         self
     }
 
+    /// Mark this glyph with a dot below it.
+    pub const fn with_dot_under(mut self, enabled: bool) -> Self {
+        self.dot_under = enabled;
+        self
+    }
+
     /// Update this glyph with the consonant attributes of another glyph.
     pub fn integrate_consonant(&mut self, other: Self) {
         self.base = other.base;
+        self.base_final = other.base_final;
         self.rince = other.rince;
         self.nasal = other.nasal;
         self.labial = other.labial;
@@ -309,6 +343,17 @@ impl<P: Policy> Glyph<P> {
         }
     }
 
+    /// Decide the base [`char`], once it is known whether the glyph is at the
+    ///     end of a word. If it is, the [final base](Self::base_final) is used.
+    ///     Either way, the final base is cleared.
+    pub fn decide_final(&mut self, word_final: bool) {
+        if let Some(base) = self.base_final.take() {
+            if word_final {
+                self.base = Some(base);
+            }
+        }
+    }
+
     /// If the [`Tehta`] matches a specific value, change it to another.
     pub fn replace_tehta(&mut self, old: Tehta, new: Tehta) -> bool {
         if self.tehta == Some(old) {
@@ -330,16 +375,16 @@ impl<P: Policy> Glyph<P> {
     /// Determine the base character to be used for this glyph. If one is not
     ///     set, an appropriate "carrier" mark will be returned instead.
     pub fn base_nuq(&self) -> char {
-        match self {
-            &Glyph {
+        match *self {
+            Glyph {
                 base: Some(base), // Has a tengwa.
                 tehta: Some(_), // Has a tehta.
                 tehta_alt: false, // Will use the BASE form of the tehta.
                 nuquerna: true, // Is set to use a Nuquerna.
                 ..
-            } => P::nuquerna(base),
+            } => self.rule(|p| p.nuquerna_in(base), || P::nuquerna(base)),
 
-            &Glyph {
+            Glyph {
                 base: Some(base), // Has a tengwa.
                 tehta: Some(tehta), // Has a tehta.
                 tehta_alt: true, // Will use the ALTERNATE form of the tehta.
@@ -347,7 +392,7 @@ impl<P: Policy> Glyph<P> {
                 vowels, ..
             } if vowels.allow_long_above() // Alt tehta is allowed above tengwa.
                 && !tehta.needs_ara() // This alt tehta does not require Ára.
-                => P::nuquerna(base),
+                => self.rule(|p| p.nuquerna_in(base), || P::nuquerna(base)),
 
             _ => self.base(),
         }
@@ -358,9 +403,9 @@ impl<P: Policy> Glyph<P> {
     ///
     /// This method does not apply a Nuquerna variant.
     pub const fn base(&self) -> char {
-        match self {
-            &Glyph { base: Some(base), .. } => base,
-            &Glyph { base: None, tehta_alt, ligate_short, .. } => {
+        match *self {
+            Glyph { base: Some(base), .. } => base,
+            Glyph { base: None, tehta_alt, ligate_short, .. } => {
                 if tehta_alt {
                     CARRIER_LONG
                 } else if ligate_short {
@@ -413,14 +458,15 @@ impl<P: Policy> Glyph<P> {
     /// Determine whether a rincë may be added to this glyph. Returns `false` if
     ///     a rincë is already set.
     pub fn can_take_rince(&self) -> bool {
-        !self.rince && P::rince_valid(self.base())
+        let base: char = self.base();
+        !self.rince && self.rule(|p| p.rince_valid_in(base), || P::rince_valid(base))
     }
 
     /// Determine whether the base character has a nuquerna variant, but is set
     ///     to not use it.
     pub fn ignoring_nuquerna(&self) -> bool {
         match self.base {
-            Some(base) if !self.nuquerna => P::nuquerna_valid(base),
+            Some(base) if !self.nuquerna => self.nuquerna_valid(base),
             _ => false,
         }
     }
@@ -442,24 +488,29 @@ impl<P: Policy> Glyph<P> {
     /// Determine whether the base [`char`] of this glyph is permitted to ligate
     ///     with another glyph using a zero-width joiner.
     pub fn ligates_with<Q: Policy>(&self, other: &Glyph<Q>) -> bool {
-        P::ligature_valid(self, other, self.ligate_zwj)
+        self.rule(
+            |p| p.ligature_valid_in(self, other, self.ligate_zwj),
+            || P::ligature_valid(self, other, self.ligate_zwj),
+        )
     }
 
     /// Determine whether the base [`char`] of this glyph is permitted to ligate
     ///     with [Ára](TENGWA_ARA) using a zero-width joiner.
     pub fn ligates_with_ara(&self) -> bool {
-        P::ligates_with_ara(self.base())
+        self.base_ligates_with_ara(self.base())
     }
 
     /// Determine whether [Telco](TENGWA_TELCO) is permitted to ligate with the
     ///     base [`char`] of this glyph using a zero-width joiner.
     pub fn telco_ligates(&self) -> bool {
-        P::telco_ligates_with(self.base())
+        let base: char = self.base();
+        self.rule(|p| p.telco_ligates_with_in(base), || P::telco_ligates_with(base))
     }
 
     /// Choose the correct form of Sa-Rincë.
     pub fn choose_rince(&self) -> Rince {
-        P::rince(self.base(), self.rince_final)
+        let base: char = self.base();
+        self.rule(|p| p.rince_in(base, self.rince_final), || P::rince(base, self.rince_final))
     }
 
     pub fn carries_tehta(&self) -> bool {
@@ -507,7 +558,7 @@ impl<P: Policy> Glyph<P> {
 
         //  If the base tengwa has a Nuquerna variant, but it is not going to be
         //      used, the standard form cannot hold a double or alternate tehta.
-        let nuq_ignored: bool = !self.nuquerna && P::nuquerna_valid(tengwa);
+        let nuq_ignored: bool = !self.nuquerna && self.nuquerna_valid(tengwa);
         let cannot_hold: bool = self.tehta_alt && nuq_ignored;
 
         if cannot_hold || needs_ara {
@@ -533,21 +584,38 @@ impl<P: Policy> Glyph<P> {
 
 /// Private: Helper methods.
 impl<P: Policy> Glyph<P> {
+    /// Follow a rule of the [`Policy`], under the value carried by this glyph
+    ///     if there is one, or by its type otherwise.
+    fn rule<T>(&self, value: impl FnOnce(&P) -> T, default: impl FnOnce() -> T) -> T {
+        match &self.policy {
+            Some(policy) => value(policy),
+            None => default(),
+        }
+    }
+
+    fn base_ligates_with_ara(&self, base: char) -> bool {
+        self.rule(|p| p.ligates_with_ara_in(base), || P::ligates_with_ara(base))
+    }
+
+    fn nuquerna_valid(&self, base: char) -> bool {
+        self.rule(|p| p.nuquerna_valid_in(base), || P::nuquerna_valid(base))
+    }
+
     fn write_mods(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.nasal { f.write_char(MOD_NASAL)?; }
-        if self.long_cons { f.write_char(MOD_LONG_CONS)?; }
-        if self.labial { f.write_char(MOD_LABIAL)?; }
-        if self.palatal { f.write_char(MOD_PALATAL)?; }
-        if self.dot_inner { f.write_char(DC_INNER_DOT_1)?; }
-        if self.dot_under { f.write_char(DC_UNDER_DOT_1)?; }
+        if self.nasal { f.write_encoded(MOD_NASAL)?; }
+        if self.long_cons { f.write_encoded(MOD_LONG_CONS)?; }
+        if self.labial { f.write_encoded(MOD_LABIAL)?; }
+        if self.palatal { f.write_encoded(MOD_PALATAL)?; }
+        if self.dot_inner { f.write_encoded(DC_INNER_DOT_1)?; }
+        if self.dot_under { f.write_encoded(DC_UNDER_DOT_1)?; }
         Ok(())
     }
 
     fn write_rince(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         if self.rince {
             match self.choose_rince() {
-                Rince::Basic => f.write_char(SA_RINCE),
-                Rince::Final => f.write_char(SA_RINCE_FINAL),
+                Rince::Basic => f.write_encoded(SA_RINCE),
+                Rince::Final => f.write_encoded(SA_RINCE_FINAL),
             }
         } else {
             Ok(())
@@ -556,7 +624,7 @@ impl<P: Policy> Glyph<P> {
 
     fn write_rince_nonfinal(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         if self.rince {
-            f.write_char(SA_RINCE)
+            f.write_encoded(SA_RINCE)
         } else {
             Ok(())
         }
@@ -575,12 +643,12 @@ impl<P: Policy> Glyph<P> {
         if self.rince {
             match self.choose_rince() {
                 Rince::Basic => {
-                    f.write_char(SA_RINCE)?;
+                    f.write_encoded(SA_RINCE)?;
                     if !self.tehta_hidden { write_tehta(f, tehta, double)?; }
                 }
                 Rince::Final => {
                     if !self.tehta_hidden { write_tehta(f, tehta, double)?; }
-                    f.write_char(SA_RINCE_FINAL)?;
+                    f.write_encoded(SA_RINCE_FINAL)?;
                 }
             }
         } else {
@@ -597,37 +665,37 @@ impl<P: Policy> Display for Glyph<P> {
 
         match self.tehta_char() {
             Some(TehtaChar::OnAraAfter(tehta)) => {
-                f.write_char(base)?;
+                f.write_encoded(base)?;
                 self.write_mods(f)?;
                 self.write_rince_nonfinal(f)?;
 
-                if 0 < self.ligate_zwj && P::ligates_with_ara(base) {
-                    f.write_char(ZWJ)?;
+                if 0 < self.ligate_zwj && self.base_ligates_with_ara(base) {
+                    f.write_encoded(ZWJ)?;
                 }
 
-                f.write_char(CARRIER_LONG)?;
-                if !self.tehta_hidden { f.write_char(tehta)?; }
+                f.write_encoded(CARRIER_LONG)?;
+                if !self.tehta_hidden { f.write_encoded(tehta)?; }
             }
             Some(TehtaChar::OnAraBefore(tehta)) => {
-                f.write_char(CARRIER_LONG)?;
-                if !self.tehta_hidden { f.write_char(tehta)?; }
+                f.write_encoded(CARRIER_LONG)?;
+                if !self.tehta_hidden { f.write_encoded(tehta)?; }
 
-                f.write_char(base)?;
+                f.write_encoded(base)?;
                 self.write_mods(f)?;
                 self.write_rince(f)?;
             }
             Some(TehtaChar::OnTengwaOnce(tehta)) => {
-                f.write_char(base)?;
+                f.write_encoded(base)?;
                 self.write_mods(f)?;
                 self.write_rince_tehta(f, tehta, false)?;
             }
             Some(TehtaChar::OnTengwaTwice(tehta)) => {
-                f.write_char(base)?;
+                f.write_encoded(base)?;
                 self.write_mods(f)?;
                 self.write_rince_tehta(f, tehta, true)?;
             }
             None => {
-                f.write_char(base)?;
+                f.write_encoded(base)?;
                 self.write_mods(f)?;
                 self.write_rince(f)?;
             }
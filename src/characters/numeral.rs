@@ -1,5 +1,5 @@
 use std::fmt::{Display, Formatter};
-use super::consts::*;
+use super::{consts::*, encoding::Encoding};
 
 
 pub const BASE_10_DOT: char = DC_OVER_DOT_1;
@@ -186,7 +186,7 @@ impl Numeral {
             _ => false,
         };
 
-        let neg: bool = 0 < slice.len() && slice[0] == '-';
+        let neg: bool = !slice.is_empty() && slice[0] == '-';
         let end: usize = neg as usize
             + slice.iter()
             .skip(neg as usize)
@@ -337,6 +337,7 @@ impl Display for Numeral {
 
         Ok(())*/
 
+        let text: String = text.chars().map(|c| Encoding::DEFAULT.encode(c)).collect();
         Display::fmt(text.as_str(), f)
     }
 }
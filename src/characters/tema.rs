@@ -52,12 +52,12 @@ impl Tema {
     }
 
     /// Return the [`TengwaRegular`] in this Téma with the default [`Tyelle`].
-    pub const fn new_tengwa(&self) -> TengwaRegular {
+    pub const fn new_tengwa(&self) -> TengwaRegular<'_> {
         TengwaRegular::new(self)
     }
 
     /// Return the [`TengwaRegular`] in this Téma at a specified [`Tyelle`].
-    pub const fn get_tengwa(&self, tyelle: Tyelle) -> TengwaRegular {
+    pub const fn get_tengwa(&self, tyelle: Tyelle) -> TengwaRegular<'_> {
         TengwaRegular { tema: self, tyelle }
     }
 
@@ -0,0 +1,67 @@
+//! This module defines the Cirth, the runic alphabet of the Elves, which was
+//!     later taken up by the Dwarves.
+//!
+//! The Cirth are a much simpler system than the Tengwar, being a true alphabet,
+//!     in which every sound is written with its own rune, or "certh". The
+//!     [`Rune`] type plays the role of a [`Glyph`](crate::Glyph), and is
+//!     carried by the [`Token::Rune`] variant.
+//!
+//! Modes of the Cirth implement the same [`TengwarMode`] trait as the modes of
+//!     the Tengwar, so they can be used with the same [`Tokenizer`] and
+//!     [`Transcriber`](crate::Transcriber). There are two modes implemented:
+//!     The [`Daeron`] mode used by the Elves, and the [`Moria`] mode used by
+//!     the Dwarves.
+//!
+//! [`TengwarMode`]: crate::TengwarMode
+//! [`Tokenizer`]: crate::mode::Tokenizer
+//!
+//! # Example
+//! ```
+//! use tengwar::{cirth::{consts::*, Moria}, TengwarMode};
+//!
+//! let runes: String = Moria::transcribe("balin");
+//! assert_eq!(runes, [CERTH_02, CERTH_48, CERTH_31, CERTH_39, CERTH_22]
+//!     .into_iter().collect::<String>());
+//! ```
+
+pub mod consts;
+pub mod daeron;
+pub mod moria;
+pub mod rune;
+
+mod tests;
+
+pub use daeron::Daeron;
+pub use moria::Moria;
+pub use rune::Rune;
+
+use crate::{characters::{ESC, ESC_NOP}, mode::ParseAction, policy::Policy, Token};
+
+
+/// Process a slice of [`char`]s according to the rules shared by all modes of
+///     the Cirth, using a given function to find runes. Returns a
+///     [`ParseAction`], in the same way as [`TengwarMode::process`].
+///
+/// [`TengwarMode::process`]: crate::TengwarMode::process
+pub fn process_runes<P: Policy>(
+    current: &mut Option<Rune>,
+    chunk: &[char],
+    get_rune: fn(&[char]) -> Option<Rune>,
+) -> ParseAction<P> {
+    if let [ESC, ESC_NOP, ..] = chunk {
+        ParseAction::matched_opt(current.take().map(Token::Rune), 2)
+    } else if let [ESC, _, ..] = chunk {
+        ParseAction::ESC_BACKSLASH
+    } else if let Some(rune) = current.take() {
+        //  A second rune is waiting to be output.
+        ParseAction::MatchedToken { token: Token::Rune(rune), len: 0 }
+    } else if let ['x'] = chunk {
+        //  X is written as KS.
+        *current = get_rune(&['s']);
+        ParseAction::matched_opt(get_rune(&['k']).map(Token::Rune), 1)
+    } else if let Some(rune) = get_rune(chunk) {
+        ParseAction::MatchedToken { token: Token::Rune(rune), len: chunk.len() }
+    } else {
+        ParseAction::MatchedNone
+    }
+}
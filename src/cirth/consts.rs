@@ -0,0 +1,104 @@
+//! This module defines all mappings to Unicode codepoints used for output of
+//!     the Cirth. Codepoints follow the Cirth block of the ConScript Unicode
+//!     Registry, in which each certh is placed according to its number in the
+//!     Angerthas, as given in Appendix E of The Lord of the Rings.
+
+
+pub const CERTH_01: char = ''; // p
+pub const CERTH_02: char = ''; // b
+pub const CERTH_03: char = ''; // f
+pub const CERTH_04: char = ''; // v
+pub const CERTH_05: char = ''; // hw
+pub const CERTH_06: char = ''; // m
+pub const CERTH_07: char = ''; // mb
+pub const CERTH_08: char = ''; // t
+pub const CERTH_09: char = ''; // d
+pub const CERTH_10: char = ''; // th
+pub const CERTH_11: char = ''; // dh
+pub const CERTH_12: char = ''; // n
+pub const CERTH_13: char = ''; // ch
+pub const CERTH_14: char = ''; // j
+pub const CERTH_15: char = ''; // sh
+pub const CERTH_16: char = ''; // zh
+pub const CERTH_17: char = ''; // nj
+pub const CERTH_18: char = ''; // k
+pub const CERTH_19: char = ''; // g
+pub const CERTH_20: char = ''; // kh
+pub const CERTH_21: char = ''; // gh
+pub const CERTH_22: char = ''; // ŋ
+pub const CERTH_23: char = ''; // kw
+pub const CERTH_24: char = ''; // gw
+pub const CERTH_25: char = ''; // khw
+pub const CERTH_26: char = ''; // ghw, w
+pub const CERTH_27: char = ''; // ngw
+pub const CERTH_28: char = ''; // nw
+pub const CERTH_29: char = ''; // r
+pub const CERTH_30: char = ''; // rh
+pub const CERTH_31: char = ''; // l
+pub const CERTH_32: char = ''; // lh
+pub const CERTH_33: char = ''; // ng
+pub const CERTH_34: char = ''; // s
+pub const CERTH_35: char = ''; // s
+pub const CERTH_36: char = ''; // z
+pub const CERTH_37: char = ''; // ŋ
+pub const CERTH_38: char = ''; // nd, n
+pub const CERTH_39: char = ''; // i
+pub const CERTH_40: char = ''; // y
+pub const CERTH_41: char = ''; // hy
+pub const CERTH_42: char = ''; // u
+pub const CERTH_43: char = ''; // z
+pub const CERTH_44: char = ''; // w
+pub const CERTH_45: char = ''; // ü
+pub const CERTH_46: char = ''; // e
+pub const CERTH_47: char = ''; // ē
+pub const CERTH_48: char = ''; // a
+pub const CERTH_49: char = ''; // ā
+pub const CERTH_50: char = ''; // o
+pub const CERTH_51: char = ''; // ō
+pub const CERTH_52: char = ''; // ö
+pub const CERTH_53: char = ''; // n
+pub const CERTH_54: char = ''; // h, s
+pub const CERTH_55: char = ''; // ə
+pub const CERTH_56: char = ''; // ə
+pub const CERTH_57: char = ''; // ps
+pub const CERTH_58: char = ''; // ts
+pub const CERTH_59: char = ''; // +h
+pub const CERTH_60: char = ''; // &
+
+
+/// Index in this array corresponds to the number of the certh in the Angerthas,
+///     minus one.
+pub const CIRTH: [char; 60] = [
+    CERTH_01, CERTH_02, CERTH_03, CERTH_04, CERTH_05, CERTH_06,
+    CERTH_07, CERTH_08, CERTH_09, CERTH_10, CERTH_11, CERTH_12,
+    CERTH_13, CERTH_14, CERTH_15, CERTH_16, CERTH_17, CERTH_18,
+    CERTH_19, CERTH_20, CERTH_21, CERTH_22, CERTH_23, CERTH_24,
+    CERTH_25, CERTH_26, CERTH_27, CERTH_28, CERTH_29, CERTH_30,
+    CERTH_31, CERTH_32, CERTH_33, CERTH_34, CERTH_35, CERTH_36,
+    CERTH_37, CERTH_38, CERTH_39, CERTH_40, CERTH_41, CERTH_42,
+    CERTH_43, CERTH_44, CERTH_45, CERTH_46, CERTH_47, CERTH_48,
+    CERTH_49, CERTH_50, CERTH_51, CERTH_52, CERTH_53, CERTH_54,
+    CERTH_55, CERTH_56, CERTH_57, CERTH_58, CERTH_59, CERTH_60,
+];
+
+
+/// Return the certh with a given number in the Angerthas, if there is one.
+pub const fn certh(number: usize) -> Option<char> {
+    match number.checked_sub(1) {
+        Some(idx) if idx < CIRTH.len() => Some(CIRTH[idx]),
+        _ => None,
+    }
+}
+
+
+/// Return the number in the Angerthas of a certh, if it is one.
+pub const fn certh_number(chr: char) -> Option<usize> {
+    let first: u32 = CERTH_01 as u32;
+    let code: u32 = chr as u32;
+
+    if first <= code && code < first + CIRTH.len() as u32 {
+        Some((code - first) as usize + 1)
+    } else {
+        None
+    }
+}
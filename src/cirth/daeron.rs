@@ -0,0 +1,125 @@
+use crate::{mode::{ParseAction, TengwarMode}, policy::Policy, Token};
+use super::{consts::*, process_runes, Rune};
+
+
+pub const fn certh_char(slice: &[char]) -> Option<char> {
+    Some(match slice {
+        //  Labials.
+        ['p']           /**/ => CERTH_01,
+        ['b']           /**/ => CERTH_02,
+        ['f'] | ['φ']
+        | ['p', 'h']    /**/ => CERTH_03,
+        ['v']           /**/ => CERTH_04,
+        ['h', 'w']      /**/ => CERTH_05,
+        ['m']           /**/ => CERTH_06,
+        ['m', 'b']
+        | ['m', 'h']    /**/ => CERTH_07,
+
+        //  Dentals.
+        ['t']           /**/ => CERTH_08,
+        ['d']           /**/ => CERTH_09,
+        ['t', 'h']
+        | ['þ'] | ['θ'] /**/ => CERTH_10,
+        ['d', 'h']
+        | ['ð']         /**/ => CERTH_11,
+        ['n']           /**/ => CERTH_12,
+
+        //  Palatals.
+        ['c', 'h']      /**/ => CERTH_13,
+        ['j']           /**/ => CERTH_14,
+        ['s', 'h']      /**/ => CERTH_15,
+        ['z', 'h']      /**/ => CERTH_16,
+        ['n', 'j']      /**/ => CERTH_17,
+
+        //  Velars.
+        ['k'] | ['c']   /**/ => CERTH_18,
+        ['g']           /**/ => CERTH_19,
+        ['k', 'h']      /**/ => CERTH_20,
+        ['g', 'h']      /**/ => CERTH_21,
+        ['ñ'] | ['ŋ']   /**/ => CERTH_22,
+
+        //  Labialized velars.
+        ['k', 'w']
+        | ['q', 'u']
+        | ['q']         /**/ => CERTH_23,
+        ['g', 'w']      /**/ => CERTH_24,
+        ['k', 'h', 'w'] /**/ => CERTH_25,
+        ['g', 'h', 'w'] /**/ => CERTH_26,
+        ['n', 'g', 'w'] /**/ => CERTH_27,
+        ['n', 'w']      /**/ => CERTH_28,
+
+        //  Liquids and sibilants.
+        ['r']           /**/ => CERTH_29,
+        ['r', 'h']      /**/ => CERTH_30,
+        ['l']           /**/ => CERTH_31,
+        ['l', 'h']      /**/ => CERTH_32,
+        ['n', 'g']      /**/ => CERTH_33,
+        ['s']           /**/ => CERTH_34,
+        ['z']           /**/ => CERTH_36,
+        ['n', 'd']      /**/ => CERTH_38,
+
+        //  Vowels and semivowels.
+        ['i']           /**/ => CERTH_39,
+        ['y']           /**/ => CERTH_40,
+        ['h', 'y']      /**/ => CERTH_41,
+        ['u']           /**/ => CERTH_42,
+        ['w']           /**/ => CERTH_44,
+        ['ü']           /**/ => CERTH_45,
+        ['e']           /**/ => CERTH_46,
+        ['é'] | ['ê'] | ['ē']
+        | ['e', 'e']    /**/ => CERTH_47,
+        ['a']           /**/ => CERTH_48,
+        ['á'] | ['â'] | ['ā']
+        | ['a', 'a']    /**/ => CERTH_49,
+        ['o']           /**/ => CERTH_50,
+        ['ó'] | ['ô'] | ['ō']
+        | ['o', 'o']    /**/ => CERTH_51,
+        ['ö']           /**/ => CERTH_52,
+
+        //  Others.
+        ['h']           /**/ => CERTH_54,
+        ['ə']           /**/ => CERTH_55,
+        ['p', 's']      /**/ => CERTH_57,
+        ['t', 's']      /**/ => CERTH_58,
+        ['&']           /**/ => CERTH_60,
+
+        _ => { return None; }
+    })
+}
+
+
+pub const fn get_rune(slice: &[char]) -> Option<Rune> {
+    match certh_char(slice) {
+        Some(certh) => Some(Rune::new(certh)),
+        None => match slice {
+            //  Long vowels without their own cirth are written twice.
+            ['í'] | ['î'] | ['ī'] | ['i', 'i'] => Some(Rune::new(CERTH_39).with_long(true)),
+            ['ú'] | ['û'] | ['ū'] | ['u', 'u'] => Some(Rune::new(CERTH_42).with_long(true)),
+            _ => None,
+        }
+    }
+}
+
+
+/// The Angerthas Daeron, an arrangement of the Cirth attributed to Daeron of
+///     Doriath, and used by the Elves of Beleriand.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Daeron {
+    current: Option<Rune>,
+}
+
+impl TengwarMode for Daeron {
+    /// The Cirth have no numerals or punctuation of their own in this crate,
+    ///     so anything else is passed through unchanged.
+    fn find_secondary<P: Policy>(&mut self, _slice: &[char]) -> Option<(Token<P>, usize)> {
+        None
+    }
+
+    fn finish_current<P: Policy>(&mut self) -> Option<Token<P>> {
+        self.current.take().map(Token::Rune)
+    }
+
+    fn process<P: Policy>(&mut self, chunk: &[char]) -> ParseAction<P> {
+        process_runes(&mut self.current, chunk, get_rune)
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{mode::{ParseAction, TengwarMode}, policy::Policy, Token};
+use super::{consts::*, daeron, process_runes, Rune};
+
+
+/// Return the certh for a sound whose value was changed by the Dwarves of
+///     Moria. Any sound not listed here keeps its value from the
+///     [Angerthas Daeron](super::Daeron).
+pub const fn certh_char(slice: &[char]) -> Option<char> {
+    Some(match slice {
+        ['r']           /**/ => CERTH_12,
+        ['z']           /**/ => CERTH_17,
+        ['n']           /**/ => CERTH_22,
+        ['j']           /**/ => CERTH_29,
+        ['z', 'h']      /**/ => CERTH_30,
+        ['n', 'd']      /**/ => CERTH_33,
+        ['h']           /**/ => CERTH_34,
+        ['ñ'] | ['ŋ']   /**/ => CERTH_36,
+        ['n', 'g']      /**/ => CERTH_37,
+        ['n', 'j']      /**/ => CERTH_38,
+        ['ú'] | ['û'] | ['ū']
+        | ['u', 'u']    /**/ => CERTH_43,
+        ['s']           /**/ => CERTH_54,
+
+        _ => { return None; }
+    })
+}
+
+
+pub const fn get_rune(slice: &[char]) -> Option<Rune> {
+    match certh_char(slice) {
+        Some(certh) => Some(Rune::new(certh)),
+        None => daeron::get_rune(slice),
+    }
+}
+
+
+/// The Angerthas Moria, the variation of the
+///     [Angerthas Daeron](super::Daeron) used by the Dwarves of Khazad-dûm.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Moria {
+    current: Option<Rune>,
+}
+
+impl TengwarMode for Moria {
+    /// As in the Angerthas Daeron, anything that is not a rune is passed
+    ///     through unchanged.
+    fn find_secondary<P: Policy>(&mut self, _slice: &[char]) -> Option<(Token<P>, usize)> {
+        None
+    }
+
+    fn finish_current<P: Policy>(&mut self) -> Option<Token<P>> {
+        self.current.take().map(Token::Rune)
+    }
+
+    fn process<P: Policy>(&mut self, chunk: &[char]) -> ParseAction<P> {
+        process_runes(&mut self.current, chunk, get_rune)
+    }
+}
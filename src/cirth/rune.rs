@@ -0,0 +1,57 @@
+use std::fmt::{Display, Formatter, Write};
+use super::consts::certh_number;
+
+
+/// A single certh of the Cirth, along with any extra tags it requires. This is
+///     the counterpart of a [`Glyph`](crate::Glyph), but the Cirth are a much
+///     simpler alphabet, so there is much less to specify.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Rune {
+    /// The base certh.
+    pub base: char,
+
+    /// Indicates that this rune represents a long sound with no dedicated
+    ///     certh of its own. It will be rendered twice.
+    pub long: bool,
+}
+
+/// Public: Construction and modification.
+impl Rune {
+    /// Define a new rune with a base [`char`].
+    pub const fn new(base: char) -> Self {
+        Self { base, long: false }
+    }
+
+    /// Mark this rune as being long.
+    pub const fn with_long(mut self, enabled: bool) -> Self {
+        self.long = enabled;
+        self
+    }
+}
+
+/// Public: Inspection.
+impl Rune {
+    /// Return the number in the Angerthas of the base certh.
+    pub const fn number(&self) -> Option<usize> {
+        certh_number(self.base)
+    }
+}
+
+impl From<char> for Rune {
+    fn from(base: char) -> Self {
+        Self::new(base)
+    }
+}
+
+impl Display for Rune {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_char(self.base)?;
+
+        if self.long {
+            f.write_char(self.base)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+#![cfg(test)]
+
+use crate::TengwarMode;
+use super::{consts::*, *};
+
+
+fn runes(cirth: &[char]) -> String {
+    cirth.iter().collect()
+}
+
+
+#[test]
+fn daeron() {
+    assert_eq!(Daeron::transcribe::<String>("daeron"), runes(&[
+        CERTH_09, CERTH_48, CERTH_46, CERTH_29, CERTH_50, CERTH_12,
+    ]));
+    assert_eq!(Daeron::transcribe::<String>("angerthas"), runes(&[
+        CERTH_48, CERTH_33, CERTH_46, CERTH_29, CERTH_10, CERTH_48, CERTH_34,
+    ]));
+
+    //  Long vowels.
+    assert_eq!(Daeron::transcribe::<String>("ā é ó"), runes(&[
+        CERTH_49, ' ', CERTH_47, ' ', CERTH_51,
+    ]));
+    assert_eq!(Daeron::transcribe::<String>("ī"), runes(&[CERTH_39, CERTH_39]));
+    assert_eq!(Daeron::transcribe::<String>("ii"), Daeron::transcribe::<String>("í"));
+}
+
+
+#[test]
+fn moria() {
+    assert_eq!(Moria::transcribe::<String>("balin fundinul"), runes(&[
+        CERTH_02, CERTH_48, CERTH_31, CERTH_39, CERTH_22, ' ',
+        CERTH_03, CERTH_42, CERTH_33, CERTH_39, CERTH_22, CERTH_42, CERTH_31,
+    ]));
+    assert_eq!(Moria::transcribe::<String>("khazad-dûm"), runes(&[
+        CERTH_20, CERTH_48, CERTH_17, CERTH_48, CERTH_09, '-',
+        CERTH_09, CERTH_43, CERTH_06,
+    ]));
+    assert_eq!(Moria::transcribe::<String>("ū"), runes(&[CERTH_43]));
+
+    //  Values not changed in Moria are the same as in Daeron.
+    assert_eq!(Moria::transcribe::<String>("kheled"), Daeron::transcribe::<String>("kheled"));
+    assert_ne!(Moria::transcribe::<String>("r"), Daeron::transcribe::<String>("r"));
+}
+
+
+#[test]
+fn special() {
+    //  X is written with two runes.
+    assert_eq!(Daeron::transcribe::<String>("ax"), runes(&[CERTH_48, CERTH_18, CERTH_34]));
+    assert_eq!(Moria::transcribe::<String>("xa"), runes(&[CERTH_18, CERTH_54, CERTH_48]));
+
+    //  Punctuation and numerals are passed through.
+    assert_eq!(Daeron::transcribe::<String>("a, 2."), runes(&[CERTH_48, ',', ' ', '2', '.']));
+
+    //  Escapes are passed through.
+    assert_eq!(Daeron::transcribe::<String>("a\\b"), runes(&[CERTH_48, 'b']));
+
+    //  Numbers of cirth.
+    for number in 1..=60 {
+        assert_eq!(certh(number).and_then(certh_number), Some(number));
+    }
+
+    assert_eq!(certh(0), None);
+    assert_eq!(certh(61), None);
+    assert_eq!(Rune::new(CERTH_48).number(), Some(48));
+}
@@ -1,9 +1,11 @@
-use std::iter::Peekable;
+use std::{collections::VecDeque, fmt::{Display, Formatter}, str::FromStr};
 use crate::{
     characters::*,
     mode::Tokenizer,
     policy::{Policy, Standard},
     token::Token,
+    trace::SharedTrace,
+    transform::{self, Context, Transform},
 };
 
 
@@ -12,7 +14,7 @@ use crate::{
 ///
 /// This type is a special case of a [`TokenIter`], where the internal iterator
 ///     is a [`Tokenizer`].
-pub type Transcriber<M, P = Standard> = TokenIter<Tokenizer<M>, Standard, P>;
+pub type Transcriber<M, P = Standard> = TokenIter<Tokenizer<M, P>, P, P>;
 
 
 /// An iterator over a sequence of [`Token`]s which applies various rules. This
@@ -24,18 +26,34 @@ pub type Transcriber<M, P = Standard> = TokenIter<Tokenizer<M>, Standard, P>;
 ///     context is analyzed. This ability may be critical to perform changes
 ///     that would affect the context.
 pub struct TokenIter<I: Iterator<Item=Token<P>>, P: Policy, Q: Policy = P> {
-    inner: Peekable<I>,
+    inner: I,
+    /// Tokens taken from the inner iterator in advance, which have not yet
+    ///     been returned.
+    ahead: VecDeque<Token<P>>,
+    /// The conversion of each [`Token`] to the [`Policy`] of this iterator.
+    convert: fn(Token<P>) -> Token<Q>,
     last: Option<Token<Q>>,
     pub settings: TranscriberSettings,
+    /// The [`Transform`]s applied to each Token, in order.
+    pub transforms: Vec<Box<dyn Transform<Q>>>,
+    /// A record of the rules applied, if one is being kept.
+    trace: Option<SharedTrace>,
+    /// The number of tokens taken from the inner iterator.
+    index: usize,
 }
 
 impl<I: Iterator<Item=Token<P>>, P: Policy> TokenIter<I, P, P> {
     /// Construct a TokenIter around an arbitrary Iterator of [`Token`]s.
     pub fn new(iter: I) -> Self {
         Self {
-            inner: iter.peekable(),
+            inner: iter,
+            ahead: VecDeque::new(),
+            convert: |token| token,
             last: None,
             settings: Default::default(),
+            transforms: transform::defaults(),
+            trace: None,
+            index: 0,
         }
     }
 }
@@ -48,14 +66,31 @@ impl<I: Iterator<Item=Token<P>>, P: Policy, Q: Policy> TokenIter<I, P, Q> {
     pub fn last(&self) -> Option<&Token<Q>> { self.last.as_ref() }
 
     /// Return a reference to the next Token, without advancing the Iterator.
-    pub fn peek(&mut self) -> Option<&Token<P>> { self.inner.peek() }
+    pub fn peek(&mut self) -> Option<&Token<P>> {
+        if self.ahead.is_empty() {
+            let token: Token<P> = self.inner.next()?;
+            self.ahead.push_back(token);
+        }
+
+        self.ahead.front()
+    }
 
     /// Change the [`Policy`] used for the [`Glyph`]s produced by this iterator.
-    pub fn set_policy<R: Policy>(self) -> TokenIter<I, P, R> {
+    ///
+    /// The transforms of the iterator are kept, in order. Each of the built-in
+    ///     [defaults](transform::defaults) is replaced by the same transform
+    ///     for the new Policy. Any other [`Transform`] is shown the tokens as
+    ///     if they were still of the old Policy.
+    pub fn set_policy<R: Policy>(self) -> TokenIter<I, P, R> where Q: 'static {
         TokenIter {
             inner: self.inner,
+            ahead: self.ahead,
+            convert: Token::change_policy,
             last: self.last.map(Token::change_policy),
             settings: self.settings,
+            transforms: self.transforms.into_iter().map(transform::change_policy).collect(),
+            trace: self.trace,
+            index: self.index,
         }
     }
 
@@ -64,6 +99,49 @@ impl<I: Iterator<Item=Token<P>>, P: Policy, Q: Policy> TokenIter<I, P, Q> {
         self.settings = new;
         self
     }
+
+    /// Add a [`Transform`] to be applied to each Token, after all of the
+    ///     transforms already added.
+    pub fn with_transform(mut self, transform: impl Transform<Q> + 'static) -> Self {
+        self.transforms.push(Box::new(transform));
+        self
+    }
+
+    /// Record the rules applied by this iterator in a [`Trace`].
+    ///
+    /// [`Trace`]: crate::trace::Trace
+    pub fn with_trace(mut self, trace: SharedTrace) -> Self {
+        self.trace = Some(trace);
+        self
+    }
+
+    /// Take the next token from the inner iterator.
+    fn next_inner(&mut self) -> Option<Token<Q>> {
+        let token: Token<P> = match self.ahead.pop_front() {
+            Some(token) => token,
+            None => self.inner.next()?,
+        };
+
+        self.index += 1;
+        Some((self.convert)(token))
+    }
+
+    /// Find the next token that is not a directive, without advancing the
+    ///     iterator. Any directives before it are kept to be applied later.
+    fn peek_past_directives(&mut self) -> Option<Token<Q>> {
+        let mut idx: usize = 0;
+
+        loop {
+            match self.ahead.get(idx) {
+                Some(Token::Directive(_)) => idx += 1,
+                Some(token) => break Some((self.convert)(*token)),
+                None => {
+                    let token: Token<P> = self.inner.next()?;
+                    self.ahead.push_back(token);
+                }
+            }
+        }
+    }
 }
 
 impl<I, P> From<I> for TokenIter<I::IntoIter, P, P> where
@@ -81,53 +159,30 @@ impl<I, P, Q> Iterator for TokenIter<I, P, Q> where
     type Item = Token<Q>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut token: Token<Q> = self.inner.next()?.change_policy::<Q>();
+        let mut token: Token<Q> = self.next_inner()?;
 
-        if let Token::Glyph(glyph) = &mut token {
-            glyph.ligate_zwj = self.settings.ligate_zwj;
-            glyph.nuquerna = self.settings.nuquerna;
-            glyph.vowels = self.settings.vowels;
-
-            if self.settings.dot_plain && !glyph.carries_tehta() {
-                glyph.dot_under = true;
-            }
-
-            if self.settings.elide_a && glyph.tehta_is_a() {
-                if self.settings.keep_a_long && glyph.tehta_alt {
-                    //  We want to shorten long vowels, and this one is long.
-                    glyph.tehta_alt = false;
-
-                    /*if glyph.base.is_some() { // TODO
-                        glyph.tehta_alt = false;
-                    } else {
-                        glyph.tehta_hidden = true;
-                    }*/
-                } else if self.settings.keep_a_init {
-                    //  We want to keep an initial occurrence.
-                    if matches!(&self.last, Some(Token::Glyph(_))) {
-                        //  This is not an initial occurrence. Elide it.
-                        glyph.tehta_hidden = true;
-                    }
-                } else {
-                    glyph.tehta_hidden = true;
-                }
-            }
-
-            if self.settings.alt_a {
-                glyph.set_alt_a();
-            }
+        //  Apply any inline directives to the settings. They take effect from
+        //      the next token onward, and are not passed through.
+        while let Token::Directive(directive) = token {
+            directive.apply(&mut self.settings);
+            token = self.next_inner()?;
+        }
 
-            match self.inner.peek() {
-                Some(Token::Glyph(next)) => {
-                    glyph.rince_final = false;
-                    glyph.ligate_short = self.settings.ligate_short
-                        // && glyph.is_short_carrier()
-                        && next.telco_ligates();
-                }
-                _ => {
-                    glyph.rince_final = self.settings.alt_rince;
-                    glyph.ligate_short = false;
-                }
+        //  The context of the token is the next glyph or character, even if a
+        //      directive comes between them.
+        let next: Option<Token<Q>> = self.peek_past_directives();
+        let context = Context {
+            prev: self.last.as_ref(),
+            next: next.as_ref(),
+            settings: &self.settings,
+        };
+
+        for transform in &self.transforms {
+            let before: Token<Q> = token;
+            transform.apply(&mut token, &context);
+
+            if let Some(trace) = &self.trace {
+                trace.borrow_mut().rule(self.index - 1, transform.name(), &before, &token);
             }
         }
 
@@ -140,6 +195,7 @@ impl<I, P, Q> Iterator for TokenIter<I, P, Q> where
 /// Behavior settings to be used by a [`TokenIter`].
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
 pub struct TranscriberSettings {
     /// If this is `true`, the [A-tehta](TEHTA_A) will be replaced with its
     ///     [alternate form](TEHTA_YANTA).
@@ -200,3 +256,211 @@ impl TranscriberSettings {
 impl Default for TranscriberSettings {
     fn default() -> Self { Self::new() }
 }
+
+
+/// A change to a single field of [`TranscriberSettings`], which can be written
+///     inline, within the text being transcribed.
+///
+/// A directive is written between [braces](Self::OPEN), and has one of three
+///     forms: `{+name}` enables a setting, `{-name}` disables it, and
+///     `{name=value}` gives it a specific value. The name of a directive is the
+///     name of a field of [`TranscriberSettings`], such as `alt_a`, and either
+///     underscores or hyphens may be used. The [vowel style](VowelStyle) may
+///     also be named `long`, as in `{long=unique}`.
+///
+/// Directives are only recognized by a [`Tokenizer`] that is set to read them,
+///     with [`Tokenizer::with_directives`]; Otherwise, braces are ordinary
+///     text. They are applied by a [`TokenIter`] to all the tokens that follow
+///     them, and do not divide the word they are written in. A directive in
+///     the middle of a glyph takes effect after that glyph.
+///
+/// A directive can be written literally by [escaping](ESC) its opening brace.
+///     Any text between braces that is not a valid directive, such as
+///     `{bogus=1}`, is passed through unchanged, without being transcribed.
+///
+/// # Example
+/// ```
+/// use tengwar::{mode::Tokenizer, Quenya, ToTengwar, TranscriberSettings};
+///
+/// let mut settings = TranscriberSettings::new();
+/// settings.alt_a = true;
+///
+/// let text: String = Tokenizer::<Quenya>::from_str("{+alt_a}namárië")
+///     .with_directives()
+///     .into_transcriber()
+///     .into_string();
+///
+/// assert_eq!(text, "namárië".to_tengwar_with::<Quenya>(settings));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Directive {
+    /// Change [`TranscriberSettings::alt_a`].
+    AltA(bool),
+    /// Change [`TranscriberSettings::alt_rince`].
+    AltRince(bool),
+    /// Change [`TranscriberSettings::dot_plain`].
+    DotPlain(bool),
+    /// Change [`TranscriberSettings::elide_a`].
+    ElideA(bool),
+    /// Change [`TranscriberSettings::keep_a_init`].
+    KeepAInit(bool),
+    /// Change [`TranscriberSettings::keep_a_long`].
+    KeepALong(bool),
+    /// Change [`TranscriberSettings::ligate_short`].
+    LigateShort(bool),
+    /// Change [`TranscriberSettings::ligate_zwj`]. When this is enabled by
+    ///     name, it is set to the highest level.
+    LigateZwj(u8),
+    /// Change [`TranscriberSettings::nuquerna`].
+    Nuquerna(bool),
+    /// Change [`TranscriberSettings::vowels`].
+    Vowels(VowelStyle),
+}
+
+impl Directive {
+    /// The character that begins an inline directive.
+    pub const OPEN: char = '{';
+    /// The character that ends an inline directive.
+    pub const CLOSE: char = '}';
+    /// The longest text allowed between the braces of an inline directive.
+    const MAX_LEN: usize = 24;
+
+    /// Return the name of the setting changed by this directive.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::AltA(_) => "alt_a",
+            Self::AltRince(_) => "alt_rince",
+            Self::DotPlain(_) => "dot_plain",
+            Self::ElideA(_) => "elide_a",
+            Self::KeepAInit(_) => "keep_a_init",
+            Self::KeepALong(_) => "keep_a_long",
+            Self::LigateShort(_) => "ligate_short",
+            Self::LigateZwj(_) => "ligate_zwj",
+            Self::Nuquerna(_) => "nuquerna",
+            Self::Vowels(_) => "vowels",
+        }
+    }
+
+    /// Change the settings according to this directive.
+    pub fn apply(self, settings: &mut TranscriberSettings) {
+        match self {
+            Self::AltA(value) => settings.alt_a = value,
+            Self::AltRince(value) => settings.alt_rince = value,
+            Self::DotPlain(value) => settings.dot_plain = value,
+            Self::ElideA(value) => settings.elide_a = value,
+            Self::KeepAInit(value) => settings.keep_a_init = value,
+            Self::KeepALong(value) => settings.keep_a_long = value,
+            Self::LigateShort(value) => settings.ligate_short = value,
+            Self::LigateZwj(value) => settings.ligate_zwj = value,
+            Self::Nuquerna(value) => settings.nuquerna = value,
+            Self::Vowels(value) => settings.vowels = value,
+        }
+    }
+
+    /// If an inline directive begins at the start of a slice of [`char`]s,
+    ///     return it, along with the number of `char`s it spans, including its
+    ///     braces.
+    pub fn find(chars: &[char]) -> Option<(Self, usize)> {
+        match Self::find_braced(chars)? {
+            (Ok(directive), n) => Some((directive, n)),
+            (Err(_), _) => None,
+        }
+    }
+
+    /// If text between braces begins at the start of a slice of [`char`]s,
+    ///     try to parse it as an inline directive. Return the result, along
+    ///     with the number of `char`s between and including the braces.
+    pub fn find_braced(chars: &[char]) -> Option<(Result<Self, String>, usize)> {
+        let [Self::OPEN, rest @ ..] = chars else { return None; };
+        let len: usize = rest.iter()
+            .take(Self::MAX_LEN + 1)
+            .position(|&c| c == Self::CLOSE)?;
+        let text: String = rest[..len].iter().collect();
+
+        Some((text.parse(), len + 2))
+    }
+
+    /// If an inline directive begins at the start of a string, return its
+    ///     length, in bytes, including its braces.
+    pub(crate) fn find_str(text: &str) -> Option<usize> {
+        let chars: Vec<char> = text.chars().take(Self::MAX_LEN + 2).collect();
+        let (_, n) = Self::find(&chars)?;
+
+        Some(chars[..n].iter().map(|c| c.len_utf8()).sum())
+    }
+
+    /// Construct a directive from the name of a setting and a value for it.
+    fn with_value(name: &str, value: &str) -> Result<Self, String> {
+        let flag = || match value {
+            "true" | "on" | "yes" => Ok(true),
+            "false" | "off" | "no" => Ok(false),
+            _ => Err(format!("invalid value for `{name}`: {value:?}")),
+        };
+
+        Ok(match name.replace('-', "_").as_str() {
+            "alt_a" => Self::AltA(flag()?),
+            "alt_rince" => Self::AltRince(flag()?),
+            "dot_plain" => Self::DotPlain(flag()?),
+            "elide_a" => Self::ElideA(flag()?),
+            "keep_a_init" => Self::KeepAInit(flag()?),
+            "keep_a_long" => Self::KeepALong(flag()?),
+            "ligate_short" => Self::LigateShort(flag()?),
+            "ligate_zwj" => Self::LigateZwj(match flag() {
+                Ok(enable) => if enable { u8::MAX } else { 0 },
+                Err(_) => value.parse().map_err(|_| {
+                    format!("invalid value for `{name}`: {value:?}")
+                })?,
+            }),
+            "nuquerna" => Self::Nuquerna(flag()?),
+            "vowels" | "long" => Self::Vowels(
+                clap::ValueEnum::from_str(value, true)
+                    .map_err(|_| format!("invalid value for `{name}`: {value:?}"))?,
+            ),
+            _ => return Err(format!("unknown setting `{name}`")),
+        })
+    }
+}
+
+/// A directive is written as it would be between braces, such as `+alt_a`.
+impl Display for Directive {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LigateZwj(value) => write!(f, "{}={value}", self.name()),
+            Self::Vowels(style) => match clap::ValueEnum::to_possible_value(style) {
+                Some(value) => write!(f, "{}={}", self.name(), value.get_name()),
+                None => write!(f, "{}={style:?}", self.name()),
+            },
+            Self::AltA(value)
+            | Self::AltRince(value)
+            | Self::DotPlain(value)
+            | Self::ElideA(value)
+            | Self::KeepAInit(value)
+            | Self::KeepALong(value)
+            | Self::LigateShort(value)
+            | Self::Nuquerna(value) => {
+                write!(f, "{}{}", if *value { '+' } else { '-' }, self.name())
+            }
+        }
+    }
+}
+
+/// A directive is parsed from the text between its braces, such as `+alt_a`,
+///     `-nuquerna` or `long=unique`. Case is ignored.
+impl FromStr for Directive {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s: String = s.to_lowercase();
+
+        if let Some(name) = s.strip_prefix('+') {
+            Self::with_value(name, "true")
+        } else if let Some(name) = s.strip_prefix('-') {
+            Self::with_value(name, "false")
+        } else if let Some((name, value)) = s.split_once('=') {
+            Self::with_value(name, value)
+        } else {
+            Err(format!("invalid directive {s:?}; expected `+name`, `-name`, or `name=value`"))
+        }
+    }
+}
@@ -26,7 +26,12 @@
 //!
 //! # Examples
 //!
+//! The expected text in these examples is written in the CSUR block, and is
+//!     converted by [`Encoding::recode`] into the block of the output, which
+//!     depends on the features of the crate.
+//!
 //! [`collect`]: Iterator::collect
+//! [`Encoding::recode`]: characters::encoding::Encoding::recode
 //!
 //! ## `TengwarMode` trait
 //!
@@ -35,10 +40,10 @@
 //!     return any type that implements `FromIterator<Token>`; This includes
 //!     `Vec<Token>` and [`String`].
 //! ```
-//! use tengwar::{Quenya, TengwarMode};
+//! use tengwar::{characters::encoding::Encoding, Quenya, TengwarMode};
 //!
 //! let text: String = Quenya::transcribe("namárië !");
-//! assert_eq!(text, " ");
+//! assert_eq!(text, Encoding::DEFAULT.recode(" "));
 //! ```
 //!
 //! ## `ToTengwar` trait
@@ -52,40 +57,40 @@
 //! The `Transcriber` also has [`TranscriberSettings`], holding several public
 //!     fields, which can be changed to adjust various aspects of its behavior.
 //! ```
-//! use tengwar::{Quenya, ToTengwar};
+//! use tengwar::{characters::encoding::Encoding, Quenya, ToTengwar};
 //!
 //! let mut transcriber = "namárië !".transcriber::<Quenya>();
 //! transcriber.settings.alt_a = true; // Use the alternate form of the A-tehta.
 //!
 //! let text: String = transcriber.collect();
-//! assert_eq!(text, " ");
+//! assert_eq!(text, Encoding::DEFAULT.recode(" "));
 //! ```
 //!
 //! The second method is [`ToTengwar::to_tengwar`]. This is mostly a convenience
 //!     method, which simply calls [`ToTengwar::transcriber`] and immediately
 //!     [`collect`]s the Iterator into a [`String`].
 //! ```
-//! use tengwar::{Quenya, ToTengwar};
+//! use tengwar::{characters::encoding::Encoding, Quenya, ToTengwar};
 //!
 //! let text: String = "namárië !".to_tengwar::<Quenya>();
-//! assert_eq!(text, " ");
+//! assert_eq!(text, Encoding::DEFAULT.recode(" "));
 //! ```
 //!
 //! The third method is [`ToTengwar::to_tengwar_with`], which does the same, but
 //!     takes [`TranscriberSettings`] to modify the [`Transcriber`] before it is
 //!     collected. This allows settings to be specified once and reused.
 //! ```
-//! use tengwar::{Quenya, ToTengwar, TranscriberSettings};
+//! use tengwar::{characters::encoding::Encoding, Quenya, ToTengwar, TranscriberSettings};
 //!
 //! let mut settings = TranscriberSettings::new();
 //! settings.alt_a = true;
 //! settings.nuquerna = true;
 //!
 //! let text: String = "namárië !".to_tengwar_with::<Quenya>(settings);
-//! assert_eq!(text, " ");
+//! assert_eq!(text, Encoding::DEFAULT.recode(" "));
 //!
 //! let text: String = "lotsë súva".to_tengwar_with::<Quenya>(settings);
-//! assert_eq!(text, " ");
+//! assert_eq!(text, Encoding::DEFAULT.recode(" "));
 //! ```
 //!
 //! ## Crate-level function
@@ -96,10 +101,10 @@
 //!     type that implements [`ToTengwar`], and is a passthrough to the
 //!     [`ToTengwar::to_tengwar`] method.
 //! ```
-//! use tengwar::{Quenya, transcribe};
+//! use tengwar::{characters::encoding::Encoding, Quenya, transcribe};
 //!
 //! let text: String = transcribe::<Quenya>("namárië !");
-//! assert_eq!(text, " ");
+//! assert_eq!(text, Encoding::DEFAULT.recode(" "));
 //! ```
 //!
 //! ---
@@ -144,6 +149,11 @@
 //!     important step, as some modes require that different base characters are
 //!     used depending on what follows them.
 //!
+//! A mode may also ask to see more of the surrounding `Token`s, by way of
+//!     [`TengwarMode::finalize_in`]. The [`Neighbors`](mode::Neighbors) it is
+//!     given include several `Token`s in each direction, and can find the edges
+//!     of the current word.
+//!
 //! ## TokenIter / Transcriber
 //!
 //! The second level of iteration is the [`TokenIter`]. This iterator can wrap
@@ -152,10 +162,25 @@
 //!     allows the executable transcriber to take CLI options that change rules,
 //!     such as the treatment of "long" tehta variants.
 //!
+//! Each of these rules is a [`Transform`](transform::Transform), and more can be
+//!     added to a `TokenIter` to make changes of its own. See the [`transform`]
+//!     module for details.
+//!
+//! The settings of a `TokenIter` can also be changed by the text itself, with
+//!     inline [`Directive`]s such as `{+alt_a}`. These are recognized by a
+//!     `Tokenizer` that is set to read them, and applied by the `TokenIter` to
+//!     all the tokens after them.
+//!
 //! A `TokenIter` that wraps a [`Tokenizer`](mode::Tokenizer) can also be called
 //!     a [`Transcriber`] for simplicity, because it is known that its `Token`s
 //!     are being produced directly from text.
 //!
+//! ## Span
+//!
+//! The position of each `Token` can be mapped back to the text it was read
+//!     from, and forward to the text it is rendered into. See the [`span`]
+//!     module for details.
+//!
 //! ## Policy
 //!
 //! A "Policy" is similar to a Mode, but rather than defining details about
@@ -164,10 +189,19 @@
 //!
 //! The [`Policy`](policy::Policy) trait is provided for this purpose, and is
 //!     used as a generic parameter for the [`Glyph`] type. Because of this, it
-//!     is also a generic parameter for the [`Token`] and [`TokenIter`] types;
-//!     The [`Tokenizer`](mode::Tokenizer) type is considered to be out of scope
-//!     of the Policy system, and simply yields all of its `Token`s with the
-//!     default policy ([`policy::Standard`]).
+//!     is also a generic parameter for the [`Token`], [`TokenIter`], and
+//!     [`Tokenizer`](mode::Tokenizer) types. The methods of [`TengwarMode`] are
+//!     generic over the Policy as well, so that a mode can consult it while
+//!     constructing `Token`s; For example, to decide whether a tengwa can take
+//!     a *Sa-Rincë*. Unless specified otherwise, the default policy is used
+//!     ([`policy::Standard`]).
+//!
+//! ## Reverse Transcription
+//!
+//! Text that is already written in the Tengwar can be read back into a Latin
+//!     form by way of the [`Romanize`](reverse::Romanize) trait, which is
+//!     implemented for each of the default Modes. See the [`reverse`] module for
+//!     details.
 
 #[macro_use]
 extern crate cfg_if;
@@ -180,15 +214,20 @@ extern crate serde;
 // mod macros;
 
 pub mod characters;
+pub mod cirth;
 pub mod mode;
 pub mod policy;
+pub mod reverse;
 
 mod iter;
+pub mod span;
 mod token;
+pub mod trace;
+pub mod transform;
 
 pub use characters::{Glyph, Numeral, VowelStyle};
-pub use iter::{TokenIter, Transcriber, TranscriberSettings};
-pub use mode::{Beleriand, Gondor, Quenya, TengwarMode};
+pub use iter::{Directive, TokenIter, Transcriber, TranscriberSettings};
+pub use mode::{Beleriand, General, Gondor, GondorFull, Quenya, TengwarMode};
 pub use token::Token;
 
 
@@ -209,33 +248,33 @@ pub trait ToTengwar {
     ///
     /// # Example
     /// ```
-    /// use tengwar::{Quenya, ToTengwar, VowelStyle};
+    /// use tengwar::{characters::encoding::Encoding, Quenya, ToTengwar, VowelStyle};
     ///
     /// const INPUT: &str = "lotsë súva"; // "a flower is sinking"
     ///
     ///
     /// //  Collect directly with default settings.
     /// let mut ts = INPUT.transcriber::<Quenya>();
-    /// assert_eq!(ts.into_string(), " ");
+    /// assert_eq!(ts.into_string(), Encoding::DEFAULT.recode(" "));
     ///
     ///
     /// //  Use Unique Tehtar.
     /// let mut ts = INPUT.transcriber::<Quenya>();
     /// ts.settings.vowels = VowelStyle::Unique;
-    /// assert_eq!(ts.into_string(), " ");
+    /// assert_eq!(ts.into_string(), Encoding::DEFAULT.recode(" "));
     ///
     ///
     /// //  Use Nuquernë Tengwar.
     /// let mut ts = INPUT.transcriber::<Quenya>();
     /// ts.settings.nuquerna = true;
-    /// assert_eq!(ts.into_string(), " ");
+    /// assert_eq!(ts.into_string(), Encoding::DEFAULT.recode(" "));
     ///
     ///
     /// //  Use Unique Tehtar and Nuquernë Tengwar.
     /// let mut ts = INPUT.transcriber::<Quenya>();
     /// ts.settings.nuquerna = true;
     /// ts.settings.vowels = VowelStyle::Unique;
-    /// assert_eq!(ts.into_string(), " ");
+    /// assert_eq!(ts.into_string(), Encoding::DEFAULT.recode(" "));
     ///
     ///
     /// //  Use several options.
@@ -244,7 +283,7 @@ pub trait ToTengwar {
     /// ts.settings.alt_rince = true;
     /// ts.settings.nuquerna = true;
     /// ts.settings.vowels = VowelStyle::Separate;
-    /// assert_eq!(ts.into_string(), " ");
+    /// assert_eq!(ts.into_string(), Encoding::DEFAULT.recode(" "));
     /// ```
     fn transcriber<M: TengwarMode + Default>(&self) -> Transcriber<M>;
 
@@ -252,10 +291,10 @@ pub trait ToTengwar {
     ///
     /// # Example
     /// ```
-    /// use tengwar::{Quenya, ToTengwar};
+    /// use tengwar::{characters::encoding::Encoding, Quenya, ToTengwar};
     ///
     /// let text: String = "namárië !".to_tengwar::<Quenya>();
-    /// assert_eq!(text, " ");
+    /// assert_eq!(text, Encoding::DEFAULT.recode(" "));
     /// ```
     fn to_tengwar<M: TengwarMode + Default>(&self) -> String {
         self.transcriber::<M>().into_string()
@@ -270,17 +309,17 @@ pub trait ToTengwar {
     ///
     /// # Example
     /// ```
-    /// use tengwar::{Quenya, ToTengwar, TranscriberSettings};
+    /// use tengwar::{characters::encoding::Encoding, Quenya, ToTengwar, TranscriberSettings};
     ///
     /// let mut settings = TranscriberSettings::new();
     /// settings.alt_a = true;
     /// settings.nuquerna = true;
     ///
     /// let text: String = "namárië !".to_tengwar_with::<Quenya>(settings);
-    /// assert_eq!(text, " ");
+    /// assert_eq!(text, Encoding::DEFAULT.recode(" "));
     ///
     /// let text: String = "lotsë súva".to_tengwar_with::<Quenya>(settings);
-    /// assert_eq!(text, " ");
+    /// assert_eq!(text, Encoding::DEFAULT.recode(" "));
     /// ```
     fn to_tengwar_with<M>(&self, settings: TranscriberSettings) -> String
         where M: TengwarMode + Default
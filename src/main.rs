@@ -4,24 +4,244 @@ extern crate clap;
 mod bin_mode;
 mod bin_test;
 
-use std::{io::{BufRead, stdin, stdout, Write}, process::exit};
+use std::{io::{BufRead, stderr, stdin, stdout, Write}, process::exit};
 use bin_mode::*;
-use tengwar::{TranscriberSettings, VowelStyle};
+use tengwar::{
+    characters::encoding::Encoding,
+    mode::{markup::Syntax, Language},
+    policy::Font,
+    TranscriberSettings,
+    VowelStyle,
+};
+#[cfg(any(feature = "mode-custom", feature = "font-coverage"))]
+use std::path::PathBuf;
+#[cfg(feature = "mode-custom")]
+use {std::path::Path, tengwar::mode::custom::file::ModeFile};
+#[cfg(feature = "font-coverage")]
+use tengwar::policy::{coverage::FontCoverage, Standard};
+#[cfg(feature = "policy-custom")]
+use tengwar::policy::data::PolicyFile;
+
+
+/// Read a mode file, converting any failure into a usage error.
+#[cfg(feature = "mode-custom")]
+fn load_mode_file(path: &Path) -> Result<ModeFile, clap::Error> {
+    ModeFile::from_path(path).map_err(|err| {
+        <Command as clap::CommandFactory>::command().error(
+            clap::error::ErrorKind::InvalidValue,
+            format!("invalid mode file {}: {err}", path.display()),
+        )
+    })
+}
+
+
+/// Read a policy file, converting any failure into a usage error.
+#[cfg(feature = "policy-custom")]
+fn load_policy_file(path: &Path) -> Result<PolicyFile, clap::Error> {
+    PolicyFile::from_path(path).map_err(|err| {
+        <Command as clap::CommandFactory>::command().error(
+            clap::error::ErrorKind::InvalidValue,
+            format!("invalid policy file {}: {err}", path.display()),
+        )
+    })
+}
+
+
+/// Return the command line options that select some settings.
+#[cfg(feature = "font-coverage")]
+fn options(settings: &TranscriberSettings) -> Vec<String> {
+    let mut options: Vec<String> = Vec::new();
+    let flags = [
+        (settings.alt_a, "-a"),
+        (settings.alt_rince, "-r"),
+        (settings.dot_plain, "-d"),
+        (settings.elide_a, "-e"),
+        (!settings.nuquerna, "-n"),
+        (settings.ligate_short, "-s"),
+    ];
+
+    options.extend(flags.into_iter().filter(|(set, _)| *set).map(|(_, flag)| flag.to_owned()));
+
+    if 0 < settings.ligate_zwj {
+        options.push(format!("-{}", "z".repeat(settings.ligate_zwj as usize)));
+    }
+
+    if let Some(value) = clap::ValueEnum::to_possible_value(&settings.vowels) {
+        options.push(format!("--long={}", value.get_name()));
+    }
+
+    options
+}
+
+
+#[derive(Debug, Subcommand)]
+#[cfg(any(feature = "mode-custom", feature = "font-coverage"))]
+enum Action {
+    /// Run the test vectors of a mode file, and report any mismatches.
+    ///
+    /// Each `[[test]]` table in the file is transcribed with its own settings,
+    ///     and the output is compared to the expected text glyph by glyph.
+    #[cfg(feature = "mode-custom")]
+    CheckMode {
+        /// The mode file to be checked.
+        path: PathBuf,
+    },
+
+    /// Export a built-in mode as a mode file, and print it.
+    ///
+    /// The output can be used as a starting point for a custom mode. Some
+    ///     special cases of the built-in modes are decided by code, rather than
+    ///     by their tables, and these are not included.
+    #[cfg(feature = "mode-custom")]
+    DumpMode {
+        /// The mode to be exported.
+        #[arg(value_enum, ignore_case = true)]
+        mode: Mode,
+    },
+
+    /// Read a TrueType or OpenType font, and report the style options that it
+    ///     can render.
+    ///
+    /// The font is checked for every codepoint that may be written, and for
+    ///     ligatures of tengwar joined by zero-width joiners in its `GSUB`
+    ///     table. Ligatures defined only by Graphite rules are not found.
+    #[cfg(feature = "font-coverage")]
+    CheckFont {
+        /// The font file to be checked.
+        path: PathBuf,
+    },
+}
+
+#[cfg(any(feature = "mode-custom", feature = "font-coverage"))]
+impl Action {
+    /// Perform the action, writing a report to the output. Returns `true` if
+    ///     the action was successful.
+    fn run(&self, out: &mut impl Write) -> Result<bool, clap::Error> {
+        match self {
+            #[cfg(feature = "mode-custom")]
+            Self::CheckMode { path } => {
+                let file: ModeFile = load_mode_file(path)?;
+                let mode = file.resolve();
+                let mut failed: usize = 0;
+
+                for (n, test) in file.tests.iter().enumerate() {
+                    let result = test.run(&mode);
+
+                    if result.passed() {
+                        writeln!(out, "test {} ({:?}): ok", n + 1, test.input)?;
+                    } else {
+                        failed += 1;
+                        writeln!(out, "test {} ({:?}): FAILED", n + 1, test.input)?;
+                        writeln!(out, "    expected: {}", test.expected)?;
+                        writeln!(out, "    received: {}", result.received)?;
+
+                        for mismatch in &result.mismatches {
+                            writeln!(out, "    {mismatch}")?;
+                        }
+                    }
+                }
+
+                writeln!(out, "{} tests, {failed} failed", file.tests.len())?;
+                Ok(failed == 0)
+            }
+            #[cfg(feature = "mode-custom")]
+            Self::DumpMode { mode } => {
+                let error = |msg: String| <Command as clap::CommandFactory>::command()
+                    .error(clap::error::ErrorKind::InvalidValue, msg);
+
+                let name: String = mode.name();
+
+                let Some(base) = mode.base() else {
+                    return Err(error(format!(
+                        "the mode `{name}` cannot be exported as a mode file",
+                    )));
+                };
+
+                let text: String = base.export().to_toml_string()
+                    .map_err(|err| error(format!("cannot export mode `{name}`: {err}")))?;
+
+                out.write_all(text.as_bytes())?;
+                Ok(true)
+            }
+            #[cfg(feature = "font-coverage")]
+            Self::CheckFont { path } => {
+                let coverage = FontCoverage::from_path(path).map_err(|err| {
+                    <Command as clap::CommandFactory>::command().error(
+                        clap::error::ErrorKind::InvalidValue,
+                        format!("invalid font file {}: {err}", path.display()),
+                    )
+                })?;
+
+                let total: usize = FontCoverage::all_codepoints().count();
+                let name: &str = coverage.name.as_deref().unwrap_or("(unnamed)");
+
+                writeln!(out, "font: {name}")?;
+                writeln!(out, "codepoints: {} of {total}", coverage.codepoints.len())?;
+
+                for c in coverage.missing() {
+                    match tengwar::characters::tengwa_name(c) {
+                        Some(name) => writeln!(out, "    missing: U+{:04X} ({name})", c as u32)?,
+                        None => writeln!(out, "    missing: U+{:04X}", c as u32)?,
+                    }
+                }
+
+                for level in 1..=3 {
+                    let (defined, possible) = coverage.ligation::<Standard>(level);
+                    writeln!(out, "ligatures at level {level}: {defined} of {possible}")?;
+                }
+
+                writeln!(out, "options: {}", options(&coverage.settings()).join(" "))?;
+                Ok(!coverage.codepoints.is_empty())
+            }
+        }
+    }
+}
 
 
 #[derive(Args, Debug)]
 struct ModeFlags {
-    /// Transliterate in the Classical Quenya Mode (default).
+    /// Transliterate Quenya, in the Classical Mode (default).
     ///
     /// Independent Tengwar represent consonant sounds, with vowels being
     ///     represented by a Tehta placed above either the preceding consonant
     ///     or a "carrier" mark.
     ///
-    /// This mode is typically used for Quenya.
+    /// Only the Classical Mode may be used for Quenya.
     #[arg(long, short = 'Q')]
-    #[arg(group = "mode")]
+    #[arg(group = "language")]
     quenya: bool,
 
+    /// Transliterate Sindarin, in the Mode of Gondor unless another mode is
+    ///     selected.
+    ///
+    /// Sindarin may also be written in the "Full" Mode of Gondor, the Mode of
+    ///     Beleriand, or either of the Angerthas.
+    #[arg(long, short = 'S')]
+    #[arg(group = "language")]
+    sindarin: bool,
+
+    /// Transliterate English, in an Orthographic English mode unless another
+    ///     mode is selected.
+    ///
+    /// Independent Tengwar represent consonant letters, with vowels being
+    ///     represented by a Tehta placed above either the preceding consonant
+    ///     or a "carrier" mark. A silent final E is represented by a dot below
+    ///     the preceding consonant.
+    ///
+    /// English may also be written in the Angerthas Moria.
+    #[arg(long, short = 'E')]
+    #[arg(group = "language")]
+    english: bool,
+
+    /// Set a language by name.
+    ///
+    /// The primary mode of the language is used, unless another mode is also
+    ///     selected. Selecting a mode that is not used for the language is an
+    ///     error.
+    #[arg(long = "language", short = 'L', value_name = "LANG")]
+    #[arg(group = "language", value_enum, ignore_case = true)]
+    by_language: Option<Language>,
+
     /// Transliterate in the Sindarin Mode of Gondor.
     ///
     /// Independent Tengwar represent consonant sounds, with vowels being
@@ -46,20 +266,23 @@ struct ModeFlags {
     #[arg(group = "mode")]
     beleriand: bool,
 
-    /*/// Transliterate in an Orthographic English mode.
-    #[arg(long, short = 'E')]
-    #[arg(group = "mode")]
-    english: bool,*/
-
-    /*/// Set a mode by language.
-    #[arg(long = "lang", short = 'L', value_name = "LANG")]
-    #[arg(group = "mode", value_enum, ignore_case = true)]
-    language: Option<Language>,*/
-
     /// Set a mode by name.
+    ///
+    /// The mode is an orthography, independent of language. If a language is
+    ///     also selected, the mode must be one that is used for it.
     #[arg(long = "mode", short = 'M', value_name = "MODE")]
     #[arg(group = "mode", value_enum, ignore_case = true)]
     by_name: Option<Mode>,
+
+    /// Load a custom mode from a TOML file.
+    ///
+    /// The file defines the tengwar and tehtar used for each sequence of input
+    ///     characters, and the rules for combining them. A custom mode is not
+    ///     associated with any language, and cannot be combined with one.
+    #[arg(long = "mode-file", value_name = "PATH")]
+    #[arg(group = "mode", conflicts_with = "language")]
+    #[cfg(feature = "mode-custom")]
+    mode_file: Option<PathBuf>,
 }
 
 
@@ -70,8 +293,9 @@ struct StyleFlags {
     /// The alternate form is simpler and much quicker to write by hand than the
     ///     default tri-dot, and may be preferred when typesetting text intended
     ///     to be handwritten.
-    #[arg(long, short = 'a')]
-    alt_a: bool,
+    #[arg(long, short = 'a', value_name = "BOOL")]
+    #[arg(num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    alt_a: Option<bool>,
 
     /// Use a more ornate "sa-rincë" for final sibilants.
     ///
@@ -81,16 +305,18 @@ struct StyleFlags {
     ///
     /// The alternate version may only be applied to the final tengwa in a word,
     ///     and only to certain tengwar.
-    #[arg(long, short = 'r')]
-    alt_rince: bool,
+    #[arg(long, short = 'r', value_name = "BOOL")]
+    #[arg(num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    alt_rince: Option<bool>,
 
     /// Use a dot to mark any tengwa that does not carry a vowel tehta.
     ///
     /// This is often used in combination with A-vowel elision; Any tengwa that
     ///     does not show a vowel, but is also not marked with a dot, can be
     ///     assumed to have an implicit A-vowel.
-    #[arg(long, short = 'd')]
-    dot_plain: bool,
+    #[arg(long, short = 'd', value_name = "BOOL")]
+    #[arg(num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    dot_plain: Option<bool>,
 
     /// Do not use a tehta at all for A-vowels.
     ///
@@ -99,8 +325,9 @@ struct StyleFlags {
     ///     would then be inferred between consonants that could not form valid
     ///     clusters.
     //  TODO: Allow partial elision, using the tehta for initial/long A-vowels.
-    #[arg(long, short = 'e')]
-    elide_a: bool,
+    #[arg(long, short = 'e', value_name = "BOOL")]
+    #[arg(num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    elide_a: Option<bool>,
 
     /// Set behavior for long vowel tehtar.
     ///
@@ -120,10 +347,11 @@ struct StyleFlags {
     ///     all are provided as possibilities. However, certain tehtar are not
     ///     suitable for doubling, and so will always use the separate extended
     ///     carrier, regardless of this setting.
+    ///
+    /// The default is `doubled`, unless a `--font` is selected.
     #[arg(long = "long", short = 'l', value_name = "STYLE")]
     #[arg(group = "tehtar", value_enum, ignore_case = true)]
-    #[arg(default_value_t = VowelStyle::DEFAULT)]
-    vowels: VowelStyle,
+    vowels: Option<VowelStyle>,
 
     /// Do not use inverted "nuquerna" variants.
     ///
@@ -131,9 +359,9 @@ struct StyleFlags {
     ///     diacritic would be placed. When one of these tengwar needs to have a
     ///     vowel, it is often inverted to make room; This option prevents that,
     ///     as some typefaces can handle it well.
-    #[arg(long = "no-nuquernar", short = 'n')]
-    #[arg(action = clap::ArgAction::SetFalse)]
-    nuquerna: bool,
+    #[arg(long = "no-nuquernar", short = 'n', value_name = "BOOL")]
+    #[arg(num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    no_nuquerna: Option<bool>,
 }
 
 
@@ -145,7 +373,13 @@ struct StyleFlags {
 ///     available for selection by command line options.
 #[derive(Debug, Parser)]
 #[command(version, max_term_width(100))]
+#[command(args_conflicts_with_subcommands = true)]
 struct Command {
+    /// Perform an action other than transliteration.
+    #[command(subcommand)]
+    #[cfg(any(feature = "mode-custom", feature = "font-coverage"))]
+    action: Option<Action>,
+
     /// Text to be transliterated.
     ///
     /// If this is not provided, Standard Input will be used instead.
@@ -156,8 +390,9 @@ struct Command {
     ligate_all: bool,
 
     /// Use the ligated short carrier where applicable.
-    #[arg(long, short = 's')]
-    ligate_short: bool,
+    #[arg(long, short = 's', value_name = "BOOL")]
+    #[arg(num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    ligate_short: Option<bool>,
 
     /// Use zero-width joiners to ligate output.
     ///
@@ -175,6 +410,100 @@ struct Command {
     #[arg(action = clap::ArgAction::Count)]
     ligate_zwj: u8,
 
+    /// Allow spans of text to be transliterated in other modes.
+    ///
+    /// A span is marked with the name of a mode, such as `{q:namárië}`, and
+    ///     may use any name or alias accepted by `--mode`. Spans may be nested.
+    ///     A delimiter that follows a backslash is not treated as markup.
+    ///
+    /// The syntax may be changed by giving three characters: The start of a
+    ///     span, the separator after the name, and the end of a span. For
+    ///     example, `--markup="[|]"` marks spans like `[q|namárië]`.
+    #[arg(long, value_name = "SYNTAX")]
+    #[arg(num_args = 0..=1, require_equals = true, default_missing_value = "{:}")]
+    markup: Option<Syntax>,
+
+    /// Read inline directives from the text, such as `{+alt_a}`.
+    ///
+    /// A directive changes a style option for the rest of the text, without
+    ///     dividing the word it is written in. Anything between braces that
+    ///     is not a valid directive is written unchanged. Without this option,
+    ///     braces are transliterated as text.
+    #[arg(long)]
+    directives: bool,
+
+    /// Set the typeface that the output will be displayed in.
+    ///
+    /// Fonts differ in the ligatures and variants that they support. Selecting
+    ///     a font uses only the forms it supports, and changes the default
+    ///     style of the output to suit it; Any other style option given will
+    ///     still be applied, and a switch may be given a value to turn off a
+    ///     style of the font, such as `--alt-rince=false`. A font in the Dan
+    ///     Smith layout also changes the default `--encoding`.
+    #[arg(long, value_name = "NAME", conflicts_with = "explain")]
+    #[arg(value_enum, ignore_case = true)]
+    font: Option<Font>,
+
+    /// Load the typographic rules of a font from a TOML file.
+    ///
+    /// The file defines the ligatures, nuquernë variants and sa-rinci that a
+    ///     font supports, and may extend the rules of a built-in `--font`. The
+    ///     rules replace those of any `--font` that is also selected, but the
+    ///     style of that font is still used.
+    #[arg(long = "policy-file", value_name = "PATH", conflicts_with = "explain")]
+    #[cfg(feature = "policy-custom")]
+    policy_file: Option<PathBuf>,
+
+    /// Set the block of codepoints used for the Tengwar in the output.
+    ///
+    /// By default, the Tengwar are written in the Private Use Area, using the
+    ///     codepoints of the ConScript Unicode Registry, which are supported by
+    ///     the fonts of the Free Tengwar Font Project. They may instead be
+    ///     written in the block of the Supplementary Multilingual Plane that
+    ///     has been proposed for them, or in the ASCII layout of the fonts of
+    ///     Dan Smith, such as Tengwar Annatar.
+    #[arg(long, value_name = "ENCODING")]
+    #[arg(value_enum, ignore_case = true)]
+    encoding: Option<Encoding>,
+
+    /// Convert text already written in the Tengwar into the `--encoding`,
+    ///     rather than transliterating it.
+    ///
+    /// The input may be written in either block, or in a mixture of both. Any
+    ///     character that is not one of the Tengwar is left unchanged.
+    #[arg(long, conflicts_with_all = ["markup", "explain"])]
+    recode: bool,
+
+    /// Set the encoding of the input to `--recode`.
+    ///
+    /// Text in either block is recognized without this option, but text in the
+    ///     Dan Smith layout cannot be told apart from ASCII, and must be
+    ///     selected explicitly; Plain text on the same keys is then converted
+    ///     as well.
+    #[arg(long, value_name = "ENCODING", requires = "recode")]
+    #[arg(value_enum, ignore_case = true)]
+    from: Option<Encoding>,
+
+    /// Report details of transliteration to Standard Error.
+    ///
+    /// The mode is reported first. If the mode is automatic, the language that
+    ///     is detected for each part of the text is also reported, with the
+    ///     score of its features; A positive score favors Sindarin, and a
+    ///     negative score favors Quenya.
+    #[arg(long, short = 'v')]
+    verbose: bool,
+
+    /// Explain how each part of the output was produced, on Standard Error.
+    ///
+    /// For each token of the output, a table shows the input it was read from,
+    ///     the codepoints it was rendered as, and a description of the token.
+    ///     Any changes made to the token after it was read are listed, followed
+    ///     by every step the mode took while reading it. The table does not
+    ///     follow the rules of a `--font` or `--policy-file`, so neither may be
+    ///     given with it.
+    #[arg(long)]
+    explain: bool,
+
     /// Options for determining output style.
     #[command(flatten, next_help_heading = "Style")]
     style_flags: StyleFlags,
@@ -190,49 +519,129 @@ struct Command {
 }
 
 impl Command {
-    const fn mode(&self) -> Mode {
-        let ModeFlags {
-            quenya,
-            gondor,
-            beleriand,
-            /*english,*/
-            /*language,*/
-            by_name,
-        } = self.mode_flags;
-
-        if let Some(mode) = by_name {
-            mode
-        /*} else if let Some(lang) = language {
-            lang.mode()*/
+    const fn language(&self) -> Option<Language> {
+        let ModeFlags { quenya, sindarin, english, by_language, .. } = self.mode_flags;
+
+        if let Some(lang) = by_language {
+            Some(lang)
         } else if quenya {
-            Mode::Classical
+            Some(Language::Quenya)
+        } else if sindarin {
+            Some(Language::Sindarin)
+        } else if english {
+            Some(Language::English)
+        } else {
+            None
+        }
+    }
+
+    fn mode(&self) -> Result<Mode, clap::Error> {
+        let ModeFlags { gondor, beleriand, by_name, .. } = self.mode_flags;
+
+        let mode: Option<Mode> = if let Some(mode) = by_name {
+            Some(mode)
         } else if gondor {
-            Mode::Gondor
+            Some(Mode::Gondor)
         } else if beleriand {
-            Mode::Beleriand
-        /*} else if english {
-            Mode::English*/
+            Some(Mode::Beleriand)
         } else {
-            Mode::DEFAULT
+            None
+        };
+
+        match (self.language(), mode) {
+            (None, None) => Ok(Mode::DEFAULT),
+            (None, Some(mode)) => Ok(mode),
+            (Some(lang), None) => Ok(Mode::of_language(lang)),
+            (Some(lang), Some(mode)) if lang.supports(&mode.name()) => Ok(mode),
+            (Some(lang), Some(mode)) => Err(<Self as clap::CommandFactory>::command().error(
+                clap::error::ErrorKind::ArgumentConflict,
+                format!(
+                    "the mode `{}` cannot be used for {lang:?}; \
+                    the modes used for {lang:?} are: {}",
+                    mode.name(),
+                    lang.modes().join(", "),
+                ),
+            )),
         }
     }
 
-    const fn runner(&self) -> Runner {
-        Runner::new(self.mode(), self.settings())
+    fn runner(&self) -> Result<Runner, clap::Error> {
+        #[allow(unused_mut)]
+        let mut runner = Runner::new(self.mode()?, self.settings());
+
+        #[cfg(feature = "mode-custom")]
+        if let Some(path) = &self.mode_flags.mode_file {
+            let name: String = path.display().to_string();
+            runner = runner.with_custom(name, load_mode_file(path)?.resolve());
+        }
+
+        if let Some(syntax) = self.markup {
+            runner = runner.with_markup(syntax);
+        }
+
+        if self.directives {
+            runner = runner.with_directives();
+        }
+
+        if let Some(source) = self.from {
+            runner = runner.with_source(source);
+        }
+
+        if let Some(font) = self.font {
+            runner = runner.with_font(font);
+        }
+
+        #[cfg(feature = "policy-custom")]
+        if let Some(path) = &self.policy_file {
+            runner = runner.with_policy(load_policy_file(path)?);
+        }
+
+        Ok(runner.with_encoding(self.encoding()))
+    }
+
+    fn encoding(&self) -> Encoding {
+        match (self.encoding, self.font) {
+            (Some(encoding), _) => encoding,
+            (None, Some(font)) => font.encoding(),
+            (None, None) => Encoding::DEFAULT,
+        }
     }
 
     const fn settings(&self) -> TranscriberSettings {
+        let base: TranscriberSettings = match self.font {
+            Some(font) => font.settings(),
+            None => TranscriberSettings { nuquerna: true, ..TranscriberSettings::new() },
+        };
+
+        //  A switch that is not given keeps the setting of the font.
+        const fn or(flag: Option<bool>, base: bool) -> bool {
+            match flag {
+                Some(flag) => flag,
+                None => base,
+            }
+        }
+
         TranscriberSettings {
-            alt_a: self.style_flags.alt_a,
-            alt_rince: self.style_flags.alt_rince,
-            dot_plain: self.style_flags.dot_plain,
-            elide_a: self.style_flags.elide_a,
-            keep_a_init: false,
-            keep_a_long: false,
-            ligate_short: self.ligate_all || self.ligate_short,
-            ligate_zwj: if self.ligate_all { u8::MAX } else { self.ligate_zwj },
-            nuquerna: self.style_flags.nuquerna,
-            vowels: self.style_flags.vowels,
+            alt_a: or(self.style_flags.alt_a, base.alt_a),
+            alt_rince: or(self.style_flags.alt_rince, base.alt_rince),
+            dot_plain: or(self.style_flags.dot_plain, base.dot_plain),
+            elide_a: or(self.style_flags.elide_a, base.elide_a),
+            keep_a_init: base.keep_a_init,
+            keep_a_long: base.keep_a_long,
+            ligate_short: self.ligate_all || or(self.ligate_short, base.ligate_short),
+            ligate_zwj: match (self.ligate_all, self.ligate_zwj) {
+                (true, _) => u8::MAX,
+                (false, 0) => base.ligate_zwj,
+                (false, level) => level,
+            },
+            nuquerna: match self.style_flags.no_nuquerna {
+                Some(no_nuquerna) => !no_nuquerna,
+                None => base.nuquerna,
+            },
+            vowels: match self.style_flags.vowels {
+                Some(vowels) => vowels,
+                None => base.vowels,
+            },
         }
     }
 }
@@ -240,26 +649,71 @@ impl Command {
 
 fn main() {
     let command: Command = clap::Parser::parse();
-    let runner = command.runner();
+
+    #[cfg(any(feature = "mode-custom", feature = "font-coverage"))]
+    if let Some(action) = &command.action {
+        let success = action.run(&mut stdout()).unwrap_or_else(|err| err.exit());
+        exit(!success as i32);
+    }
+
+    let runner = command.runner().unwrap_or_else(|err| err.exit());
 
     #[cfg(debug_assertions)]
     if command.debug {
-        let mode = command.mode();
+        let language = command.language();
+        let mode = command.mode().unwrap_or_else(|err| err.exit());
         let settings = command.settings();
-        dbg!(command, mode, settings);
+        dbg!(command, language, mode, settings);
         exit(0);
     }
 
+    if command.verbose {
+        eprintln!("mode: {}", runner.name);
+    }
+
     if command.text.is_empty() {
-        for line in stdin().lock().lines() {
-            if let Ok(text) = line {
-                let conv: String = runner.convert(text);
+        for text in stdin().lock().lines().map_while(Result::ok) {
+            if command.recode {
+                println!("{}", runner.recode(text));
+                continue;
+            }
+
+            for error in runner.check(&text) {
+                eprintln!("warning: {error}");
+            }
 
-                println!("{}", conv);
+            if command.verbose {
+                runner.report(&text, &mut stderr()).ok();
             }
+
+            if command.explain {
+                runner.explain(&text, &mut stderr()).ok();
+            }
+
+            let conv: String = runner.convert(text);
+
+            println!("{}", conv);
         }
     } else {
         let text: String = command.text.join(" ");
+
+        if command.recode {
+            print!("{}", runner.recode(text));
+            exit(stdout().write(b"\n").is_err() as i32);
+        }
+
+        for error in runner.check(&text) {
+            eprintln!("warning: {error}");
+        }
+
+        if command.verbose {
+            runner.report(&text, &mut stderr()).ok();
+        }
+
+        if command.explain {
+            runner.explain(&text, &mut stderr()).ok();
+        }
+
         let conv: String = runner.convert(text);
 
         print!("{}", conv);
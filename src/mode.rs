@@ -5,34 +5,56 @@
 //!     common functionality. The [`Tokenizer`] iterator takes input text and
 //!     uses the rules of a mode to construct [`Token`]s.
 //!
-//! There are three modes currently implemented by default: [`Quenya`],
-//!     [`Gondor`], and [`Beleriand`].
+//! There are five modes currently implemented by default: [`Quenya`],
+//!     [`Gondor`], [`GondorFull`], and [`Beleriand`], as well as an
+//!     orthographic [`General`] mode for English.
+//!
+//! Modes may also be selected by name at runtime, through a [`ModeRegistry`],
+//!     or by the [`Language`] they are used to write.
+//!
+//! The [`Markup`] mode allows spans of text to be marked for transcription in
+//!     other modes, such as a phrase of Quenya in a passage of Sindarin.
+//!
+//! The [`Auto`] mode does not have rules of its own; Instead, it detects whether
+//!     text is written in Quenya or in Sindarin, and uses the primary mode of
+//!     the language.
 
+pub mod auto;
 pub mod beleriand;
 #[cfg(feature = "mode-custom")]
 pub mod custom;
-// pub mod general;
+pub mod general;
 pub mod gondor;
+pub mod gondor_full;
+pub mod language;
+pub mod markup;
 pub mod quenya;
+pub mod registry;
+pub mod table;
 
 mod tests;
 mod tokenizer;
 
+pub use auto::Auto;
 pub use beleriand::Beleriand;
 #[cfg(feature = "mode-custom")]
 pub use custom::CustomMode;
-// pub use general::General;
+pub use general::General;
 pub use gondor::Gondor;
+pub use gondor_full::GondorFull;
+pub use language::Language;
+pub use markup::Markup;
 pub use quenya::Quenya;
+pub use registry::{Directed, DynMode, ModeRegistry};
 pub use tokenizer::Tokenizer;
 
-use crate::{Numeral, Token, Transcriber};
+use crate::{policy::{Policy, Standard}, Glyph, Numeral, Token, Transcriber};
 
 
 /// The result of a call to [`TengwarMode::process`]. This specifies the next
 ///     action that will be taken by a [`Tokenizer`].
 #[derive(Clone, Debug)]
-pub enum ParseAction {
+pub enum ParseAction<P: Policy = Standard> {
     /// Nothing could be done with the input.
     MatchedNone,
     /// A portion of the input was processed successfully, but more is needed.
@@ -40,7 +62,7 @@ pub enum ParseAction {
     /// A complete [`Token`] has been processed.
     MatchedToken {
         /// The completed [`Token`].
-        token: Token,
+        token: Token<P>,
         /// The number of [`char`]s that were processed during the final step of
         ///     parsing this Token.
         len: usize,
@@ -61,11 +83,11 @@ pub enum ParseAction {
     },
 }
 
-impl ParseAction {
+impl<P: Policy> ParseAction<P> {
     /// The behavior of a simple backslash escape sequence.
     pub const ESC_BACKSLASH: Self = Self::Escape { len_seq: 1, n_skip: 1 };
 
-    pub const fn matched_opt(token: Option<Token>, len: usize) -> Self {
+    pub const fn matched_opt(token: Option<Token<P>>, len: usize) -> Self {
         match token {
             Some(token) => Self::MatchedToken { token, len },
             None => Self::MatchedPart(len),
@@ -74,9 +96,85 @@ impl ParseAction {
 }
 
 
+/// The [`Token`]s around one that is being finalized by a [`Tokenizer`]. The
+///     number of tokens in each direction is set by [`TengwarMode::LOOKBEHIND`]
+///     and [`TengwarMode::LOOKAHEAD`], but there may be fewer near the start or
+///     end of the text.
+///
+/// Any [`Directive`](crate::Directive) among the tokens is skipped, so that it
+///     does not count toward the tokens in either direction. A word is a run of
+///     [`Glyph`]s, and a directive between them does not end the word. Because
+///     the tokens are limited, the edges of a long word may be out of view.
+#[derive(Clone, Copy, Debug)]
+pub struct Neighbors<'n, P: Policy = Standard> {
+    /// The tokens before, in the order of the text, after they were finalized.
+    ///     The last of these is immediately before the current token.
+    pub before: &'n [Token<P>],
+    /// The tokens after, in the order of the text, before they are finalized.
+    ///     The first of these is immediately after the current token.
+    pub after: &'n [Token<P>],
+}
+
+impl<'n, P: Policy> Neighbors<'n, P> {
+    /// Return the token `n` places before the current one, where zero is the
+    ///     token immediately before it.
+    pub fn prev(&self, n: usize) -> Option<&'n Token<P>> {
+        Self::skip_directives(self.before.iter().rev()).nth(n)
+    }
+
+    /// Return the token `n` places after the current one, where zero is the
+    ///     token immediately after it.
+    pub fn next(&self, n: usize) -> Option<&'n Token<P>> {
+        Self::skip_directives(self.after.iter()).nth(n)
+    }
+
+    /// Return the glyphs of the same word before the current token, nearest
+    ///     first.
+    pub fn word_before(&self) -> impl Iterator<Item=&'n Glyph<P>> {
+        Self::word(self.before.iter().rev())
+    }
+
+    /// Return the glyphs of the same word after the current token, nearest
+    ///     first.
+    pub fn word_after(&self) -> impl Iterator<Item=&'n Glyph<P>> {
+        Self::word(self.after.iter())
+    }
+
+    /// Return `true` if no glyph of the same word comes before the current
+    ///     token.
+    pub fn word_initial(&self) -> bool {
+        self.word_before().next().is_none()
+    }
+
+    /// Return `true` if no glyph of the same word comes after the current
+    ///     token.
+    pub fn word_final(&self) -> bool {
+        self.word_after().next().is_none()
+    }
+
+    /// Take the glyphs of a word from a sequence of tokens, skipping over any
+    ///     directives.
+    fn word(tokens: impl Iterator<Item=&'n Token<P>>) -> impl Iterator<Item=&'n Glyph<P>> {
+        Self::skip_directives(tokens).map_while(Token::glyph)
+    }
+
+    /// Skip over any directives in a sequence of tokens.
+    fn skip_directives(
+        tokens: impl Iterator<Item=&'n Token<P>>,
+    ) -> impl Iterator<Item=&'n Token<P>> {
+        tokens.filter(|token| !matches!(token, Token::Directive(_)))
+    }
+}
+
+
 /// This trait defines a "mode" of transcription of text into the Tengwar. It
 ///     implements methods that receive slices of [`char`]s and progressively
 ///     construct [`Token`]s held in internal state.
+///
+/// The methods that produce `Token`s are generic over a [`Policy`], which is
+///     chosen by the [`Tokenizer`]. A mode should consult the `Policy` for any
+///     decision that depends on the typeface, such as whether a tengwa may
+///     take a sa-rincë.
 #[allow(unused_variables)]
 pub trait TengwarMode {
     /// This is the maximum size for a "chunk" of [`char`]s passed to
@@ -84,6 +182,15 @@ pub trait TengwarMode {
     ///     be attempted before skipping a `char` and moving on.
     const MAX_CHUNK: usize = 3;
 
+    /// The number of finalized [`Token`]s that are kept, to be shown to
+    ///     [`Self::finalize_in`] as the tokens before the current one.
+    const LOOKBEHIND: usize = 0;
+
+    /// The number of [`Token`]s that are read in advance, to be shown to
+    ///     [`Self::finalize_in`] as the tokens after the current one. If this
+    ///     is zero, [`Self::finalize`] will never see a following token.
+    const LOOKAHEAD: usize = 1;
+
     /// Set up a [`Transcriber`] over the characters of an input string, and
     ///     immediately collect it into the target type.
     fn transcribe<T: FromIterator<Token>>(input: impl AsRef<str>) -> T
@@ -110,7 +217,16 @@ pub trait TengwarMode {
     /// Perform any last-minute modifications to a [`Token`] that may be needed
     ///     upon finding out what the following `Token` will be. By default,
     ///     this method is a no-op.
-    fn finalize(&self, token: &mut Token, next: Option<&Token>) {}
+    fn finalize<P: Policy>(&self, token: &mut Token<P>, next: Option<&Token<P>>) {}
+
+    /// Perform any last-minute modifications to a [`Token`], in light of the
+    ///     [`Neighbors`] around it. This allows rules that depend on more than
+    ///     the following `Token`, such as the position of a glyph in its word.
+    ///
+    /// By default, this method calls [`Self::finalize`] with the next `Token`.
+    fn finalize_in<P: Policy>(&self, token: &mut Token<P>, neighbors: &Neighbors<P>) {
+        self.finalize(token, neighbors.next(0));
+    }
 
     /// Try to parse a slice of characters into an "index" of a sequence. This
     ///     special case of a numeral is intended for use in enumerated lists.
@@ -143,7 +259,7 @@ pub trait TengwarMode {
     //  TODO: Should the methods called here be included directly? The default
     //      impls are already direct passthroughs. Is anything really gained
     //      downstream from keeping them as separate methods?
-    fn find_secondary(&mut self, slice: &[char]) -> Option<(Token, usize)> {
+    fn find_secondary<P: Policy>(&mut self, slice: &[char]) -> Option<(Token<P>, usize)> {
         //  Check for a sequential index.
         if let Some((char, len)) = self.find_index(slice) {
             Some((Token::Char(char), len))
@@ -170,7 +286,7 @@ pub trait TengwarMode {
     ///     end of its data.
     ///
     /// [`next`]: Iterator::next
-    fn finish_current(&mut self) -> Option<Token>;
+    fn finish_current<P: Policy>(&mut self) -> Option<Token<P>>;
 
     /// Process a slice of [`char`]s, and return a [`ParseAction`] indicating
     ///     the new state of the conversion in progress.
@@ -179,5 +295,18 @@ pub trait TengwarMode {
     ///     be no more than [`MAX_CHUNK`] in length, but it may be shorter.
     ///
     /// [`MAX_CHUNK`]: Self::MAX_CHUNK
-    fn process(&mut self, chunk: &[char]) -> ParseAction;
+    fn process<P: Policy>(&mut self, chunk: &[char]) -> ParseAction<P>;
+
+    /// Process a slice of [`char`]s in the same way as [`Self::process`], under
+    ///     the value of the [`Policy`] given to the [`Tokenizer`], if there is
+    ///     one. A mode that consults the policy while it builds a [`Glyph`],
+    ///     such as to decide whether it can take a sa-rincë, should do so here.
+    ///
+    /// The `Tokenizer` sets its policy on every `Glyph` once it is built, so
+    ///     by default, this method calls [`Self::process`].
+    ///
+    /// [`Glyph`]: crate::Glyph
+    fn process_in<P: Policy>(&mut self, chunk: &[char], policy: Option<P>) -> ParseAction<P> {
+        self.process(chunk)
+    }
 }
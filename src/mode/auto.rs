@@ -0,0 +1,347 @@
+//! This module defines the [`Auto`] mode, which detects whether each part of
+//!     its input is written in Quenya or in Sindarin, and transcribes it with
+//!     the [primary mode](Language::primary_mode) of that language.
+//!
+//! Detection is done by scoring phonotactic features that are found in only
+//!     one of the two languages, most of which are also reflected by the tables
+//!     of their modes. For example, Quenya has no `dh` or `ph`, and the voiced
+//!     stops `b`, `d` and `g` are found only after a nasal or liquid; Sindarin,
+//!     on the other hand, has no `qu`, and uses `y` as a vowel. These features
+//!     are heuristics, and some words have none of them; Such a word is given
+//!     the language of the text around it.
+//!
+//! Because this mode dispatches to other modes, it does not implement
+//!     [`TengwarMode`], but it does implement [`DynMode`].
+//!
+//! # Example
+//! ```
+//! use tengwar::{Gondor, Quenya, TengwarMode, TranscriberSettings};
+//! use tengwar::mode::{auto::{detect, Auto}, DynMode, Language};
+//!
+//! assert_eq!(detect("namárië"), Some(Language::Quenya));
+//! assert_eq!(detect("Ennyn Durin"), Some(Language::Sindarin));
+//! assert_eq!(detect("aran"), None);
+//!
+//! let text: String = Auto::default().transcribe_with(
+//!     "namárië Ennyn Durin",
+//!     TranscriberSettings::new(),
+//! );
+//! assert_eq!(text, format!(
+//!     "{}{}",
+//!     Quenya::transcribe::<String>("namárië "),
+//!     Gondor::transcribe::<String>("Ennyn Durin"),
+//! ));
+//! ```
+
+use std::ops::Range;
+use unicode_normalization::UnicodeNormalization;
+use crate::trace::SharedTrace;
+use super::{registry::BoxedSpanned, *};
+#[cfg(feature = "policy-custom")]
+use {super::registry::BoxedTokenizer, crate::policy::data::DataPolicy};
+
+
+/// Return `true` if a lowercase character is a vowel, in either language.
+const fn is_vowel(c: char) -> bool {
+    matches!(c,
+        'a' | 'e' | 'i' | 'o' | 'u' | 'y'
+        | 'á' | 'é' | 'í' | 'ó' | 'ú' | 'ý'
+        | 'â' | 'ê' | 'î' | 'ô' | 'û' | 'ŷ'
+        | 'ä' | 'ë' | 'ï' | 'ö' | 'ü' | 'ÿ'
+        | 'ā' | 'ē' | 'ī' | 'ō' | 'ū' | 'ȳ'
+        | 'æ' | 'œ'
+    )
+}
+
+
+/// Score a single word by its phonotactic features. A positive score is
+///     evidence for Sindarin, and a negative score is evidence for Quenya. A
+///     score of zero means that the word could be either.
+///
+/// Any inline [`Directive`](crate::Directive) in the word is ignored.
+pub fn score_word(word: &str) -> i32 {
+    let mut chars: Vec<char> = Vec::new();
+    let mut rest: &str = word;
+
+    while let Some(c) = rest.chars().next() {
+        match crate::Directive::find_str(rest) {
+            Some(len) => rest = &rest[len..],
+            None => {
+                chars.extend(c.to_lowercase());
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    let chars: Vec<char> = chars.into_iter().nfc().collect();
+    let elided: bool = matches!(chars.last(), Some('\'' | '’'));
+    let letters: Vec<char> = chars.into_iter().filter(|c| c.is_alphabetic()).collect();
+    let mut score: i32 = 0;
+
+    for (i, &c) in letters.iter().enumerate() {
+        let prev: Option<char> = i.checked_sub(1).map(|j| letters[j]);
+        let next: Option<char> = letters.get(i + 1).copied();
+
+        score += match c {
+            //  Vowels and consonants found only in the tables of Sindarin.
+            'æ' | 'œ' | 'ð' | 'â' | 'ê' | 'î' | 'ô' | 'û' | 'ŷ' | 'ý' | 'ÿ' | 'ȳ' => 3,
+
+            //  Y is a vowel in Sindarin, but only a consonant in Quenya.
+            'y' => match next {
+                Some(n) if is_vowel(n) => match prev {
+                    Some('t' | 'n' | 'l' | 'h') => -2,
+                    _ => 0,
+                },
+                _ => 3,
+            },
+
+            //  Quenya does not have voiced stops, except after a nasal or a
+            //      liquid.
+            'b' if prev != Some('m') => 2,
+            'd' if !matches!(prev, Some('n' | 'l' | 'r')) => 2,
+            'g' if prev != Some('n') => 2,
+
+            //  Spirants and voiceless sonorants of Sindarin.
+            'h' if matches!(prev, Some('c' | 'd' | 'l' | 'm' | 'p' | 'r')) => 2,
+
+            //  Letters used only in Quenya.
+            'q' => -3,
+            'w' if matches!(prev, Some('c' | 'k')) => -2,
+            'k' | 'x' | 'z' => -1,
+            's' if prev == Some('t') => -1,
+
+            //  A diaeresis is common in Quenya, especially on a final E.
+            'ë' if next.is_none() => -3,
+            'ë' => -1,
+
+            _ => 0,
+        };
+
+        //  Diphthongs found in only one of the languages.
+        score += match (c, next) {
+            ('a' | 'o', Some('e')) | ('e', Some('i')) => 2,
+            ('o', Some('i')) | ('e' | 'i', Some('u')) => -2,
+            _ => 0,
+        };
+    }
+
+    //  Quenya words end in a vowel or in a single dental or liquid, unless a
+    //      final vowel has been elided. A doubled consonant is not counted,
+    //      since the elision of a vowel after it is often left unmarked, as in
+    //      "lúmenn(a)".
+    if !elided {
+        score += match letters.as_slice() {
+            [.., last] if !is_vowel(*last)
+                && !matches!(last, 'n' | 'l' | 'r' | 's' | 't') => 2,
+            [.., a, b] if !is_vowel(*a) && !is_vowel(*b) && a != b
+                && !matches!((a, b), ('n', 't') | ('t', 's')) => 2,
+            _ => 0,
+        };
+    }
+
+    score
+}
+
+
+/// Score a section of text, by adding the scores of all of its words.
+pub fn score(text: &str) -> i32 {
+    text.split_whitespace().map(score_word).sum()
+}
+
+
+/// Detect whether a section of text is written in Quenya or in Sindarin.
+///     Returns `None` if there is no evidence either way.
+pub fn detect(text: &str) -> Option<Language> {
+    detect_score(score(text))
+}
+
+
+/// The size of the sections of text that are each given a language.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Unit {
+    /// Each word is given a language.
+    #[default]
+    Word,
+    /// Each line is given a language.
+    Line,
+}
+
+
+/// A section of input text which has been given a language.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Segment {
+    /// The range of bytes of the input covered by this segment.
+    pub range: Range<usize>,
+    /// The language of the segment.
+    pub language: Language,
+    /// The score of the segment. If this is zero, the language was not detected
+    ///     from the segment itself, but given by the text around it.
+    pub score: i32,
+}
+
+
+/// A mode which detects, for each word or line of its input, whether it is
+///     written in Quenya or in Sindarin, and uses the primary mode of that
+///     language.
+#[derive(Clone, Copy, Debug)]
+pub struct Auto {
+    /// The size of the sections of text that are each given a language.
+    pub unit: Unit,
+    /// The language used when there is no evidence in the text at all.
+    pub fallback: Language,
+}
+
+impl Auto {
+    pub const fn new(unit: Unit) -> Self {
+        Self { unit, fallback: Language::Quenya }
+    }
+
+    /// Split text into [`Segment`]s, each in a single language. Together, the
+    ///     segments cover all of the input, and any text between two words or
+    ///     lines belongs to the earlier segment.
+    pub fn segments(&self, text: &str) -> Vec<Segment> {
+        //  Find the start of each section, and its score.
+        let mut sections: Vec<(usize, i32)> = Vec::new();
+
+        match self.unit {
+            Unit::Word => {
+                let mut in_word: bool = false;
+
+                for (idx, c) in text.char_indices() {
+                    if c.is_whitespace() {
+                        in_word = false;
+                    } else if !in_word {
+                        in_word = true;
+                        sections.push((idx, 0));
+                    }
+                }
+            }
+            Unit::Line => {
+                let mut start: usize = 0;
+
+                for line in text.split_inclusive('\n') {
+                    sections.push((start, 0));
+                    start += line.len();
+                }
+            }
+        }
+
+        if let Some(first) = sections.first_mut() {
+            first.0 = 0;
+        }
+
+        for i in 0..sections.len() {
+            let end: usize = sections.get(i + 1).map_or(text.len(), |s| s.0);
+            sections[i].1 = score(&text[sections[i].0..end]);
+        }
+
+        //  A section with no evidence takes the language of the section before
+        //      it, or at the start, the first language that is detected.
+        let mut language: Language = sections.iter()
+            .find_map(|&(_, s)| detect_score(s))
+            .unwrap_or(self.fallback);
+        let mut segments: Vec<Segment> = Vec::new();
+
+        for (i, &(start, score)) in sections.iter().enumerate() {
+            let end: usize = sections.get(i + 1).map_or(text.len(), |s| s.0);
+            language = detect_score(score).unwrap_or(language);
+
+            match segments.last_mut() {
+                Some(last) if last.language == language => {
+                    last.range.end = end;
+                    last.score += score;
+                }
+                _ => segments.push(Segment { range: start..end, language, score }),
+            }
+        }
+
+        segments
+    }
+}
+
+impl Default for Auto {
+    fn default() -> Self { Self::new(Unit::default()) }
+}
+
+impl DynMode for Auto {
+    fn boxed_spanned(&self, input: &str) -> BoxedSpanned {
+        self.spanned(input, None, false)
+    }
+
+    fn boxed_traced(&self, input: &str, trace: &SharedTrace) -> BoxedSpanned {
+        self.spanned(input, Some(trace), false)
+    }
+
+    fn boxed_directed(&self, input: &str, trace: Option<&SharedTrace>) -> BoxedSpanned {
+        self.spanned(input, trace, true)
+    }
+
+    #[cfg(feature = "policy-custom")]
+    fn boxed_tokenizer_in(
+        &self,
+        input: &str,
+        policy: DataPolicy,
+        directives: bool,
+    ) -> BoxedTokenizer<DataPolicy> {
+        self.chain(input, |mode, range| {
+            mode.boxed_tokenizer_in(&input[range], policy, directives)
+        })
+    }
+}
+
+impl Auto {
+    /// Chain together the tokens of every segment, each read by the primary
+    ///     mode of its language from the range of the input that it covers.
+    fn chain<T: 'static>(
+        &self,
+        input: &str,
+        read: impl Fn(&dyn DynMode, Range<usize>) -> Box<dyn Iterator<Item=T>>,
+    ) -> Box<dyn Iterator<Item=T>> {
+        let registry = ModeRegistry::new();
+        let tokenizers: Vec<Box<dyn Iterator<Item=T>>> = self.segments(input)
+            .into_iter()
+            .map(|segment| {
+                let mode = registry.get(segment.language.primary_mode())
+                    .expect("primary mode of language is not registered");
+
+                read(mode.as_ref(), segment.range)
+            })
+            .collect();
+
+        Box::new(tokenizers.into_iter().flatten())
+    }
+
+    /// Chain together the tokens of every segment, each in its own mode.
+    fn spanned(
+        &self,
+        input: &str,
+        trace: Option<&SharedTrace>,
+        directives: bool,
+    ) -> BoxedSpanned {
+        self.chain(input, |mode, range| {
+            let start: usize = range.start;
+            let chars: usize = input[..start].chars().count();
+            let text: &str = &input[range];
+
+            let spanned: BoxedSpanned = match trace {
+                _ if directives => mode.boxed_directed(text, trace),
+                Some(trace) => mode.boxed_traced(text, trace),
+                None => mode.boxed_spanned(text),
+            };
+
+            Box::new(spanned.map(move |(token, span)| (token, span.shift(start, chars))))
+        })
+    }
+}
+
+
+/// Convert a score into a language, if it is not zero.
+const fn detect_score(score: i32) -> Option<Language> {
+    if score < 0 {
+        Some(Language::Quenya)
+    } else if score > 0 {
+        Some(Language::Sindarin)
+    } else {
+        None
+    }
+}
@@ -1,5 +1,5 @@
-use crate::{characters::*, Token};
-use super::{ParseAction, TengwarMode};
+use crate::{characters::*, policy::Policy, Token};
+use super::{table::{lookup, table, Table}, Neighbors, ParseAction, TengwarMode};
 
 
 /// Tengwa for a consonantal initial I.
@@ -16,61 +16,62 @@ pub const VOWEL_U: char = TENGWA_URE;
 pub const VOWEL_Y: char = TENGWA_SILME_NUQ;
 
 
+pub const CONSONANTS: Table<char> = table! {
+    //  Tincotéma.
+    ['t']           /**/ => TEMA_TINCO.single_dn,
+    ['d']           /**/ => TEMA_TINCO.double_dn,
+    ['t', 'h']
+    | ['θ'] | ['þ'] /**/ => TEMA_TINCO.single_up,
+    ['d', 'h']
+    | ['ð']         /**/ => TEMA_TINCO.double_up,
+    ['n', 'n']      /**/ => TEMA_TINCO.double_sh,
+    ['n']           /**/ => TEMA_TINCO.single_sh,
+
+    //  Parmatéma.
+    ['p']           /**/ => TEMA_PARMA.single_dn,
+    ['b']           /**/ => TEMA_PARMA.double_dn,
+    ['p', 'h']
+    | ['φ']         /**/ => TEMA_PARMA.single_up,
+    ['v']           /**/ => TEMA_PARMA.double_up,
+    ['m', 'm']      /**/ => TEMA_PARMA.double_sh,
+    ['m']           /**/ => TEMA_PARMA.single_sh,
+
+    //  Calmatéma.
+    ['c'] | ['k']   /**/ => TEMA_CALMA.single_dn,
+    ['g']           /**/ => TEMA_CALMA.double_dn,
+    ['c', 'h']
+    | ['k', 'h']    /**/ => TEMA_CALMA.single_up,
+    ['g', 'h']      /**/ => TEMA_CALMA.double_up,
+    ['n', 'g']
+    | ['ñ']         /**/ => TEMA_CALMA.double_sh,
+    // []           /**/ => TEMA_CALMA.single_sh, // Vowel O.
+
+    //  Qessetéma.
+    // []           /**/ => TEMA_QESSE.single_dn,
+    // []           /**/ => TEMA_QESSE.double_dn,
+    // []           /**/ => TEMA_QESSE.single_up,
+    // []           /**/ => TEMA_QESSE.double_up,
+    // []           /**/ => TEMA_QESSE.double_sh,
+    ['w']           /**/ => TEMA_QESSE.single_sh,
+
+    //  Irregulars.
+    ['l']           /**/ => TENGWA_LAMBE,
+    ['r']           /**/ => TENGWA_ROMEN,
+    // ['l', 'h']      /**/ => TENGWA_ALDA, // NOTE: Only for initials.
+    // ['r', 'h']      /**/ => TENGWA_ARDA,
+    ['m', 'h']      /**/ => TENGWA_VALA_HOOKED,
+    ['s']           /**/ => TENGWA_SILME,
+    ['s', 's']
+    | ['z'] | ['ß'] /**/ => TENGWA_ESSE,
+
+    ['h']           /**/ => TENGWA_HYARMEN,
+    ['h', 'w']      /**/ => TENGWA_HWESTA_SINDARINWA,
+    ['j']           /**/ => CONSONANT_I,
+};
+
+
 pub const fn consonant_char(slice: &[char]) -> Option<char> {
-    Some(match slice {
-        //  Tincotéma.
-        ['t']           /**/ => TEMA_TINCO.single_dn,
-        ['d']           /**/ => TEMA_TINCO.double_dn,
-        ['t', 'h']
-        | ['θ'] | ['þ'] /**/ => TEMA_TINCO.single_up,
-        ['d', 'h']
-        | ['ð']         /**/ => TEMA_TINCO.double_up,
-        ['n', 'n']      /**/ => TEMA_TINCO.double_sh,
-        ['n']           /**/ => TEMA_TINCO.single_sh,
-
-        //  Parmatéma.
-        ['p']           /**/ => TEMA_PARMA.single_dn,
-        ['b']           /**/ => TEMA_PARMA.double_dn,
-        ['p', 'h']
-        | ['φ']         /**/ => TEMA_PARMA.single_up,
-        ['v']           /**/ => TEMA_PARMA.double_up,
-        ['m', 'm']      /**/ => TEMA_PARMA.double_sh,
-        ['m']           /**/ => TEMA_PARMA.single_sh,
-
-        //  Calmatéma.
-        ['c'] | ['k']   /**/ => TEMA_CALMA.single_dn,
-        ['g']           /**/ => TEMA_CALMA.double_dn,
-        ['c', 'h']
-        | ['k', 'h']    /**/ => TEMA_CALMA.single_up,
-        ['g', 'h']      /**/ => TEMA_CALMA.double_up,
-        ['n', 'g']
-        | ['ñ']         /**/ => TEMA_CALMA.double_sh,
-        // []           /**/ => TEMA_CALMA.single_sh, // Vowel O.
-
-        //  Qessetéma.
-        // []           /**/ => TEMA_QESSE.single_dn,
-        // []           /**/ => TEMA_QESSE.double_dn,
-        // []           /**/ => TEMA_QESSE.single_up,
-        // []           /**/ => TEMA_QESSE.double_up,
-        // []           /**/ => TEMA_QESSE.double_sh,
-        ['w']           /**/ => TEMA_QESSE.single_sh,
-
-        //  Irregulars.
-        ['l']           /**/ => TENGWA_LAMBE,
-        ['r']           /**/ => TENGWA_ROMEN,
-        // ['l', 'h']      /**/ => TENGWA_ALDA, // NOTE: Only for initials.
-        // ['r', 'h']      /**/ => TENGWA_ARDA,
-        ['m', 'h']      /**/ => TENGWA_VALA_HOOKED,
-        ['s']           /**/ => TENGWA_SILME,
-        ['s', 's']
-        | ['z'] | ['ß'] /**/ => TENGWA_ESSE,
-
-        ['h']           /**/ => TENGWA_HYARMEN,
-        ['h', 'w']      /**/ => TENGWA_HWESTA_SINDARINWA,
-        ['j']           /**/ => CONSONANT_I,
-
-        _ => { return None; }
-    })
+    lookup(CONSONANTS, slice)
 }
 
 
@@ -82,41 +83,43 @@ pub const fn get_consonant(slice: &[char]) -> Option<Glyph> {
 }
 
 
-pub const fn get_diphthong(slice: &[char]) -> Option<Glyph> {
-    match slice {
-        // ['a', 'e'] | ['æ'] => Some(Glyph::new_both(VOWEL_A, TEHTA_YANTA)),
-        // ['o', 'e'] | ['œ'] => Some(Glyph::new_both(VOWEL_O, TEHTA_YANTA)),
+pub const DIPHTHONGS: Table<Glyph> = table! {
+    // ['a', 'e'] | ['æ'] => Glyph::new_both(VOWEL_A, TEHTA_YANTA),
+    // ['o', 'e'] | ['œ'] => Glyph::new_both(VOWEL_O, TEHTA_YANTA),
 
-        ['a', 'i'] => Some(Glyph::new_both(VOWEL_A, TEHTA_Y)),
-        ['e', 'i'] => Some(Glyph::new_both(VOWEL_E, TEHTA_Y)),
-        ['u', 'i'] => Some(Glyph::new_both(VOWEL_U, TEHTA_Y)),
+    ['a', 'i'] => Glyph::new_both(VOWEL_A, TEHTA_Y),
+    ['e', 'i'] => Glyph::new_both(VOWEL_E, TEHTA_Y),
+    ['u', 'i'] => Glyph::new_both(VOWEL_U, TEHTA_Y),
 
-        ['a', 'u']
-        | ['a', 'w'] => Some(Glyph::new_base(VOWEL_A).with_labial(true)),
+    ['a', 'u']
+    | ['a', 'w'] => Glyph::new_base(VOWEL_A).with_labial(true),
+};
 
-        _ => None,
-    }
+
+pub const fn get_diphthong(slice: &[char]) -> Option<Glyph> {
+    lookup(DIPHTHONGS, slice)
 }
 
 
+pub const VOWELS: Table<Glyph> = table! {
+    ['a'] | ['ä'] => Glyph::new_base(VOWEL_A),
+    ['e'] | ['ë'] => Glyph::new_base(VOWEL_E),
+    ['i'] | ['ï'] => Glyph::new_base(VOWEL_I),
+    ['o'] | ['ö'] => Glyph::new_base(VOWEL_O),
+    ['u'] | ['ü'] => Glyph::new_base(VOWEL_U),
+    ['y'] | ['ÿ'] => Glyph::new_base(VOWEL_Y),
+
+    ['á'] | ['â'] | ['ā'] | ['a', 'a'] => Glyph::new_both(VOWEL_A, ANDAITH),
+    ['é'] | ['ê'] | ['ē'] | ['e', 'e'] => Glyph::new_both(VOWEL_E, ANDAITH),
+    ['í'] | ['î'] | ['ī'] | ['i', 'i'] => Glyph::new_both(VOWEL_I, ANDAITH),
+    ['ó'] | ['ô'] | ['ō'] | ['o', 'o'] => Glyph::new_both(VOWEL_O, ANDAITH),
+    ['ú'] | ['û'] | ['ū'] | ['u', 'u'] => Glyph::new_both(VOWEL_U, ANDAITH),
+    ['ý'] | ['ŷ'] | ['ȳ'] | ['y', 'y'] => Glyph::new_both(VOWEL_Y, ANDAITH),
+};
+
+
 pub const fn get_vowel(slice: &[char]) -> Option<Glyph> {
-    Some(match slice {
-        ['a'] | ['ä'] => Glyph::new_base(VOWEL_A),
-        ['e'] | ['ë'] => Glyph::new_base(VOWEL_E),
-        ['i'] | ['ï'] => Glyph::new_base(VOWEL_I),
-        ['o'] | ['ö'] => Glyph::new_base(VOWEL_O),
-        ['u'] | ['ü'] => Glyph::new_base(VOWEL_U),
-        ['y'] | ['ÿ'] => Glyph::new_base(VOWEL_Y),
-
-        ['á'] | ['â'] | ['ā'] | ['a', 'a'] => Glyph::new_both(VOWEL_A, ANDAITH),
-        ['é'] | ['ê'] | ['ē'] | ['e', 'e'] => Glyph::new_both(VOWEL_E, ANDAITH),
-        ['í'] | ['î'] | ['ī'] | ['i', 'i'] => Glyph::new_both(VOWEL_I, ANDAITH),
-        ['ó'] | ['ô'] | ['ō'] | ['o', 'o'] => Glyph::new_both(VOWEL_O, ANDAITH),
-        ['ú'] | ['û'] | ['ū'] | ['u', 'u'] => Glyph::new_both(VOWEL_U, ANDAITH),
-        ['ý'] | ['ŷ'] | ['ȳ'] | ['y', 'y'] => Glyph::new_both(VOWEL_Y, ANDAITH),
-
-        _ => { return None; }
-    })
+    lookup(VOWELS, slice)
 }
 
 
@@ -139,49 +142,29 @@ pub struct Beleriand {
 }
 
 impl Beleriand {
-    pub fn decide_f(next: &[char]) -> Glyph {
-        let mut mode = Self::default();
-        let mut is_final: bool = true;
-        let mut n: usize = next.len();
-
-        while 0 < n {
-            match mode.process(&next[..n]) {
-                ParseAction::MatchedNone => {
-                    //  Next token is unknown.
-                    n -= 1;
-                }
-                ParseAction::MatchedPart(_) => {
-                    //  Next token is a glyph.
-                    is_final = false;
-                    break;
-                }
-                ParseAction::MatchedToken { token: Token::Glyph(_), .. } => {
-                    //  Next token is a glyph.
-                    is_final = false;
-                    break;
-                }
-                _ => {
-                    //  Next token is NOT a glyph.
-                    is_final = true;
-                    break;
-                }
-            }
-        }
-
-        let phonetic: &[char] = if is_final { &['v'] } else { &['p', 'h'] };
-        consonant_char(phonetic).unwrap().into()
+    /// Return the glyph of an F, which is written as "ph" unless it ends a
+    ///     word, where it is written as "v".
+    pub fn consonant_f() -> Glyph {
+        let medial: Glyph = consonant_char(&['p', 'h']).unwrap().into();
+        medial.with_base_final(consonant_char(&['v']).unwrap())
     }
 }
 
 impl TengwarMode for Beleriand {
-    fn finish_current(&mut self) -> Option<Token> {
+    fn finalize_in<P: Policy>(&self, token: &mut Token<P>, neighbors: &Neighbors<P>) {
+        if let Token::Glyph(glyph) = token {
+            glyph.decide_final(neighbors.word_final());
+        }
+    }
+
+    fn finish_current<P: Policy>(&mut self) -> Option<Token<P>> {
         self.previous = self.current.take();
-        self.previous.map(Token::Glyph)
+        self.previous.map(|g| Token::Glyph(g.change_policy()))
     }
 
     //  TODO: Completely review this code; A significant mistake was found. It
     //      should probably be rebuilt from the ground up, directly from specs.
-    fn process(&mut self, chunk: &[char]) -> ParseAction {
+    fn process<P: Policy>(&mut self, chunk: &[char]) -> ParseAction<P> {
         macro_rules! finish {
             ($glyph:expr) => {finish!($glyph, 0)};
             ($glyph:expr, $len:expr) => {{
@@ -192,10 +175,11 @@ impl TengwarMode for Beleriand {
         }
         macro_rules! output {
             ($glyph:expr, $len:expr) => {{
-                self.previous = Some($glyph);
+                let glyph: Glyph = $glyph;
+                self.previous = Some(glyph);
 
                 ParseAction::MatchedToken {
-                    token: Token::Glyph($glyph),
+                    token: Token::Glyph(glyph.change_policy()),
                     len: $len,
                 }
             }};
@@ -205,7 +189,7 @@ impl TengwarMode for Beleriand {
 
         if let [ESC, ESC_NOP, ..] = chunk {
             self.previous = None;
-            ParseAction::matched_opt(self.current.take().map(Token::Glyph), 2)
+            ParseAction::matched_opt(self.current.take().map(|g| Token::Glyph(g.change_policy())), 2)
         } else if let [ESC, _, ..] = chunk {
             ParseAction::ESC_BACKSLASH
         } else if let Some(current) = &mut self.current {
@@ -234,9 +218,10 @@ impl TengwarMode for Beleriand {
                 finish!(Glyph::new_base(TENGWA_ARDA), 2)
             }
 
-            //  Check for F, and decide whether it is final.
-            else if let ['f', ahead @ ..]  = chunk {
-                finish!(Self::decide_f(ahead), 1)
+            //  Check for F. Whether it is final is decided when the glyph is
+            //      finalized.
+            else if let ['f', ..] = chunk {
+                finish!(Self::consonant_f(), 1)
             }
 
             //  Check for a consonant.
@@ -1,14 +1,21 @@
+pub mod base;
 pub mod file;
+pub mod vector;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use crate::characters::*;
 use super::*;
+use base::BaseMode;
 use file::*;
 
 
 /// A runtime-defined mode of the Tengwar.
 #[derive(Clone, Debug)]
 pub struct CustomMode {
+    /// A built-in mode whose tables are used for anything not found in the
+    ///     tables of this mode.
+    pub extends: Option<BaseMode>,
+
     pub chunks: usize,
     pub vowels_first: bool,
 
@@ -16,103 +23,399 @@ pub struct CustomMode {
     pub checks_new: Vec<Check>,
 
     pub consonants: HashMap<Vec<char>, GlyphSpec>,
+    pub diphthongs: HashMap<Vec<char>, GlyphSpec>,
     pub vowels: HashMap<Vec<char>, TehtaSpec>,
+    pub sequences: Vec<Sequence>,
+
+    pub rince: Vec<Modifier>,
+    pub labial: Vec<Modifier>,
+    pub nasal: Vec<Modifier>,
+    pub palatal: Vec<Modifier>,
+
+    pub replacements: Vec<Replacement>,
+    pub rules: Vec<Rule>,
 
     current: Option<Glyph>,
     previous: Option<Glyph>,
+    /// Glyphs of a [`Sequence`] that have been found, but not yet returned.
+    pending: VecDeque<Glyph>,
+    /// Whether the window has narrowed since the last match. Sequences are
+    ///     only checked in the widest window, where they can look ahead.
+    narrowed: bool,
 }
 
 impl CustomMode {
     pub fn get_consonant(&self, chunk: &[char]) -> Option<Glyph> {
-        let new: &GlyphSpec = self.consonants.get(chunk)?;
-        Some(Glyph::from(*new))
+        match self.consonants.get(chunk) {
+            Some(new) => Some(Glyph::from(*new)),
+            None => self.extends?.get_consonant(chunk),
+        }
     }
 
-    pub fn get_diphthong(&self, _chunk: &[char]) -> Option<Glyph> {
-        todo!()
+    pub fn get_diphthong(&self, chunk: &[char]) -> Option<Glyph> {
+        match self.diphthongs.get(chunk) {
+            Some(new) => Some(Glyph::from(*new)),
+            None => self.extends?.get_diphthong(chunk),
+        }
     }
 
     pub fn get_vowel(&self, chunk: &[char]) -> Option<Glyph> {
-        let new: &TehtaSpec = self.vowels.get(chunk)?;
-        Some(Glyph::from(GlyphSpec::from(*new)))
+        match self.vowels.get(chunk) {
+            Some(new) => Some(Glyph::from(GlyphSpec::from(*new))),
+            None => self.extends?.get_vowel(chunk),
+        }
+    }
+
+    /// Try to find a nasalized consonant. The chunk must begin with the pattern
+    ///     of a nasal [`Modifier`], and the remainder must be a consonant.
+    pub fn get_nasal(&self, chunk: &[char], ctx: &Context) -> Option<Glyph> {
+        self.nasal.iter()
+            .filter(|nasal| nasal.valid.allows(ctx))
+            .find_map(|nasal| {
+                let rest = chunk.strip_prefix(nasal.chars().as_slice())?;
+                let new: Glyph = self.get_consonant(rest)?;
+                Some(new.with_nasal(true))
+            })
+    }
+
+    /// Return `true` if the chunk represents a vowel or a diphthong.
+    pub fn is_vowel(&self, chunk: &[char]) -> bool {
+        self.get_vowel(chunk).is_some() || self.get_diphthong(chunk).is_some()
+    }
+
+    /// Find a [`Sequence`] at the start of the chunk, which may be used in the
+    ///     given context. The rest of the chunk decides whether the sequence is
+    ///     followed by another glyph, or by a vowel.
+    pub fn get_sequence(&self, chunk: &[char], ctx: Context) -> Option<(Vec<Glyph>, usize)> {
+        let starts = |rest: &[char], vowel: bool| (1..=rest.len()).any(|n| {
+            self.is_vowel(&rest[..n]) || (!vowel && self.get_consonant(&rest[..n]).is_some())
+        });
+
+        self.sequences.iter()
+            .filter(|seq| !seq.pattern.is_empty() && !seq.glyphs.is_empty())
+            .find_map(|seq| {
+                let pattern: Vec<char> = seq.chars();
+                let rest: &[char] = chunk.strip_prefix(pattern.as_slice())?;
+                let ctx = Context {
+                    word_final: Some(!starts(rest, false)),
+                    before_vowel: Some(starts(rest, true)),
+                    ..ctx
+                };
+
+                seq.valid.allows(&ctx).then(|| (seq.glyphs(), pattern.len()))
+            })
+    }
+
+    /// Return `true` if the glyph represents only a vowel or a diphthong, with
+    ///     no consonant.
+    pub fn is_vowel_glyph(&self, glyph: &Glyph) -> bool {
+        glyph.base.is_none()
+            || self.extends.is_some_and(|base| base.is_vowel_glyph(glyph))
+            || self.diphthongs.values().any(|diph| {
+                diph.tengwa == glyph.base
+                    && diph.tehta.map(|t| t.base) == glyph.tehta.map(|t| t.base)
+            })
+    }
+
+    /// Return `true` if the first sound of the glyph is a vowel.
+    fn starts_with_vowel(&self, glyph: &Glyph) -> bool {
+        self.is_vowel_glyph(glyph) || (self.vowels_first && glyph.tehta.is_some())
+    }
+
+    /// Return `true` if the last sound of the glyph is a vowel.
+    fn ends_with_vowel(&self, glyph: &Glyph) -> bool {
+        self.is_vowel_glyph(glyph) || (!self.vowels_first && glyph.tehta.is_some())
+    }
+
+    /// Find the context of a modifier, applied to the glyph currently being
+    ///     constructed.
+    fn context_mod(&self, current: &Glyph) -> Context {
+        Context {
+            after_consonant: current.base.is_some(),
+            after_vowel: current.tehta.is_some(),
+            word_initial: self.previous.is_none(),
+            word_final: None,
+            before_vowel: None,
+        }
+    }
+
+    /// Find the context of a new glyph, after the previous one.
+    fn context_new(&self) -> Context {
+        Context {
+            after_consonant: self.previous.is_some_and(|g| !self.ends_with_vowel(&g)),
+            after_vowel: self.previous.is_some_and(|g| self.ends_with_vowel(&g)),
+            word_initial: self.previous.is_none(),
+            word_final: None,
+            before_vowel: None,
+        }
+    }
+
+    /// Start a series of new glyphs. Every glyph but the last is finished
+    ///     immediately, and the last one is finished as well if it is a vowel
+    ///     that cannot be followed by its consonant.
+    fn start<P: Policy>(&mut self, glyphs: Vec<Glyph>, len: usize) -> ParseAction<P> {
+        let mut glyphs: VecDeque<Glyph> = glyphs.into();
+
+        if let Some(last) = glyphs.pop_back() {
+            if last.base.is_none() && !self.vowels_first {
+                //  Nothing can be added to a vowel that is not followed by its
+                //      consonant. Finish it now.
+                glyphs.push_back(last);
+            } else {
+                self.current = Some(last);
+            }
+        }
+
+        match glyphs.pop_front() {
+            Some(first) => {
+                self.pending = glyphs;
+                self.previous = Some(first);
+
+                ParseAction::MatchedToken {
+                    token: Token::Glyph(first.change_policy()),
+                    len,
+                }
+            }
+            None => ParseAction::MatchedPart(len),
+        }
+    }
+
+    /// Find the context of a finished glyph, between two other tokens.
+    fn context_rule(
+        &self,
+        glyph: &Glyph,
+        prev: Option<&Glyph>,
+        next: Option<&Glyph>,
+    ) -> Context {
+        let before_vowel = if self.vowels_first || glyph.tehta.is_none() {
+            next.is_some_and(|g| self.starts_with_vowel(g))
+        } else {
+            true
+        };
+
+        Context {
+            after_consonant: prev.is_some_and(|g| !self.ends_with_vowel(g)),
+            after_vowel: prev.is_some_and(|g| self.ends_with_vowel(g)),
+            word_initial: prev.is_none(),
+            word_final: Some(next.is_none()),
+            before_vowel: Some(before_vowel),
+        }
     }
 }
 
 impl TengwarMode for CustomMode {
-    fn finish_current(&mut self) -> Option<Token> {
-        self.previous = self.current.take();
-        self.previous.map(Token::Glyph)
+    const LOOKBEHIND: usize = 1;
+
+    fn finalize_in<P: Policy>(&self, token: &mut Token<P>, neighbors: &Neighbors<P>) {
+        let prev: Option<Glyph> = neighbors.prev(0)
+            .and_then(Token::glyph)
+            .map(Glyph::change_policy);
+        let next: Option<&Token<P>> = neighbors.next(0);
+
+        if let Token::Glyph(glyph) = token {
+            //  Rules are defined in terms of the standard policy.
+            let mut new: Glyph = glyph.change_policy();
+            new.tehta_first = self.vowels_first;
+
+            let next: Option<Glyph> = next.and_then(Token::glyph).map(Glyph::change_policy);
+            let ctx: Context = self.context_rule(&new, prev.as_ref(), next.as_ref());
+
+            for rule in &self.rules {
+                rule.apply(&mut new, &ctx, next.as_ref());
+            }
+
+            *glyph = new.change_policy();
+        }
+    }
+
+    fn finish_current<P: Policy>(&mut self) -> Option<Token<P>> {
+        self.narrowed = false;
+        self.previous = self.pending.pop_front().or_else(|| self.current.take());
+        self.previous.map(|g| Token::Glyph(g.change_policy()))
     }
 
-    fn process(&mut self, chunk: &[char]) -> ParseAction {
-        macro_rules! _finish {
-            ($glyph:expr) => {finish!($glyph, 0)};
-            ($glyph:expr, $len:expr) => {{
-                let glyph = $glyph;
+    fn process<P: Policy>(&mut self, chunk: &[char]) -> ParseAction<P> {
+        self.process_in(chunk, None)
+    }
 
-                self.current = None;
-                self.previous = Some(glyph);
+    fn process_in<P: Policy>(&mut self, chunk: &[char], policy: Option<P>) -> ParseAction<P> {
+        macro_rules! replace {
+            ($old:expr, $new:expr, $len:expr) => {{
+                let finished = $old;
+                self.current = $new;
+                self.previous = Some(finished);
 
                 ParseAction::MatchedToken {
-                    token: Token::Glyph(glyph),
+                    token: Token::Glyph(finished.change_policy()),
                     len: $len,
                 }
             }};
         }
 
-        let _initial: bool = self.previous.is_none();
+        //  Sequences are only checked in the widest window, since a narrower one
+        //      would hide what follows them.
+        let narrowed: bool = std::mem::take(&mut self.narrowed);
+
+        if let Some(pending) = self.pending.pop_front() {
+            //  Finish the glyphs of a sequence before reading any further.
+            self.previous = Some(pending);
 
-        if let [ESC, ESC_NOP, ..] = chunk {
+            ParseAction::MatchedToken {
+                token: Token::Glyph(pending.change_policy()),
+                len: 0,
+            }
+        } else if let [ESC, ESC_NOP, ..] = chunk {
             self.previous = None;
-            ParseAction::matched_opt(self.current.take().map(Token::Glyph), 2)
+            ParseAction::matched_opt(self.current.take().map(|g| Token::Glyph(g.change_policy())), 2)
         } else if let [ESC, _, ..] = chunk {
             ParseAction::ESC_BACKSLASH
-        } else if let Some(_current) = &mut self.current {
+        } else if let Some(mut current) = self.current {
             //  A glyph is currently being constructed. Try to continue it.
+
+            //  A sequence of one consonant can be added to a glyph that was
+            //      started by a vowel.
+            if current.base.is_none() && !narrowed {
+                let ctx = Context { after_vowel: current.tehta.is_some(), ..Default::default() };
+
+                if let Some((glyphs, len)) = self.get_sequence(chunk, ctx) {
+                    if let [new] = glyphs[..] {
+                        if new.tehta.is_none() {
+                            current.integrate_consonant(new);
+                            self.current = Some(current);
+                            return ParseAction::MatchedPart(len);
+                        }
+                    }
+                }
+            }
+
             for check in &self.checks_mod {
                 match check {
-                    Check::Consonant => todo!(),
-                    Check::Diphthong => todo!(),
+                    Check::Consonant => {
+                        //  Only a glyph that was started by a vowel can still
+                        //      take a consonant.
+                        if current.base.is_none() {
+                            if let Some(new) = self.get_consonant(chunk) {
+                                current.integrate_consonant(new);
+                                self.current = Some(current);
+                                return ParseAction::MatchedPart(chunk.len());
+                            }
+                        }
+                    }
+                    Check::Diphthong => {
+                        //  A diphthong cannot be placed on a consonant, but it
+                        //      can follow one, becoming a new glyph.
+                        if current.tehta.is_none() && !self.vowels_first {
+                            if let Some(new) = self.get_diphthong(chunk) {
+                                return replace!(current, Some(new), chunk.len());
+                            }
+                        }
+                    }
                     Check::Vowel => {
-                        if let Some(new) = self.get_vowel(chunk) {
-                            self.current.as_mut().unwrap().integrate_vowel(new);
+                        if current.tehta.is_none() {
+                            if let Some(new) = self.get_vowel(chunk) {
+                                current.integrate_vowel(new);
+                                self.current = Some(current);
+                                return ParseAction::MatchedPart(chunk.len());
+                            }
+                        }
+                    }
+
+                    Check::Rince => {
+                        if current.with_policy(policy).can_take_rince()
+                            && find_modifier(&self.rince, &self.context_mod(&current), chunk)
+                        {
+                            current.rince = true;
+                            self.current = Some(current);
+                            return ParseAction::MatchedPart(chunk.len());
+                        }
+                    }
+                    Check::Labial => {
+                        if !current.labial
+                            && find_modifier(&self.labial, &self.context_mod(&current), chunk)
+                        {
+                            current.labial = true;
+                            self.current = Some(current);
+                            return ParseAction::MatchedPart(chunk.len());
+                        }
+                    }
+                    Check::Nasal => {
+                        //  As with any other consonant, a nasalized consonant
+                        //      can only be added to a glyph started by a vowel.
+                        if current.base.is_none() {
+                            if let Some(new) = self.get_nasal(chunk, &self.context_mod(&current)) {
+                                current.integrate_consonant(new);
+                                self.current = Some(current);
+                                return ParseAction::MatchedPart(chunk.len());
+                            }
+                        }
+                    }
+                    Check::Palatal => {
+                        if !current.palatal
+                            && find_modifier(&self.palatal, &self.context_mod(&current), chunk)
+                        {
+                            current.palatal = true;
+                            self.current = Some(current);
                             return ParseAction::MatchedPart(chunk.len());
                         }
                     }
 
-                    Check::Rince => todo!(),
-                    Check::Labial => todo!(),
-                    Check::Nasal => todo!(),
-                    Check::Palatal => todo!(),
+                    Check::Replacements => {
+                        //  A following vowel may change the base tengwa. This
+                        //      does not consume anything, so later checks will
+                        //      still find the vowel.
+                        if current.tehta.is_none() && self.is_vowel(chunk) {
+                            for rep in &self.replacements {
+                                current.replace_base(rep.old, rep.new);
+                            }
 
-                    Check::Replacements => todo!(),
+                            self.current = Some(current);
+                        }
+                    }
                 }
             }
 
+            self.narrowed = true;
             ParseAction::MatchedNone
         } else {
-            //  Try to find a new glyph.
+            //  Try to find a new glyph, or a sequence of them.
+            if !narrowed {
+                if let Some((glyphs, len)) = self.get_sequence(chunk, self.context_new()) {
+                    return self.start(glyphs, len);
+                }
+            }
+
             for check in &self.checks_new {
-                match check {
-                    Check::Consonant => {
-                        if let Some(new) = self.get_consonant(chunk) {
-                            self.current = Some(new);
-                            return ParseAction::MatchedPart(chunk.len());
-                        }
-                    }
-                    Check::Diphthong => todo!(),
-                    Check::Vowel => todo!(),
+                let new: Option<Glyph> = match check {
+                    Check::Consonant => self.get_consonant(chunk),
+                    Check::Nasal => self.get_nasal(chunk, &Context {
+                        word_initial: self.previous.is_none(),
+                        ..Default::default()
+                    }),
+                    Check::Diphthong => self.get_diphthong(chunk),
+                    Check::Vowel => self.get_vowel(chunk),
 
-                    Check::Rince => todo!(),
-                    Check::Labial => todo!(),
-                    Check::Nasal => todo!(),
-                    Check::Palatal => todo!(),
+                    //  These checks modify an existing glyph, and there is no
+                    //      glyph to modify.
+                    Check::Rince
+                    | Check::Labial
+                    | Check::Palatal
+                    | Check::Replacements => None,
+                };
 
-                    Check::Replacements => todo!(),
+                if let Some(new) = new {
+                    return self.start(vec![new], chunk.len());
                 }
             }
 
+            self.narrowed = true;
             ParseAction::MatchedNone
         }
     }
 }
+
+
+/// Return `true` if the chunk matches any of the [`Modifier`]s that may be
+///     applied in the given context.
+fn find_modifier(modifiers: &[Modifier], ctx: &Context, chunk: &[char]) -> bool {
+    modifiers.iter().any(|m| m.valid.allows(ctx) && m.chars() == chunk)
+}
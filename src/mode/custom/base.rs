@@ -0,0 +1,337 @@
+use std::collections::BTreeMap;
+use crate::{
+    characters::*,
+    mode::{beleriand, gondor, quenya, table::{lookup, table, Table}},
+};
+use super::file::*;
+
+
+/// Consonants of the Classical Mode that are written with modifiers.
+const QUENYA_SPECIAL: Table<Glyph> = table! {
+    ['x'] => Glyph::new_base(TENGWA_CALMA).with_rince(true),
+    ['y'] => Glyph::new_base(TENGWA_ANNA).with_palatal(true),
+};
+
+/// Consonants of the Mode of Gondor that are written with modifiers, or that
+///     are decided by a [`Sequence`] where the table cannot decide them.
+const GONDOR_SPECIAL: Table<Glyph> = table! {
+    ['x'] => Glyph::new_base(TENGWA_QESSE).with_rince(true),
+    ['f'] => Glyph::new_base(TENGWA_FORMEN),
+};
+
+/// Consonants of the Mode of Beleriand that are decided by a [`Sequence`]
+///     where the table cannot decide them.
+const BELERIAND_SPECIAL: Table<Glyph> = table! {
+    ['f'] => Glyph::new_base(TENGWA_FORMEN),
+};
+
+
+/// A built-in mode, which may be extended by a [`ModeFile`]. Any sequence of
+///     input that the file does not define is looked up in the tables of the
+///     built-in mode instead.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BaseMode {
+    /// The [Classical Mode](crate::Quenya).
+    #[serde(alias = "classical")]
+    Quenya,
+    /// The [Mode of Gondor](crate::Gondor).
+    Gondor,
+    /// The [Mode of Beleriand](crate::Beleriand).
+    Beleriand,
+}
+
+/// Public: Table lookups.
+impl BaseMode {
+    pub const fn get_consonant(&self, chunk: &[char]) -> Option<Glyph> {
+        if let Some(glyph) = lookup(self.special_consonants(), chunk) {
+            return Some(glyph);
+        }
+
+        match self {
+            Self::Quenya => quenya::get_consonant(chunk),
+            Self::Gondor => gondor::get_consonant(chunk),
+            Self::Beleriand => beleriand::get_consonant(chunk),
+        }
+    }
+
+    pub const fn get_diphthong(&self, chunk: &[char]) -> Option<Glyph> {
+        match self {
+            Self::Quenya => quenya::get_diphthong(chunk),
+            Self::Gondor => gondor::get_diphthong(chunk),
+            Self::Beleriand => beleriand::get_diphthong(chunk),
+        }
+    }
+
+    pub const fn get_vowel(&self, chunk: &[char]) -> Option<Glyph> {
+        match self {
+            Self::Quenya => match quenya::get_tehta(chunk) {
+                Some((tehta, alt)) => Some(Glyph::new_vowel(tehta, alt)),
+                None => None,
+            }
+            Self::Gondor => gondor::get_vowel(chunk),
+            Self::Beleriand => beleriand::get_vowel(chunk),
+        }
+    }
+
+    /// Return `true` if the glyph represents only a vowel or a diphthong. In
+    ///     the Mode of Beleriand, this is a glyph with one of the vowel tengwar
+    ///     as its base.
+    pub const fn is_vowel_glyph(&self, glyph: &Glyph) -> bool {
+        match self {
+            Self::Quenya => glyph.base.is_none() || (glyph.tehta.is_some() && matches!(
+                glyph.base,
+                Some(quenya::CARRIER_DIPH_I | quenya::CARRIER_DIPH_U),
+            )),
+            Self::Gondor => glyph.base.is_none() || (glyph.tehta.is_some() && matches!(
+                glyph.base,
+                Some(gondor::CARRIER_DIPH_E | gondor::CARRIER_DIPH_I | gondor::CARRIER_DIPH_U),
+            )),
+            Self::Beleriand => matches!(glyph.base, Some(
+                beleriand::VOWEL_A
+                | beleriand::VOWEL_E
+                | beleriand::VOWEL_I
+                | beleriand::VOWEL_O
+                | beleriand::VOWEL_U
+                | beleriand::VOWEL_Y
+            )),
+        }
+    }
+}
+
+/// Public: Tables.
+impl BaseMode {
+    /// Return the table of consonants that are written with a single tengwa.
+    pub const fn consonants(&self) -> Table<char> {
+        match self {
+            Self::Quenya => quenya::CONSONANTS,
+            Self::Gondor => gondor::CONSONANTS,
+            Self::Beleriand => beleriand::CONSONANTS,
+        }
+    }
+
+    /// Return the table of consonants that are written with modifiers. These
+    ///     take precedence over the [main table](Self::consonants).
+    pub const fn special_consonants(&self) -> Table<Glyph> {
+        match self {
+            Self::Quenya => QUENYA_SPECIAL,
+            Self::Gondor => GONDOR_SPECIAL,
+            Self::Beleriand => BELERIAND_SPECIAL,
+        }
+    }
+
+    pub const fn diphthongs(&self) -> Table<Glyph> {
+        match self {
+            Self::Quenya => quenya::DIPHTHONGS,
+            Self::Gondor => gondor::DIPHTHONGS,
+            Self::Beleriand => beleriand::DIPHTHONGS,
+        }
+    }
+
+    /// Return `true` if a doubled consonant that is not in the tables should be
+    ///     written with a line below the single consonant.
+    pub const fn doubles_consonants(&self) -> bool {
+        match self {
+            Self::Quenya => true,
+            Self::Gondor => true,
+            Self::Beleriand => false,
+        }
+    }
+}
+
+/// Public: Default rules.
+impl BaseMode {
+    /// Return a [`ModeFile`] with the rules of the built-in mode, but with no
+    ///     tables of its own.
+    pub fn defaults(&self) -> ModeFile {
+        let after_bare_consonant = Position {
+            after_consonant: Allowance::Require,
+            after_vowel: Allowance::Forbid,
+            ..Default::default()
+        };
+        let after_consonant = Position {
+            after_consonant: Allowance::Require,
+            ..Default::default()
+        };
+        let initial = Position {
+            word_initial: Allowance::Require,
+            ..Default::default()
+        };
+        let medial = Position {
+            word_initial: Allowance::Forbid,
+            ..Default::default()
+        };
+        let modifier = |pattern: &str, valid: Position| Modifier {
+            pattern: pattern.into(),
+            valid,
+        };
+        let rule = |old: char, new: char, valid: Position| Rule {
+            old,
+            new,
+            valid,
+            before: Vec::new(),
+            tehta: Allowance::Allow,
+        };
+        let sequence = |pattern: &str, glyphs: &[char], valid: Position| Sequence {
+            pattern: pattern.into(),
+            glyphs: glyphs.iter().map(|&c| CharOr::Char(c)).collect(),
+            valid,
+        };
+        let final_ = Position {
+            word_final: Allowance::Require,
+            ..Default::default()
+        };
+        let initial_before_vowel = Position {
+            before_vowel: Allowance::Require,
+            ..initial
+        };
+
+        let mut file = ModeFile::new(Some(*self));
+
+        match self {
+            Self::Quenya => {
+                file.checks_mod = vec![
+                    Check::Palatal,
+                    Check::Rince,
+                    Check::Replacements,
+                    Check::Diphthong,
+                    Check::Vowel,
+                ];
+                file.checks_new = vec![Check::Consonant, Check::Diphthong, Check::Vowel];
+
+                file.palatal = vec![modifier("y", after_bare_consonant)];
+                file.rince = vec![
+                    modifier("s", after_bare_consonant),
+                    modifier("z", after_bare_consonant),
+                ];
+                file.replacements = vec![Replacement {
+                    old: TENGWA_ORE,
+                    new: TENGWA_ROMEN,
+                }];
+                file.rules = vec![
+                    rule(TENGWA_ANGA, TENGWA_NOLDO, initial),
+                    rule(TENGWA_UNGWE, TENGWA_NWALME, initial),
+                    rule(TENGWA_HYARMEN, TENGWA_AHA, medial),
+                    Rule {
+                        before: vec![TENGWA_LAMBE, TENGWA_ORE, TENGWA_ROMEN],
+                        tehta: Allowance::Forbid,
+                        ..rule(TENGWA_HYARMEN, TENGWA_HALLA, Default::default())
+                    },
+                ];
+            }
+            Self::Gondor => {
+                file.vowels_first = true;
+                file.checks_mod = vec![Check::Nasal, Check::Consonant, Check::Rince];
+                file.checks_new = vec![
+                    Check::Nasal,
+                    Check::Consonant,
+                    Check::Diphthong,
+                    Check::Vowel,
+                ];
+
+                file.nasal = vec![
+                    modifier("m", Default::default()),
+                    modifier("n", Default::default()),
+                ];
+                file.rince = vec![
+                    modifier("s", after_consonant),
+                    modifier("z", after_consonant),
+                ];
+                file.sequences = vec![
+                    sequence("lh", &[TENGWA_ALDA], initial),
+                    sequence("rh", &[TENGWA_ARDA], initial),
+                    sequence("mh", &[TENGWA_MALTA_HOOKED], Default::default()),
+                    sequence("f", &[TENGWA_AMPA], final_),
+                    sequence("i", &[gondor::CONSONANT_I], initial_before_vowel),
+                ];
+                file.rules = vec![rule(TENGWA_ORE, TENGWA_ROMEN, Position {
+                    word_final: Allowance::Forbid,
+                    ..Default::default()
+                })];
+            }
+            Self::Beleriand => {
+                file.checks_new = vec![
+                    Check::Consonant,
+                    Check::Nasal,
+                    Check::Diphthong,
+                    Check::Vowel,
+                ];
+
+                file.nasal = vec![
+                    modifier("m", Default::default()),
+                    modifier("n", Default::default()),
+                ];
+                file.sequences = vec![
+                    sequence("x", &[TENGWA_CALMA, TENGWA_SILME], Default::default()),
+                    sequence("æ", &[beleriand::VOWEL_A, beleriand::VOWEL_E], Default::default()),
+                    sequence("œ", &[beleriand::VOWEL_O, beleriand::VOWEL_E], Default::default()),
+                    sequence("lh", &[TENGWA_ALDA], initial),
+                    sequence("rh", &[TENGWA_ARDA], initial),
+                    sequence("f", &[TENGWA_AMPA], final_),
+                    sequence("i", &[beleriand::CONSONANT_I], initial_before_vowel),
+                ];
+            }
+        }
+
+        file
+    }
+
+    /// Return a standalone [`ModeFile`] with all of the tables and rules of the
+    ///     built-in mode, which does not extend it.
+    pub fn export(&self) -> ModeFile {
+        fn insert<T>(map: &mut BTreeMap<String, CharOr<T>>, key: &[char], value: CharOr<T>) {
+            map.entry(key.iter().collect()).or_insert(value);
+        }
+
+        let mut file = self.defaults();
+        file.extends = None;
+
+        let mut vowel = |key: &[char], glyph: Glyph| match glyph.tehta {
+            //  A vowel written only with a tehta.
+            Some(tehta) if glyph.base.is_none() => insert(
+                &mut file.vowels,
+                key,
+                TehtaSpec { long: glyph.tehta_alt, ..tehta.into() }.into(),
+            ),
+            //  A vowel written with a tengwa is treated like a diphthong.
+            _ => insert(&mut file.diphthongs, key, GlyphSpec::from(glyph).into()),
+        };
+
+        for &(key, glyph) in self.diphthongs() {
+            vowel(key, glyph);
+        }
+
+        match self {
+            Self::Quenya => for &(key, (tehta, long)) in quenya::TEHTAR {
+                vowel(key, Glyph::new_vowel(tehta, long));
+            }
+            Self::Gondor => for &(key, glyph) in gondor::VOWELS {
+                vowel(key, glyph);
+            }
+            Self::Beleriand => for &(key, glyph) in beleriand::VOWELS {
+                vowel(key, glyph);
+            }
+        }
+
+        for &(key, glyph) in self.special_consonants() {
+            insert(&mut file.consonants, key, GlyphSpec::from(glyph).into());
+        }
+
+        for &(key, tengwa) in self.consonants() {
+            insert(&mut file.consonants, key, CharOr::Char(tengwa));
+        }
+
+        if self.doubles_consonants() {
+            for &(key, tengwa) in self.consonants() {
+                if let &[c] = key {
+                    insert(&mut file.consonants, &[c, c], GlyphSpec {
+                        long_cons: true,
+                        ..tengwa.into()
+                    }.into());
+                }
+            }
+        }
+
+        file
+    }
+}
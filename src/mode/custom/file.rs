@@ -1,4 +1,14 @@
-use super::*;
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+    marker::PhantomData,
+    ops::Range,
+    path::Path,
+};
+use serde::de::{self, Deserializer, MapAccess, Unexpected, Visitor};
+use toml_edit::{ImDocument, Item, TableLike};
+use crate::characters::encoding::Encoding;
+use super::{*, base::BaseMode, vector::TestVector};
 
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
@@ -14,57 +24,213 @@ pub enum Check {
 }
 
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
 pub enum Allowance {
+    #[default]
     Allow,
     Forbid,
     Require,
 }
 
+impl Allowance {
+    const fn is_allow(&self) -> bool {
+        matches!(self, Self::Allow)
+    }
+
+    /// Return `true` if a condition with the given state is acceptable.
+    pub const fn check(self, state: bool) -> bool {
+        match self {
+            Self::Allow => true,
+            Self::Forbid => !state,
+            Self::Require => state,
+        }
+    }
+}
+
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct Replacement {
     pub old: char,
     pub new: char,
 }
 
 
+/// The surroundings of a glyph, as far as they are known.
+///
+/// For a [`Modifier`], "after" refers to the glyph being modified, since the
+///     modifier follows it. For a [`Rule`], it refers to the glyph before the
+///     one the rule is applied to.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Context {
+    pub after_consonant: bool,
+    pub after_vowel: bool,
+    /// Any token that is not a glyph marks the boundary of a word.
+    pub word_initial: bool,
+    /// This is not known until the following glyph has been found.
+    pub word_final: Option<bool>,
+    /// This is not known until the following glyph has been found.
+    pub before_vowel: Option<bool>,
+}
+
+
+/// Conditions on the surroundings of a glyph, which must be met for a rule to
+///     be applied to it.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Position {
+    #[serde(skip_serializing_if = "Allowance::is_allow")]
     pub after_consonant: Allowance,
+    #[serde(skip_serializing_if = "Allowance::is_allow")]
     pub after_vowel: Allowance,
+    #[serde(skip_serializing_if = "Allowance::is_allow")]
+    pub word_initial: Allowance,
+    #[serde(skip_serializing_if = "Allowance::is_allow")]
+    pub word_final: Allowance,
+    #[serde(skip_serializing_if = "Allowance::is_allow")]
+    pub before_vowel: Allowance,
+}
+
+impl Position {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Return `true` if a rule at this position may be applied in the given
+    ///     context. Conditions that are not yet known are not checked.
+    pub fn allows(&self, ctx: &Context) -> bool {
+        self.after_consonant.check(ctx.after_consonant)
+            && self.after_vowel.check(ctx.after_vowel)
+            && self.word_initial.check(ctx.word_initial)
+            && ctx.word_final.is_none_or(|b| self.word_final.check(b))
+            && ctx.before_vowel.is_none_or(|b| self.before_vowel.check(b))
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self {
+            after_consonant: Allowance::Allow,
+            after_vowel: Allowance::Allow,
+            word_initial: Allowance::Allow,
+            word_final: Allowance::Allow,
+            before_vowel: Allowance::Allow,
+        }
+    }
 }
 
 
+/// A sequence of input that modifies the glyph before it, such as by adding a
+///     sa-rincë or a palatal mark.
+///
+/// Modifiers are applied as the input is read, so the [`Position`] of a
+///     modifier can only be restricted by what comes before it. The
+///     `word_final` and `before_vowel` conditions are not checked.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct Modifier {
     pub pattern: String,
+    #[serde(default, skip_serializing_if = "Position::is_default")]
+    pub valid: Position,
+}
+
+impl Modifier {
+    /// Return the pattern of this modifier as a sequence of [`char`]s.
+    pub fn chars(&self) -> Vec<char> {
+        self.pattern.chars().collect()
+    }
+}
+
+
+/// A replacement of one tengwa with another, depending on the glyphs around
+///     it. Rules are applied in order, once the following glyph is known, so
+///     each rule sees the results of the rules before it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub old: char,
+    pub new: char,
+    #[serde(default, skip_serializing_if = "Position::is_default")]
+    pub valid: Position,
+
+    /// If this is not empty, the rule is only applied when the next glyph has
+    ///     one of these tengwar as its base. The next glyph will not yet have
+    ///     had any rules applied to it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub before: Vec<char>,
+
+    /// Whether the glyph itself may, must, or must not carry a tehta.
+    #[serde(default, skip_serializing_if = "Allowance::is_allow")]
+    pub tehta: Allowance,
+}
+
+impl Rule {
+    /// Apply this rule to a glyph, if its conditions are met.
+    pub fn apply(&self, glyph: &mut Glyph, ctx: &Context, next: Option<&Glyph>) {
+        let before_ok = self.before.is_empty() || next
+            .and_then(|next| next.base)
+            .is_some_and(|base| self.before.contains(&base));
+
+        if before_ok && self.tehta.check(glyph.tehta.is_some()) && self.valid.allows(ctx) {
+            glyph.replace_base(self.old, self.new);
+        }
+    }
+}
+
+
+/// A sequence of input that is written with a fixed series of glyphs, where
+///     its [`Position`] allows. Sequences are checked before the tables, for
+///     special cases that the tables cannot express, such as consonants that
+///     are written differently at the start or end of a word.
+///
+/// Unlike a [`Modifier`], a sequence may check every condition of its
+///     position. The `word_final` and `before_vowel` conditions are decided by
+///     looking ahead in the input, for the start of another glyph.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Sequence {
+    pub pattern: String,
+    pub glyphs: Vec<CharOr<GlyphSpec>>,
+    #[serde(default, skip_serializing_if = "Position::is_default")]
     pub valid: Position,
 }
 
+impl Sequence {
+    /// Return the pattern of this sequence as a series of [`char`]s.
+    pub fn chars(&self) -> Vec<char> {
+        self.pattern.chars().collect()
+    }
+
+    /// Return the glyphs written for this sequence, in order.
+    pub fn glyphs(&self) -> Vec<Glyph> {
+        self.glyphs.iter().map(|&g| Glyph::from(g.resolve())).collect()
+    }
+}
+
 
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct GlyphSpec {
     pub tengwa: Option<char>,
     pub tehta: Option<TehtaSpec>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub tehta_alt: bool,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub rince: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub nasal: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub labial: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub palatal: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub nuquerna: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub long_cons: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub dot_inner: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub dot_under: bool,
 }
 
@@ -80,12 +246,31 @@ impl From<TehtaSpec> for GlyphSpec {
     }
 }
 
+impl From<Glyph> for GlyphSpec {
+    fn from(glyph: Glyph) -> Self {
+        Self {
+            tengwa: glyph.base,
+            tehta: glyph.tehta.map(TehtaSpec::from),
+            tehta_alt: glyph.tehta_alt,
+            rince: glyph.rince,
+            nasal: glyph.nasal,
+            labial: glyph.labial,
+            palatal: glyph.palatal,
+            nuquerna: glyph.nuquerna,
+            long_cons: glyph.long_cons,
+            dot_inner: glyph.dot_inner,
+            dot_under: glyph.dot_under,
+        }
+    }
+}
+
 impl From<GlyphSpec> for Glyph {
     fn from(spec: GlyphSpec) -> Self {
         Self {
             base: spec.tengwa,
+            base_final: None,
             tehta: spec.tehta.map(Tehta::from),
-            tehta_alt: spec.tehta_alt,
+            tehta_alt: spec.tehta_alt || spec.tehta.is_some_and(|t| t.long),
             tehta_first: false,
             tehta_hidden: false,
             vowels: Default::default(),
@@ -101,25 +286,43 @@ impl From<GlyphSpec> for Glyph {
             ligate_short: false,
             ligate_zwj: 0,
             _p: Default::default(),
+            policy: None,
         }
     }
 }
 
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct TehtaSpec {
     pub base: char,
 
     #[serde(alias = "alt")]
     pub alternate: Option<char>,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub can_double: bool,
+
+    /// If this is `true`, the vowel is long, and the tehta will be written in
+    ///     its long form.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub long: bool,
 }
 
 impl From<char> for TehtaSpec {
     fn from(base: char) -> Self {
-        Self { base, alternate: None, can_double: false }
+        Self { base, alternate: None, can_double: false, long: false }
+    }
+}
+
+impl From<Tehta> for TehtaSpec {
+    fn from(tehta: Tehta) -> Self {
+        Self {
+            base: tehta.base,
+            alternate: tehta.alternate,
+            can_double: tehta.can_double,
+            long: false,
+        }
     }
 }
 
@@ -134,13 +337,72 @@ impl From<TehtaSpec> for Tehta {
 }
 
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+/// A value that may be specified either by a single [`char`], or by a table
+///     with more detail.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
 #[serde(untagged)]
 pub enum CharOr<T> {
     Char(char),
     Struct(T),
 }
 
+/// This is implemented manually, rather than derived, so that an invalid table
+///     reports the actual problem with it, instead of only saying that it does
+///     not match any variant.
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for CharOr<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CharOrVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: serde::Deserialize<'de>> Visitor<'de> for CharOrVisitor<T> {
+            type Value = CharOr<T>;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                f.write_str("a single character or a table")
+            }
+
+            fn visit_char<E: de::Error>(self, c: char) -> Result<Self::Value, E> {
+                Ok(CharOr::Char(c))
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+                let mut chars = s.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(CharOr::Char(c)),
+                    _ => Err(E::invalid_value(Unexpected::Str(s), &self)),
+                }
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                let de = de::value::MapAccessDeserializer::new(map);
+                T::deserialize(de).map(CharOr::Struct)
+            }
+        }
+
+        deserializer.deserialize_any(CharOrVisitor(PhantomData))
+    }
+}
+
+impl From<GlyphSpec> for CharOr<GlyphSpec> {
+    /// Use the shorter form if the glyph is only a tengwa.
+    fn from(spec: GlyphSpec) -> Self {
+        match spec.tengwa {
+            Some(c) if spec == GlyphSpec::from(c) => Self::Char(c),
+            _ => Self::Struct(spec),
+        }
+    }
+}
+
+impl From<TehtaSpec> for CharOr<TehtaSpec> {
+    /// Use the shorter form if the tehta has no other attributes.
+    fn from(spec: TehtaSpec) -> Self {
+        match spec == TehtaSpec::from(spec.base) {
+            true => Self::Char(spec.base),
+            false => Self::Struct(spec),
+        }
+    }
+}
+
 impl<T: From<char> + Sized> CharOr<T> {
     pub fn resolve(self) -> T {
         match self {
@@ -151,43 +413,307 @@ impl<T: From<char> + Sized> CharOr<T> {
 }
 
 
+/// An error encountered while loading a [`ModeFile`].
+#[derive(Debug)]
+pub enum ModeFileError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file was read, but does not contain a valid mode definition.
+    Parse {
+        /// Description of the problem.
+        message: String,
+        /// The key whose value caused the problem, if it can be determined.
+        key: Option<String>,
+        /// The line and column where the problem was found, starting from 1.
+        position: Option<(usize, usize)>,
+    },
+}
+
+impl ModeFileError {
+    pub(crate) fn from_toml(text: &str, err: toml::de::Error) -> Self {
+        let message = err.message().trim().to_owned();
+        let Some(Range { start, .. }) = err.span() else {
+            return Self::Parse { message, key: None, position: None };
+        };
+
+        let start = start.min(text.len());
+        let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_num = text[..line_start].matches('\n').count() + 1;
+        let column = text[line_start..start].chars().count() + 1;
+
+        //  Find the key at the position of the error, in the parsed document.
+        //      A syntax error has no key, since the document cannot be parsed.
+        let mut path: Vec<String> = Vec::new();
+        let key: Option<String> = match ImDocument::parse(text) {
+            Ok(doc) if key_at(doc.as_table(), start, &mut path) => Some(path.join(".")),
+            _ => None,
+        };
+
+        Self::Parse { message, key, position: Some((line_num, column)) }
+    }
+}
+
+/// Return `true` if a span of a TOML document contains a position.
+fn contains(span: Option<Range<usize>>, pos: usize) -> bool {
+    span.is_some_and(|span| span.contains(&pos))
+}
+
+/// Find the path of keys to the deepest item of a table whose span contains a
+///     position. Returns `false` if there is none.
+fn key_at(table: &dyn TableLike, pos: usize, path: &mut Vec<String>) -> bool {
+    for (name, _) in table.iter() {
+        let Some((key, item)) = table.get_key_value(name) else { continue; };
+        path.push(key.get().to_owned());
+
+        if contains(key.span(), pos) || item_at(item, pos, path) {
+            return true;
+        }
+
+        path.pop();
+    }
+
+    false
+}
+
+/// Find the path of keys to the deepest item within an item whose span
+///     contains a position. Returns `true` if the item itself contains it.
+fn item_at(item: &Item, pos: usize, path: &mut Vec<String>) -> bool {
+    let found: bool = if let Some(table) = item.as_table_like() {
+        key_at(table, pos, path)
+    } else if let Some(tables) = item.as_array_of_tables() {
+        tables.iter().any(|table| key_at(table, pos, path))
+    } else if let Some(array) = item.as_array() {
+        array.iter()
+            .filter_map(|value| value.as_inline_table())
+            .any(|table| key_at(table, pos, path))
+    } else {
+        false
+    };
+
+    found || contains(item.span(), pos)
+}
+
+
+impl Display for ModeFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => err.fmt(f),
+            Self::Parse { message, key, position } => {
+                if let Some((line, column)) = position {
+                    write!(f, "line {line}, column {column}: ")?;
+                }
+
+                if let Some(key) = key {
+                    write!(f, "at `{key}`: ")?;
+                }
+
+                f.write_str(message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ModeFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ModeFileError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+
+/// Replace each character of the Tengwar, in the basic strings of a TOML
+///     document, with a `\u` escape sequence. Literal strings cannot contain
+///     escapes, and are left unchanged.
+fn escape_tengwar(text: &str) -> String {
+    let is_tengwar = |c: char| [Encoding::Csur, Encoding::Smp].into_iter()
+        .any(|enc| enc.block().is_some_and(|block| block.contains(&c)));
+
+    let mut out = String::with_capacity(text.len());
+    let mut quote: Option<char> = None;
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some('"'), '\\') => {
+                //  Keep an existing escape sequence as it is.
+                out.push(c);
+                out.extend(chars.next());
+                continue;
+            }
+            (Some('"'), _) if is_tengwar(c) => {
+                match u16::try_from(c as u32) {
+                    Ok(n) => out.push_str(&format!("\\u{n:04X}")),
+                    Err(_) => out.push_str(&format!("\\U{:08X}", c as u32)),
+                }
+                continue;
+            }
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if open == c => quote = None,
+            _ => {}
+        }
+
+        out.push(c);
+    }
+
+    out
+}
+
+
 /// A runtime-defined mode of the Tengwar, which may be specified in a file.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct ModeFile {
-    pub chunks: usize,
+    /// A built-in mode to fall back on, for anything not defined by the file.
     #[serde(default)]
+    pub extends: Option<BaseMode>,
+
+    #[serde(default = "default_chunks")]
+    pub chunks: usize,
+    /// If the file extends a built-in mode that places vowels first, this will
+    ///     be enabled regardless.
+    #[serde(default, skip_serializing_if = "is_false")]
     pub vowels_first: bool,
 
+    /// If this is empty, the checks of the extended mode are used instead.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub checks_mod: Vec<Check>,
+    /// If this is empty, the checks of the extended mode are used instead.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub checks_new: Vec<Check>,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub allow_rince: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub allow_nuquernar: bool,
 
-    #[serde(default, alias = "consonant")]
-    pub consonants: HashMap<String, CharOr<GlyphSpec>>,
+    #[serde(default, alias = "consonant", skip_serializing_if = "BTreeMap::is_empty")]
+    pub consonants: BTreeMap<String, CharOr<GlyphSpec>>,
+
+    #[serde(default, alias = "diphthong", skip_serializing_if = "BTreeMap::is_empty")]
+    pub diphthongs: BTreeMap<String, CharOr<GlyphSpec>>,
+
+    #[serde(default, alias = "vowel", skip_serializing_if = "BTreeMap::is_empty")]
+    pub vowels: BTreeMap<String, CharOr<TehtaSpec>>,
+
+    /// If the file extends a built-in mode, these are checked before the
+    ///     sequences of that mode.
+    #[serde(default, alias = "sequence", skip_serializing_if = "Vec::is_empty")]
+    pub sequences: Vec<Sequence>,
 
-    #[serde(default, alias = "vowel")]
-    pub vowels: HashMap<String, CharOr<TehtaSpec>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rince: Vec<Modifier>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labial: Vec<Modifier>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nasal: Vec<Modifier>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub palatal: Vec<Modifier>,
+
+    #[serde(default, alias = "replacement", skip_serializing_if = "Vec::is_empty")]
+    pub replacements: Vec<Replacement>,
+
+    #[serde(default, alias = "rule", skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<Rule>,
+
+    /// Test cases for the mode, which are not used in transcription.
+    #[serde(default, rename = "test", alias = "tests", skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<TestVector>,
 }
 
+fn default_chunks() -> usize { 3 }
+const fn is_false(b: &bool) -> bool { !*b }
+
 impl ModeFile {
+    /// Define an empty mode, optionally extending a built-in mode.
+    pub fn new(extends: Option<BaseMode>) -> Self {
+        Self {
+            extends,
+            chunks: default_chunks(),
+            vowels_first: false,
+            checks_mod: Vec::new(),
+            checks_new: Vec::new(),
+            allow_rince: false,
+            allow_nuquernar: false,
+            consonants: BTreeMap::new(),
+            diphthongs: BTreeMap::new(),
+            vowels: BTreeMap::new(),
+            sequences: Vec::new(),
+            rince: Vec::new(),
+            labial: Vec::new(),
+            nasal: Vec::new(),
+            palatal: Vec::new(),
+            replacements: Vec::new(),
+            rules: Vec::new(),
+            tests: Vec::new(),
+        }
+    }
+
+    /// Read a mode definition from a string of TOML.
+    pub fn from_toml_str(text: &str) -> Result<Self, ModeFileError> {
+        toml::from_str(text).map_err(|err| ModeFileError::from_toml(text, err))
+    }
+
+    /// Read a mode definition from a TOML file.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ModeFileError> {
+        Self::from_toml_str(&std::fs::read_to_string(path)?)
+    }
+
+    /// Write this mode definition as a string of TOML. The Tengwar are written
+    ///     as escape sequences, since they cannot be read without a font.
+    pub fn to_toml_string(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self).map(|text| escape_tengwar(&text))
+    }
+
+    /// Build a usable [`CustomMode`] from this definition. If the file extends
+    ///     a built-in mode, the rules of that mode come first, followed by the
+    ///     rules of the file. The sequences of the file come before those of the
+    ///     mode, so that they can override them.
     pub fn resolve(&self) -> CustomMode {
+        let base: Self = match self.extends {
+            Some(mode) => mode.defaults(),
+            None => Self::new(None),
+        };
+
+        let checks = |own: &Vec<Check>, base: Vec<Check>| match own.is_empty() {
+            true => base,
+            false => own.clone(),
+        };
+        let concat = |base: Vec<Modifier>, own: &[Modifier]| [base, own.to_vec()].concat();
+
         CustomMode {
+            extends: self.extends,
             chunks: self.chunks,
-            vowels_first: self.vowels_first,
-            checks_mod: self.checks_mod.clone(),
-            checks_new: self.checks_new.clone(),
+            vowels_first: self.vowels_first || base.vowels_first,
+            checks_mod: checks(&self.checks_mod, base.checks_mod),
+            checks_new: checks(&self.checks_new, base.checks_new),
             consonants: self.consonants.iter()
                 .map(|(s, &g)| (s.chars().collect(), g.resolve()))
                 .collect(),
+            diphthongs: self.diphthongs.iter()
+                .map(|(s, &g)| (s.chars().collect(), g.resolve()))
+                .collect(),
             vowels: self.vowels.iter()
                 .map(|(s, &t)| (s.chars().collect(), t.resolve()))
                 .collect(),
+            sequences: [self.sequences.clone(), base.sequences].concat(),
+            rince: concat(base.rince, &self.rince),
+            labial: concat(base.labial, &self.labial),
+            nasal: concat(base.nasal, &self.nasal),
+            palatal: concat(base.palatal, &self.palatal),
+            replacements: [base.replacements, self.replacements.clone()].concat(),
+            rules: [base.rules, self.rules.clone()].concat(),
             current: None,
             previous: None,
+            pending: Default::default(),
+            narrowed: false,
         }
     }
 }
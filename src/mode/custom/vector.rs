@@ -0,0 +1,121 @@
+use std::fmt::{Display, Formatter};
+use crate::{characters::consts::width, TranscriberSettings};
+use super::*;
+
+
+/// Split a string of output into glyphs. Each glyph is a character, followed by
+///     any zero-width characters, such as tehtar, that are placed on it.
+pub fn glyphs(text: &str) -> Vec<&str> {
+    let mut glyphs: Vec<&str> = Vec::new();
+    let mut start: usize = 0;
+
+    for (idx, c) in text.char_indices() {
+        if idx > start && width(c) != Some(0) {
+            glyphs.push(&text[start..idx]);
+            start = idx;
+        }
+    }
+
+    if start < text.len() {
+        glyphs.push(&text[start..]);
+    }
+
+    glyphs
+}
+
+
+/// A test case for a mode, defined in a [`ModeFile`] as a `[[test]]` table.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestVector {
+    /// Text to be transcribed.
+    pub input: String,
+    /// The exact output expected from the mode.
+    pub expected: String,
+    /// Settings to be used for transcription. Any setting not specified takes
+    ///     its default value.
+    #[serde(default)]
+    pub settings: TranscriberSettings,
+}
+
+impl TestVector {
+    /// Transcribe the input with a mode, and compare the result to the expected
+    ///     output.
+    pub fn run(&self, mode: &CustomMode) -> TestResult {
+        let received: String = mode.clone()
+            .into_transcriber(&self.input)
+            .with_settings(self.settings)
+            .collect();
+
+        let expected: Vec<&str> = glyphs(&self.expected);
+        let output: Vec<&str> = glyphs(&received);
+        let mismatches: Vec<Mismatch> = (0..expected.len().max(output.len()))
+            .filter_map(|index| {
+                let exp: Option<&str> = expected.get(index).copied();
+                let out: Option<&str> = output.get(index).copied();
+
+                (exp != out).then(|| Mismatch {
+                    index,
+                    expected: exp.map(String::from),
+                    received: out.map(String::from),
+                })
+            })
+            .collect();
+
+        TestResult { received, mismatches }
+    }
+}
+
+
+/// The outcome of running a [`TestVector`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TestResult {
+    /// The full output of the mode.
+    pub received: String,
+    /// Every glyph position where the output differs from the expectation.
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl TestResult {
+    /// Return `true` if the output matched the expectation exactly.
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+
+/// A single glyph of output that differs from the expected output.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mismatch {
+    /// The position of the glyph, counted in glyphs rather than characters.
+    pub index: usize,
+    /// The glyph that was expected, or `None` if the output was too long.
+    pub expected: Option<String>,
+    /// The glyph that was produced, or `None` if the output was too short.
+    pub received: Option<String>,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fn codepoints(f: &mut Formatter<'_>, glyph: &Option<String>) -> std::fmt::Result {
+            match glyph {
+                Some(glyph) => {
+                    write!(f, "\"{glyph}\" (")?;
+
+                    for (i, c) in glyph.chars().enumerate() {
+                        if i > 0 { f.write_str(" ")?; }
+                        write!(f, "U+{:04X}", c as u32)?;
+                    }
+
+                    f.write_str(")")
+                }
+                None => f.write_str("nothing"),
+            }
+        }
+
+        write!(f, "glyph {}: expected ", self.index)?;
+        codepoints(f, &self.expected)?;
+        f.write_str(", received ")?;
+        codepoints(f, &self.received)
+    }
+}
@@ -0,0 +1,314 @@
+use crate::{characters::*, policy::Policy, Token};
+use super::{ParseAction, TengwarMode};
+
+
+/// Tengwa for a consonantal Y, or for a Y following a vowel.
+pub const CONSONANT_Y: char = TENGWA_ANNA;
+
+/// Tengwa used for the common word "the".
+pub const WORD_THE: char = TENGWA_ANTO;
+/// Tengwa used for the common word "of".
+pub const WORD_OF: char = TENGWA_AMPA;
+/// Tengwa used for the common word "and".
+pub const WORD_AND: char = TENGWA_ANDO;
+
+
+pub const fn consonant_char(slice: &[char]) -> Option<char> {
+    Some(match slice {
+        //  Tincotéma.
+        ['t']           /**/ => TEMA_TINCO.single_dn,
+        ['d']           /**/ => TEMA_TINCO.double_dn,
+        ['t', 'h']
+        | ['þ'] | ['θ'] /**/ => TEMA_TINCO.single_up,
+        ['d', 'h']
+        | ['ð']         /**/ => TEMA_TINCO.double_up,
+        ['n']           /**/ => TEMA_TINCO.double_sh,
+        ['r']           /**/ => TEMA_TINCO.single_sh,
+
+        //  Parmatéma.
+        ['p']           /**/ => TEMA_PARMA.single_dn,
+        ['b']           /**/ => TEMA_PARMA.double_dn,
+        ['f'] | ['φ']
+        | ['p', 'h']    /**/ => TEMA_PARMA.single_up,
+        ['v']           /**/ => TEMA_PARMA.double_up,
+        ['m']           /**/ => TEMA_PARMA.double_sh,
+        ['w']           /**/ => TEMA_PARMA.single_sh,
+
+        //  Calmatéma.
+        ['c', 'h']      /**/ => TEMA_CALMA.single_dn,
+        ['j']           /**/ => TEMA_CALMA.double_dn,
+        ['s', 'h']      /**/ => TEMA_CALMA.single_up,
+        ['z', 'h']      /**/ => TEMA_CALMA.double_up,
+        // ['y']           /**/ => TEMA_CALMA.single_sh, // Special case.
+
+        //  Qessetéma.
+        ['c'] | ['k']
+        | ['c', 'k']
+        | ['q']         /**/ => TEMA_QESSE.single_dn,
+        ['g']           /**/ => TEMA_QESSE.double_dn,
+        ['k', 'h']      /**/ => TEMA_QESSE.single_up,
+        ['g', 'h']      /**/ => TEMA_QESSE.double_up,
+        ['n', 'g']
+        | ['ñ']         /**/ => TEMA_QESSE.double_sh,
+
+        //  Irregulars.
+        ['l']           /**/ => TENGWA_LAMBE,
+        ['s']           /**/ => TENGWA_SILME,
+        ['z'] | ['ß']   /**/ => TENGWA_ESSE,
+
+        ['h']           /**/ => TENGWA_HYARMEN,
+        ['w', 'h']      /**/ => TENGWA_HWESTA_SINDARINWA,
+
+        _ => { return None; }
+    })
+}
+
+
+pub const fn get_consonant(slice: &[char]) -> Option<Glyph> {
+    match consonant_char(slice) {
+        Some(cons) => Some(Glyph::new_base(cons)),
+        None => match slice {
+            ['q', 'u'] => Some(Glyph::new_base(TENGWA_QESSE).with_labial(true)),
+            ['x'] => Some(Glyph::new_base(TENGWA_QESSE).with_rince(true)),
+            &[a, b] if a == b => match consonant_char(&[a]) {
+                Some(cons) => Some(Glyph::new_base(cons).with_underline(true)),
+                None => None,
+            }
+            _ => None,
+        }
+    }
+}
+
+
+/// Determine whether a nasal consonant may be written as a bar above another
+///     tengwa. Only the stops and spirants of the same series can take it.
+pub const fn takes_nasal(nasal: char, tengwa: char) -> bool {
+    let tema: &Tema = match nasal {
+        'm' => &TEMA_PARMA,
+        'n' if TEMA_TINCO.contains(tengwa) => &TEMA_TINCO,
+        'n' if TEMA_CALMA.contains(tengwa) => &TEMA_CALMA,
+        'n' => &TEMA_QESSE,
+        _ => { return false; }
+    };
+
+    tengwa == tema.single_dn || tengwa == tema.double_dn
+        || tengwa == tema.single_up || tengwa == tema.double_up
+}
+
+
+/// Find the longest consonant at the start of a slice, including a preceding
+///     nasal where possible. Returns the [`Glyph`] along with the number of
+///     [`char`]s it represents.
+pub const fn find_consonant(slice: &[char]) -> Option<(Glyph, usize)> {
+    let mut len: usize = if slice.len() < 3 { slice.len() } else { 3 };
+
+    while 0 < len {
+        let (chunk, _) = slice.split_at(len);
+
+        if let Some(glyph) = get_consonant(chunk) {
+            return Some((glyph, len));
+        } else if let [nasal @ ('m' | 'n'), rest @ ..] = chunk {
+            if let Some(Glyph { base: Some(base), rince: false, long_cons: false, .. })
+                = get_consonant(rest)
+            {
+                if takes_nasal(*nasal, base) {
+                    return Some((Glyph::new_base(base).with_nasal(true), len));
+                }
+            }
+        }
+
+        len -= 1;
+    }
+
+    None
+}
+
+
+pub const fn get_tehta(slice: &[char]) -> Option<(Tehta, bool)> {
+    match slice {
+        ['a'] | ['ä'] => Some((TEHTA_A, false)),
+        ['e'] | ['ë'] => Some((TEHTA_E, false)),
+        ['i'] | ['ï'] => Some((TEHTA_I, false)),
+        ['o'] | ['ö'] => Some((TEHTA_O, false)),
+        ['u'] | ['ü'] => Some((TEHTA_U, false)),
+        ['y'] | ['ÿ'] => Some((TEHTA_Y, false)),
+
+        ['á'] | ['â'] | ['ā'] => Some((TEHTA_A, true)),
+        ['é'] | ['ê'] | ['ē'] | ['e', 'e'] => Some((TEHTA_E, true)),
+        ['í'] | ['î'] | ['ī'] => Some((TEHTA_I, true)),
+        ['ó'] | ['ô'] | ['ō'] | ['o', 'o'] => Some((TEHTA_O, true)),
+        ['ú'] | ['û'] | ['ū'] => Some((TEHTA_U, true)),
+
+        _ => None,
+    }
+}
+
+
+/// Find the longest vowel at the start of a slice. Returns the [`Tehta`] and
+///     whether it is long, along with the number of [`char`]s it represents.
+pub const fn find_tehta(slice: &[char]) -> Option<(Tehta, bool, usize)> {
+    let mut len: usize = if slice.len() < 2 { slice.len() } else { 2 };
+
+    while 0 < len {
+        if let Some((tehta, alt)) = get_tehta(slice.split_at(len).0) {
+            return Some((tehta, alt, len));
+        }
+
+        len -= 1;
+    }
+
+    None
+}
+
+
+/// Find one of the common words written with a single tengwa. Returns the
+///     [`Glyph`] along with the number of [`char`]s in the word. This does not
+///     check that the word is complete.
+pub const fn get_word(slice: &[char]) -> Option<(Glyph, usize)> {
+    match slice {
+        ['t', 'h', 'e', ..] => Some((Glyph::new_base(WORD_THE).with_dot_under(true), 3)),
+        ['o', 'f', ..] => Some((Glyph::new_base(WORD_OF), 2)),
+        ['a', 'n', 'd', ..] => Some((Glyph::new_base(WORD_AND).with_nasal(true), 3)),
+        _ => None,
+    }
+}
+
+
+const fn is_vowel(chr: Option<&char>) -> bool {
+    match chr {
+        Some(&c) => get_tehta(&[c]).is_some(),
+        None => false,
+    }
+}
+
+
+fn is_letter(chr: Option<&char>) -> bool {
+    chr.is_some_and(|c| c.is_alphabetic())
+}
+
+
+/// An orthographic mode for English, based on the one used by J.R.R. Tolkien
+///     for the title page of The Lord of the Rings.
+///
+/// Unlike the other modes, this is based on the spelling of the text, rather
+///     than on its pronunciation. Vowels are written above the preceding
+///     consonant, and a silent E at the end of a word is written as a dot
+///     below the consonant before it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct General {
+    current: Option<Glyph>,
+    previous: Option<Glyph>,
+    /// Whether the current word has already had a vowel.
+    has_vowel: bool,
+}
+
+impl TengwarMode for General {
+    /// The common words represented by single tengwar must be seen together
+    ///     with the following character, to be sure that they are complete.
+    const MAX_CHUNK: usize = 4;
+
+    fn finish_current<P: Policy>(&mut self) -> Option<Token<P>> {
+        self.previous = self.current.take();
+        self.previous.map(|g| Token::Glyph(g.change_policy()))
+    }
+
+    fn process<P: Policy>(&mut self, chunk: &[char]) -> ParseAction<P> {
+        self.process_in(chunk, None)
+    }
+
+    fn process_in<P: Policy>(&mut self, chunk: &[char], policy: Option<P>) -> ParseAction<P> {
+        macro_rules! finish {
+            ($glyph:expr) => {finish!($glyph, 0)};
+            ($glyph:expr, $len:expr) => {{
+                let glyph = $glyph;
+
+                self.current = None;
+                self.previous = Some(glyph);
+
+                ParseAction::MatchedToken {
+                    token: Token::Glyph(glyph.change_policy()),
+                    len: $len,
+                }
+            }};
+        }
+
+        let initial: bool = self.previous.is_none();
+
+        if let [ESC, ESC_NOP, ..] = chunk {
+            self.previous = None;
+            ParseAction::matched_opt(self.current.take().map(|g| Token::Glyph(g.change_policy())), 2)
+        } else if let [ESC, _, ..] = chunk {
+            ParseAction::ESC_BACKSLASH
+        } else if let Some(current) = &mut self.current {
+            //  A glyph is currently being constructed. Try to continue it. All
+            //      decisions here are made on the full width of the chunk, so
+            //      that the end of a word can always be seen.
+            let next: Option<&char> = chunk.get(1);
+
+            match chunk {
+                _ if current.tehta.is_some() => ParseAction::MatchedNone,
+                ['e', ..] if self.has_vowel && !is_letter(next) => {
+                    //  A final E following another vowel is silent, and is
+                    //      marked by a dot below the preceding tengwa.
+                    current.dot_under = true;
+                    finish!(*current, 1)
+                }
+                ['y', ..] if is_vowel(next) => {
+                    //  A Y before a vowel is a consonant.
+                    finish!(*current)
+                }
+                ['s' | 'z', ..] if !is_letter(next) && current.with_policy(policy).can_take_rince() => {
+                    current.rince = true;
+                    ParseAction::MatchedPart(1)
+                }
+                _ => match find_tehta(chunk) {
+                    Some((tehta, alt, len)) => {
+                        //  A following vowel changes Órë to Rómen.
+                        current.replace_base(TENGWA_ORE, TENGWA_ROMEN);
+                        current.tehta = Some(tehta);
+                        current.tehta_alt = alt;
+                        self.has_vowel = true;
+
+                        finish!(*current, len)
+                    }
+                    None => finish!(*current),
+                }
+            }
+        } else {
+            //  Try to find a new glyph.
+            if initial {
+                self.has_vowel = false;
+
+                //  Check for a common word with its own tengwa.
+                if let Some((glyph, len)) = get_word(chunk) {
+                    if !is_letter(chunk.get(len)) {
+                        return finish!(glyph, len);
+                    }
+                }
+            }
+
+            if let ['y', next @ ..] = chunk {
+                if !initial || is_vowel(next.first()) {
+                    //  A Y is a consonant before a vowel, and is also written
+                    //      as such after one.
+                    self.current = Some(Glyph::new_base(CONSONANT_Y));
+                    return ParseAction::MatchedPart(1);
+                }
+            }
+
+            //  Check for a consonant.
+            if let Some((new, len)) = find_consonant(chunk) {
+                self.current = Some(new);
+                ParseAction::MatchedPart(len)
+            }
+
+            //  Check for a vowel.
+            else if let Some((tehta, alt, len)) = find_tehta(chunk) {
+                self.has_vowel = true;
+                finish!(Glyph::new_vowel(tehta, alt), len)
+            } else {
+                ParseAction::MatchedNone
+            }
+        }
+    }
+}
@@ -1,5 +1,5 @@
-use crate::{characters::*, Token};
-use super::{ParseAction, TengwarMode};
+use crate::{characters::*, policy::Policy, Token};
+use super::{table::{lookup, table, Table}, Neighbors, ParseAction, TengwarMode};
 
 
 /// Tengwa for a consonantal initial I.
@@ -10,60 +10,61 @@ pub const CARRIER_DIPH_I: char = TENGWA_ANNA;
 pub const CARRIER_DIPH_U: char = TENGWA_URE;
 
 
+pub const CONSONANTS: Table<char> = table! {
+    //  Tincotéma.
+    ['t']           /**/ => TEMA_TINCO.single_dn,
+    ['d']           /**/ => TEMA_TINCO.double_dn,
+    ['t', 'h']
+    | ['θ'] | ['þ'] /**/ => TEMA_TINCO.single_up,
+    ['d', 'h']
+    | ['ð']         /**/ => TEMA_TINCO.double_up,
+    ['n']           /**/ => TEMA_TINCO.double_sh,
+    ['r']           /**/ => TEMA_TINCO.single_sh,
+
+    //  Parmatéma.
+    ['p']           /**/ => TEMA_PARMA.single_dn,
+    ['b']           /**/ => TEMA_PARMA.double_dn,
+    ['p', 'h']
+    | ['φ']         /**/ => TEMA_PARMA.single_up,
+    ['v']           /**/ => TEMA_PARMA.double_up,
+    ['m']           /**/ => TEMA_PARMA.double_sh,
+    // []           /**/ => TEMA_PARMA.single_sh,
+
+    //  Calmatéma.
+    // []           /**/ => TEMA_CALMA.single_dn,
+    // []           /**/ => TEMA_CALMA.double_dn,
+    // []           /**/ => TEMA_CALMA.single_up,
+    // []           /**/ => TEMA_CALMA.double_up,
+    // []           /**/ => TEMA_CALMA.double_sh,
+    // []           /**/ => TEMA_CALMA.single_sh, // Diphthongs of I.
+
+    //  Qessetéma.
+    ['c'] | ['k']   /**/ => TEMA_QESSE.single_dn,
+    ['g']           /**/ => TEMA_QESSE.double_dn,
+    ['c', 'h']
+    | ['k', 'h']    /**/ => TEMA_QESSE.single_up,
+    ['g', 'h']      /**/ => TEMA_QESSE.double_up,
+    ['n', 'g']
+    | ['ñ']         /**/ => TEMA_QESSE.double_sh,
+    ['w']           /**/ => TEMA_QESSE.single_sh,
+
+    //  Irregulars.
+    ['l']           /**/ => TENGWA_LAMBE,
+    // ['l', 'h']      /**/ => TENGWA_ALDA, // NOTE: Only for initials.
+    // ['r', 'h']      /**/ => TENGWA_ARDA,
+    ['m', 'h']      /**/ => TENGWA_MALTA_HOOKED,
+    ['s']           /**/ => TENGWA_SILME,
+    ['s', 's']
+    | ['z'] | ['ß'] /**/ => TENGWA_ESSE,
+
+    ['h']           /**/ => TENGWA_HYARMEN,
+    ['h', 'w']      /**/ => TENGWA_HWESTA_SINDARINWA,
+    ['j']           /**/ => CONSONANT_I,
+};
+
+
 pub const fn consonant_char(slice: &[char]) -> Option<char> {
-    Some(match slice {
-        //  Tincotéma.
-        ['t']           /**/ => TEMA_TINCO.single_dn,
-        ['d']           /**/ => TEMA_TINCO.double_dn,
-        ['t', 'h']
-        | ['θ'] | ['þ'] /**/ => TEMA_TINCO.single_up,
-        ['d', 'h']
-        | ['ð']         /**/ => TEMA_TINCO.double_up,
-        ['n']           /**/ => TEMA_TINCO.double_sh,
-        ['r']           /**/ => TEMA_TINCO.single_sh,
-
-        //  Parmatéma.
-        ['p']           /**/ => TEMA_PARMA.single_dn,
-        ['b']           /**/ => TEMA_PARMA.double_dn,
-        ['p', 'h']
-        | ['φ']         /**/ => TEMA_PARMA.single_up,
-        ['v']           /**/ => TEMA_PARMA.double_up,
-        ['m']           /**/ => TEMA_PARMA.double_sh,
-        // []           /**/ => TEMA_PARMA.single_sh,
-
-        //  Calmatéma.
-        // []           /**/ => TEMA_CALMA.single_dn,
-        // []           /**/ => TEMA_CALMA.double_dn,
-        // []           /**/ => TEMA_CALMA.single_up,
-        // []           /**/ => TEMA_CALMA.double_up,
-        // []           /**/ => TEMA_CALMA.double_sh,
-        // []           /**/ => TEMA_CALMA.single_sh, // Diphthongs of I.
-
-        //  Qessetéma.
-        ['c'] | ['k']   /**/ => TEMA_QESSE.single_dn,
-        ['g']           /**/ => TEMA_QESSE.double_dn,
-        ['c', 'h']
-        | ['k', 'h']    /**/ => TEMA_QESSE.single_up,
-        ['g', 'h']      /**/ => TEMA_QESSE.double_up,
-        ['n', 'g']
-        | ['ñ']         /**/ => TEMA_QESSE.double_sh,
-        ['w']           /**/ => TEMA_QESSE.single_sh,
-
-        //  Irregulars.
-        ['l']           /**/ => TENGWA_LAMBE,
-        // ['l', 'h']      /**/ => TENGWA_ALDA, // NOTE: Only for initials.
-        // ['r', 'h']      /**/ => TENGWA_ARDA,
-        ['m', 'h']      /**/ => TENGWA_MALTA_HOOKED,
-        ['s']           /**/ => TENGWA_SILME,
-        ['s', 's']
-        | ['z'] | ['ß'] /**/ => TENGWA_ESSE,
-
-        ['h']           /**/ => TENGWA_HYARMEN,
-        ['h', 'w']      /**/ => TENGWA_HWESTA_SINDARINWA,
-        ['j']           /**/ => CONSONANT_I,
-
-        _ => { return None; }
-    })
+    lookup(CONSONANTS, slice)
 }
 
 
@@ -81,41 +82,43 @@ pub const fn get_consonant(slice: &[char]) -> Option<Glyph> {
 }
 
 
-pub const fn get_diphthong(slice: &[char]) -> Option<Glyph> {
-    match slice {
-        ['a', 'e'] | ['æ'] => Some(Glyph::new_both(CARRIER_DIPH_E, TEHTA_A)),
-        ['o', 'e'] | ['œ'] => Some(Glyph::new_both(CARRIER_DIPH_E, TEHTA_O)),
+pub const DIPHTHONGS: Table<Glyph> = table! {
+    ['a', 'e'] | ['æ'] => Glyph::new_both(CARRIER_DIPH_E, TEHTA_A),
+    ['o', 'e'] | ['œ'] => Glyph::new_both(CARRIER_DIPH_E, TEHTA_O),
 
-        ['a', 'i'] => Some(Glyph::new_both(CARRIER_DIPH_I, TEHTA_A)),
-        ['e', 'i'] => Some(Glyph::new_both(CARRIER_DIPH_I, TEHTA_E)),
-        ['u', 'i'] => Some(Glyph::new_both(CARRIER_DIPH_I, TEHTA_U)),
+    ['a', 'i'] => Glyph::new_both(CARRIER_DIPH_I, TEHTA_A),
+    ['e', 'i'] => Glyph::new_both(CARRIER_DIPH_I, TEHTA_E),
+    ['u', 'i'] => Glyph::new_both(CARRIER_DIPH_I, TEHTA_U),
 
-        ['a', 'u']
-        | ['a', 'w'] => Some(Glyph::new_both(CARRIER_DIPH_U, TEHTA_A)),
+    ['a', 'u']
+    | ['a', 'w'] => Glyph::new_both(CARRIER_DIPH_U, TEHTA_A),
+};
 
-        _ => None,
-    }
+
+pub const fn get_diphthong(slice: &[char]) -> Option<Glyph> {
+    lookup(DIPHTHONGS, slice)
 }
 
 
+pub const VOWELS: Table<Glyph> = table! {
+    ['a'] | ['ä'] => Glyph::new_tehta(TEHTA_A),
+    ['e'] | ['ë'] => Glyph::new_tehta(TEHTA_E),
+    ['i'] | ['ï'] => Glyph::new_tehta(TEHTA_I),
+    ['o'] | ['ö'] => Glyph::new_tehta(TEHTA_O),
+    ['u'] | ['ü'] => Glyph::new_tehta(TEHTA_U),
+    ['y'] | ['ÿ'] => Glyph::new_tehta(TEHTA_Y),
+
+    ['á'] | ['â'] | ['ā'] | ['a', 'a'] => Glyph::new_tehta_alt(TEHTA_A),
+    ['é'] | ['ê'] | ['ē'] | ['e', 'e'] => Glyph::new_tehta_alt(TEHTA_E),
+    ['í'] | ['î'] | ['ī'] | ['i', 'i'] => Glyph::new_tehta_alt(TEHTA_I),
+    ['ó'] | ['ô'] | ['ō'] | ['o', 'o'] => Glyph::new_tehta_alt(TEHTA_O),
+    ['ú'] | ['û'] | ['ū'] | ['u', 'u'] => Glyph::new_tehta_alt(TEHTA_U),
+    ['ý'] | ['ŷ'] | ['ȳ'] | ['y', 'y'] => Glyph::new_tehta_alt(TEHTA_Y),
+};
+
+
 pub const fn get_vowel(slice: &[char]) -> Option<Glyph> {
-    match slice {
-        ['a'] | ['ä'] => Some(Glyph::new_tehta(TEHTA_A)),
-        ['e'] | ['ë'] => Some(Glyph::new_tehta(TEHTA_E)),
-        ['i'] | ['ï'] => Some(Glyph::new_tehta(TEHTA_I)),
-        ['o'] | ['ö'] => Some(Glyph::new_tehta(TEHTA_O)),
-        ['u'] | ['ü'] => Some(Glyph::new_tehta(TEHTA_U)),
-        ['y'] | ['ÿ'] => Some(Glyph::new_tehta(TEHTA_Y)),
-
-        ['á'] | ['â'] | ['ā'] | ['a', 'a'] => Some(Glyph::new_tehta_alt(TEHTA_A)),
-        ['é'] | ['ê'] | ['ē'] | ['e', 'e'] => Some(Glyph::new_tehta_alt(TEHTA_E)),
-        ['í'] | ['î'] | ['ī'] | ['i', 'i'] => Some(Glyph::new_tehta_alt(TEHTA_I)),
-        ['ó'] | ['ô'] | ['ō'] | ['o', 'o'] => Some(Glyph::new_tehta_alt(TEHTA_O)),
-        ['ú'] | ['û'] | ['ū'] | ['u', 'u'] => Some(Glyph::new_tehta_alt(TEHTA_U)),
-        ['ý'] | ['ŷ'] | ['ȳ'] | ['y', 'y'] => Some(Glyph::new_tehta_alt(TEHTA_Y)),
-
-        _ => None,
-    }
+    lookup(VOWELS, slice)
 }
 
 
@@ -138,37 +141,11 @@ pub struct Gondor {
 }
 
 impl Gondor {
-    pub fn decide_f(next: &[char]) -> Glyph {
-        let mut mode = Self::default();
-        let mut is_final: bool = true;
-        let mut n: usize = next.len();
-
-        while 0 < n {
-            match mode.process(&next[..n]) {
-                ParseAction::MatchedNone => {
-                    //  Next token is unknown.
-                    n -= 1;
-                }
-                ParseAction::MatchedPart(_) => {
-                    //  Next token is a glyph.
-                    is_final = false;
-                    break;
-                }
-                ParseAction::MatchedToken { token: Token::Glyph(_), .. } => {
-                    //  Next token is a glyph.
-                    is_final = false;
-                    break;
-                }
-                _ => {
-                    //  Next token is NOT a glyph.
-                    is_final = true;
-                    break;
-                }
-            }
-        }
-
-        let phonetic: &[char] = if is_final { &['v'] } else { &['p', 'h'] };
-        consonant_char(phonetic).unwrap().into()
+    /// Return the glyph of an F, which is written as "ph" unless it ends a
+    ///     word, where it is written as "v".
+    pub fn consonant_f() -> Glyph {
+        let medial: Glyph = consonant_char(&['p', 'h']).unwrap().into();
+        medial.with_base_final(consonant_char(&['v']).unwrap())
     }
 
     pub fn find_consonant(chunk: &[char], initial: bool) -> Option<(Glyph, usize)> {
@@ -195,38 +172,41 @@ impl Gondor {
             Some((Glyph::new_base(TENGWA_QESSE).with_rince(true), 1))
         }
 
-        //  Check for a final F, which should be spelled with Ampa instead of
-        //      Formen.
-        else if let ['f', ahead @ ..] = chunk {
-            Some((Self::decide_f(ahead), 1))
+        //  Check for F, which is spelled with Ampa instead of Formen at the end
+        //      of a word. This is decided when the glyph is finalized.
+        else if let ['f', ..] = chunk {
+            Some((Self::consonant_f(), 1))
         }
 
         //  Check for any consonant.
-        else if let Some(glyph) = get_consonant(chunk) {
-            Some((glyph, chunk.len()))
-        } else {
-            None
+        else {
+            get_consonant(chunk).map(|glyph| (glyph, chunk.len()))
         }
     }
 }
 
 impl TengwarMode for Gondor {
-    fn finalize(&self, token: &mut Token, next: Option<&Token>) {
+    fn finalize_in<P: Policy>(&self, token: &mut Token<P>, neighbors: &Neighbors<P>) {
         if let Token::Glyph(glyph) = token {
             glyph.tehta_first = true;
+            glyph.decide_final(neighbors.word_final());
 
-            if let Some(Token::Glyph(_)) = next {
+            if let Some(Token::Glyph(_)) = neighbors.next(0) {
                 glyph.replace_base(TENGWA_ORE, TENGWA_ROMEN);
             }
         }
     }
 
-    fn finish_current(&mut self) -> Option<Token> {
+    fn finish_current<P: Policy>(&mut self) -> Option<Token<P>> {
         self.previous = self.current.take();
-        self.previous.map(Token::Glyph)
+        self.previous.map(|g| Token::Glyph(g.change_policy()))
+    }
+
+    fn process<P: Policy>(&mut self, chunk: &[char]) -> ParseAction<P> {
+        self.process_in(chunk, None)
     }
 
-    fn process(&mut self, chunk: &[char]) -> ParseAction {
+    fn process_in<P: Policy>(&mut self, chunk: &[char], policy: Option<P>) -> ParseAction<P> {
         macro_rules! finish {
             ($glyph:expr) => {finish!($glyph, 0)};
             ($glyph:expr, $len:expr) => {{
@@ -236,7 +216,7 @@ impl TengwarMode for Gondor {
                 self.previous = Some(glyph);
 
                 ParseAction::MatchedToken {
-                    token: Token::Glyph(glyph),
+                    token: Token::Glyph(glyph.change_policy()),
                     len: $len,
                 }
             }};
@@ -246,7 +226,7 @@ impl TengwarMode for Gondor {
 
         if let [ESC, ESC_NOP, ..] = chunk {
             self.previous = None;
-            ParseAction::matched_opt(self.current.take().map(Token::Glyph), 2)
+            ParseAction::matched_opt(self.current.take().map(|g| Token::Glyph(g.change_policy())), 2)
         } else if let [ESC, _, ..] = chunk {
             ParseAction::ESC_BACKSLASH
         } else if let Some(current) = &mut self.current {
@@ -266,7 +246,7 @@ impl TengwarMode for Gondor {
                         current.labial = true;
                         ParseAction::MatchedPart(1)
                     }
-                    ['s' | 'z'] if current.can_take_rince() => {
+                    ['s' | 'z'] if current.with_policy(policy).can_take_rince() => {
                         current.rince = true;
                         ParseAction::MatchedPart(1)
                     }
@@ -0,0 +1,111 @@
+use crate::{characters::*, policy::Policy, Token};
+use super::{beleriand, gondor::Gondor, Neighbors, ParseAction, TengwarMode};
+
+
+/// Tengwa for a consonantal initial I. In the Mode of Gondor, this would be
+///     [Yanta](TENGWA_YANTA), but here it is needed for the vowel E.
+pub const CONSONANT_I: char = TENGWA_ARA;
+
+
+/// The "Full" Mode of Gondor, used for writing Sindarin. Consonants take the
+///     same values as in the [Mode of Gondor](Gondor), but vowels are written
+///     with independent tengwar, as in the [Mode of Beleriand].
+///
+/// [Mode of Beleriand]: super::Beleriand
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GondorFull {
+    current: Option<Glyph>,
+    previous: Option<Glyph>,
+}
+
+impl TengwarMode for GondorFull {
+    fn finalize_in<P: Policy>(&self, token: &mut Token<P>, neighbors: &Neighbors<P>) {
+        if let Token::Glyph(glyph) = token {
+            glyph.decide_final(neighbors.word_final());
+
+            if let Some(Token::Glyph(_)) = neighbors.next(0) {
+                glyph.replace_base(TENGWA_ORE, TENGWA_ROMEN);
+            }
+        }
+    }
+
+    fn finish_current<P: Policy>(&mut self) -> Option<Token<P>> {
+        self.previous = self.current.take();
+        self.previous.map(|g| Token::Glyph(g.change_policy()))
+    }
+
+    fn process<P: Policy>(&mut self, chunk: &[char]) -> ParseAction<P> {
+        macro_rules! finish {
+            ($glyph:expr) => {finish!($glyph, 0)};
+            ($glyph:expr, $len:expr) => {{
+                let finished = $glyph;
+                self.current = None;
+                output!(finished, $len)
+            }};
+        }
+        macro_rules! output {
+            ($glyph:expr, $len:expr) => {{
+                let glyph: Glyph = $glyph;
+                self.previous = Some(glyph);
+
+                ParseAction::MatchedToken {
+                    token: Token::Glyph(glyph.change_policy()),
+                    len: $len,
+                }
+            }};
+        }
+
+        let initial: bool = self.previous.is_none();
+
+        if let [ESC, ESC_NOP, ..] = chunk {
+            self.previous = None;
+            ParseAction::matched_opt(self.current.take().map(|g| Token::Glyph(g.change_policy())), 2)
+        } else if let [ESC, _, ..] = chunk {
+            ParseAction::ESC_BACKSLASH
+        } else if let Some(current) = &mut self.current {
+            //  A glyph is currently being constructed, but this mode does not
+            //      have any modifications. Finish it.
+            finish!(*current)
+        } else {
+            //  Try to find a new glyph.
+
+            //  Check for special cases.
+            if let ['j'] = chunk {
+                finish!(Glyph::new_base(CONSONANT_I), 1)
+            } else if let ['æ'] = chunk {
+                self.current = Some(Glyph::new_base(beleriand::VOWEL_E));
+                output!(Glyph::new_base(beleriand::VOWEL_A), 1)
+            } else if let ['œ'] = chunk {
+                self.current = Some(Glyph::new_base(beleriand::VOWEL_E));
+                output!(Glyph::new_base(beleriand::VOWEL_O), 1)
+            }
+
+            //  Check for a consonant, using the values of the Mode of Gondor.
+            else if let Some((new, len)) = Gondor::find_consonant(chunk, initial) {
+                finish!(new, len)
+            }
+
+            //  Check for a vowel or diphthong, using the independent tengwar of
+            //      the Mode of Beleriand.
+            else if let Some(new) = beleriand::get_vowel_either(chunk) {
+                finish!(new, chunk.len())
+            } else {
+                //  An initial I, followed by a vowel, acts as a consonant.
+                if initial {
+                    if let ['i', rest @ ..] = chunk {
+                        if let Some(new) = beleriand::get_vowel_either(rest) {
+                            self.current = Some(new);
+
+                            return ParseAction::MatchedToken {
+                                token: Token::Glyph(CONSONANT_I.into()),
+                                len: chunk.len(),
+                            };
+                        }
+                    }
+                }
+
+                ParseAction::MatchedNone
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! This module defines the languages that may be written with the modes of this
+//!     crate.
+//!
+//! A language is not the same as a mode. A mode is an orthography, and there
+//!     may be several modes suitable for writing one language; Sindarin, for
+//!     example, may be written in the Mode of Gondor or the Mode of Beleriand.
+//!     Each [`Language`] has a primary mode, which should be used when no mode
+//!     has been chosen explicitly, and a list of all the modes that may be used
+//!     to write it.
+//!
+//! Modes are referred to by their names in the default
+//!     [`ModeRegistry`](super::ModeRegistry).
+//!
+//! # Example
+//! ```
+//! use tengwar::mode::{Language, ModeRegistry};
+//!
+//! assert_eq!(Language::Sindarin.primary_mode(), "gondor");
+//! assert!(Language::Sindarin.supports("beleriand"));
+//! assert!(!Language::Quenya.supports("beleriand"));
+//!
+//! let registry = ModeRegistry::new();
+//! let modes: Vec<&str> = registry.for_language(Language::Quenya)
+//!     .map(|entry| entry.name.as_str())
+//!     .collect();
+//!
+//! assert_eq!(modes, ["classical"]);
+//! ```
+
+use std::fmt::{Display, Formatter};
+
+
+/// A language which may be written in the Tengwar or the Cirth.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Language {
+    /// The High Elven of the Noldorin Exiles.
+    #[value(alias = "q", alias = "qya")]
+    Quenya,
+    /// The Grey Elven of Beleriand.
+    #[value(alias = "s", alias = "sjn")]
+    Sindarin,
+    /// The language of the Angles.
+    #[value(alias = "e", alias = "eng")]
+    English,
+}
+
+impl Language {
+    /// All languages, in the order they should be presented to a user.
+    pub const ALL: [Self; 3] = [Self::Quenya, Self::Sindarin, Self::English];
+
+    /// Return the name of the language, in lowercase.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Quenya => "quenya",
+            Self::Sindarin => "sindarin",
+            Self::English => "english",
+        }
+    }
+
+    /// Return the name of the mode that should be used for this language when
+    ///     no other mode is specified.
+    pub const fn primary_mode(&self) -> &'static str {
+        self.modes()[0]
+    }
+
+    /// Return the names of all modes that may be used for this language. The
+    ///     [primary mode](Self::primary_mode) is first.
+    pub const fn modes(&self) -> &'static [&'static str] {
+        match self {
+            Self::Quenya => &["classical"],
+            Self::Sindarin => &[
+                "gondor",
+                "gondor-full",
+                "beleriand",
+                "angerthas-daeron",
+                "angerthas-moria",
+            ],
+            Self::English => &["english", "angerthas-moria"],
+        }
+    }
+
+    /// Return `true` if the mode with the given name may be used for this
+    ///     language. Case is ignored, but aliases are not recognized.
+    pub fn supports(&self, mode: &str) -> bool {
+        self.modes().iter().any(|name| name.eq_ignore_ascii_case(mode.trim()))
+    }
+
+    /// Iterate over all languages that may be written with the mode of the
+    ///     given name.
+    pub fn of_mode(mode: &str) -> impl Iterator<Item=Self> + '_ {
+        Self::ALL.into_iter().filter(move |lang| lang.supports(mode))
+    }
+}
+
+impl Display for Language {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
@@ -0,0 +1,331 @@
+//! This module defines inline markup, which allows the mode of transcription to
+//!     be changed partway through an input.
+//!
+//! A span of text is marked with an opening delimiter, the name of a mode, a
+//!     separator, and finally a closing delimiter. By default, this looks like
+//!     `{q:...}`; The delimiters and separator can be changed by specifying a
+//!     different [`Syntax`]. The name may be the name or any alias of a mode in
+//!     a [`ModeRegistry`], so that `{q:...}`, `{s:...}` and `{b:...}` select
+//!     the Classical Mode, the Mode of Gondor and the Mode of Beleriand. Spans
+//!     may be nested, and any text outside of a span is transcribed in the base
+//!     mode.
+//!
+//! The [escape character](ESC) works the same way as it does for a single mode.
+//!     A delimiter that follows it is not treated as markup, and is passed
+//!     through to the output unchanged. An opening delimiter that is not
+//!     followed by the name of a known mode and a separator, or a closing
+//!     delimiter outside of any span, is also left as text.
+//!
+//! All of the spans are read by [`Tokenizer`]s that are chained together, and
+//!     then passed through a single [`TokenIter`](crate::TokenIter), so that settings are applied
+//!     the same way across the whole output.
+//!
+//! # Example
+//! ```
+//! use tengwar::{Gondor, Quenya, TengwarMode, TranscriberSettings};
+//! use tengwar::mode::{markup::Markup, DynMode};
+//!
+//! let markup = Markup::new(Box::new(Gondor::default()));
+//! let text: String = markup.transcribe_with(
+//!     "mellon {q:namárië}",
+//!     TranscriberSettings::new(),
+//! );
+//!
+//! assert_eq!(text, format!(
+//!     "{}{}",
+//!     Gondor::transcribe::<String>("mellon "),
+//!     Quenya::transcribe::<String>("namárië"),
+//! ));
+//! ```
+
+use std::{fmt::{Display, Formatter}, ops::Range, str::FromStr};
+use crate::{characters::ESC, trace::SharedTrace, Directive};
+use super::{registry::BoxedSpanned, *};
+#[cfg(feature = "policy-custom")]
+use {super::registry::BoxedTokenizer, crate::policy::data::DataPolicy};
+
+
+/// The characters used to mark spans of text in another mode.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Syntax {
+    /// The character that begins a span, before the name of its mode.
+    pub open: char,
+    /// The character that separates the name of the mode from the text.
+    pub separator: char,
+    /// The character that ends a span.
+    pub close: char,
+}
+
+impl Syntax {
+    /// The default syntax, where a span looks like `{q:...}`.
+    pub const DEFAULT: Self = Self { open: '{', separator: ':', close: '}' };
+
+    /// If a span begins at the given position, return the name of its mode and
+    ///     the length of its opening markup, in bytes.
+    fn find_open<'s>(&self, input: &'s str, start: usize) -> Option<(&'s str, usize)> {
+        let rest: &str = input[start..].strip_prefix(self.open)?;
+        let len: usize = rest.find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))?;
+        let name: &str = &rest[..len];
+
+        (!name.is_empty() && rest[len..].starts_with(self.separator)).then(|| (
+            name,
+            self.open.len_utf8() + len + self.separator.len_utf8(),
+        ))
+    }
+
+    /// Split marked-up input into its [`Element`]s, and find any problems with
+    ///     the markup. A span may only select a mode in the registry. Adjacent
+    ///     text is merged into a single element.
+    pub fn parse<'s>(
+        &self,
+        input: &'s str,
+        registry: &ModeRegistry,
+    ) -> (Vec<Element<'s>>, Vec<MarkupError>) {
+        fn text(elements: &mut Vec<Element>, range: Range<usize>) {
+            match elements.last_mut() {
+                Some(Element::Text(last)) if last.end == range.start => last.end = range.end,
+                _ => elements.push(Element::Text(range)),
+            }
+        }
+
+        let mut elements: Vec<Element> = Vec::new();
+        let mut errors: Vec<MarkupError> = Vec::new();
+        //  The name, position, and validity of each span that is open.
+        let mut open: Vec<(&str, usize, bool)> = Vec::new();
+        let mut chars = input.char_indices().peekable();
+
+        while let Some((idx, c)) = chars.next() {
+            if c == ESC {
+                //  Keep the escape sequence intact, to be read by the mode.
+                let end: usize = chars.next().map_or(input.len(), |(i, c)| i + c.len_utf8());
+                text(&mut elements, idx..end);
+            } else if let Some(len) = Directive::find_str(&input[idx..]) {
+                //  Keep an inline directive intact, to be read by the tokenizer.
+                text(&mut elements, idx..idx + len);
+                while chars.next_if(|&(i, _)| i < idx + len).is_some() {}
+            } else if c == self.close && !open.is_empty() {
+                match open.pop() {
+                    Some((_, _, true)) => elements.push(Element::Close(idx..idx + c.len_utf8())),
+                    _ => text(&mut elements, idx..idx + c.len_utf8()),
+                }
+            } else if let Some((name, len)) = self.find_open(input, idx) {
+                //  A span of an unknown mode is left as text, but it must still
+                //      be tracked, so that its end is not mistaken for the end
+                //      of an outer span.
+                let valid: bool = registry.contains(name);
+                open.push((name, idx, valid));
+
+                if valid {
+                    elements.push(Element::Open(idx..idx + len, name));
+                    while chars.next_if(|&(i, _)| i < idx + len).is_some() {}
+                } else {
+                    errors.push(MarkupError::UnknownMode { name: name.into(), offset: idx });
+                    text(&mut elements, idx..idx + c.len_utf8());
+                }
+            } else {
+                text(&mut elements, idx..idx + c.len_utf8());
+            }
+        }
+
+        for (name, offset, _) in open.into_iter().filter(|&(_, _, valid)| valid) {
+            errors.push(MarkupError::Unclosed { name: name.into(), offset });
+        }
+
+        (elements, errors)
+    }
+}
+
+impl Default for Syntax {
+    fn default() -> Self { Self::DEFAULT }
+}
+
+impl Display for Syntax {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.open, self.separator, self.close)
+    }
+}
+
+/// A syntax is parsed from exactly three characters: The opening delimiter,
+///     the separator, and the closing delimiter, such as `{:}` or `[|]`.
+impl FromStr for Syntax {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.chars().collect::<Vec<_>>()[..] {
+            [open, separator, close] if open == ESC || separator == ESC || close == ESC => {
+                Err(format!("the escape character {ESC:?} cannot be used for markup"))
+            }
+            [open, separator, close]
+            if open != separator && open != close && separator != close
+                && ![open, separator, close].iter().any(|c| c.is_alphanumeric())
+            => Ok(Self { open, separator, close }),
+            _ => Err(format!(
+                "markup syntax must be three distinct symbols, such as \"{}\"",
+                Self::DEFAULT,
+            )),
+        }
+    }
+}
+
+
+/// A single element of marked-up input.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Element<'s> {
+    /// Text to be transcribed in the current mode.
+    Text(Range<usize>),
+    /// The start of a span in another mode, with the name of the mode.
+    Open(Range<usize>, &'s str),
+    /// The end of the innermost span.
+    Close(Range<usize>),
+}
+
+
+/// A problem with the markup of an input. Markup with a problem can still be
+///     transcribed, as described by each variant.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MarkupError {
+    /// A span names a mode that is not in the registry. The span is left as
+    ///     text.
+    UnknownMode { name: String, offset: usize },
+    /// A span is not closed before the end of the input. The span is closed
+    ///     implicitly.
+    Unclosed { name: String, offset: usize },
+}
+
+impl Display for MarkupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownMode { name, offset } => {
+                write!(f, "unknown mode `{name}` at byte {offset}")
+            }
+            Self::Unclosed { name, offset } => {
+                write!(f, "span of mode `{name}` at byte {offset} is not closed")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MarkupError {}
+
+
+/// A mode which transcribes text in a base mode, except for spans that are
+///     marked to be transcribed in other modes.
+pub struct Markup {
+    /// The mode used for any text outside of a span.
+    pub base: Box<dyn DynMode>,
+    /// The modes which may be selected by name within the text.
+    pub registry: ModeRegistry,
+    /// The characters used to mark spans.
+    pub syntax: Syntax,
+}
+
+impl Markup {
+    /// Use markup with the default [`Syntax`] and the default [`ModeRegistry`].
+    pub fn new(base: Box<dyn DynMode>) -> Self {
+        Self {
+            base,
+            registry: ModeRegistry::new(),
+            syntax: Syntax::DEFAULT,
+        }
+    }
+
+    /// Change the modes which may be selected by name.
+    pub fn with_registry(mut self, registry: ModeRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Change the characters used to mark spans.
+    pub const fn with_syntax(mut self, syntax: Syntax) -> Self {
+        self.syntax = syntax;
+        self
+    }
+
+    /// Split marked-up input into its [`Element`]s.
+    pub fn parse<'s>(&self, input: &'s str) -> Vec<Element<'s>> {
+        self.syntax.parse(input, &self.registry).0
+    }
+
+    /// Check marked-up input for problems. Any problems are reported in the
+    ///     order they are found.
+    pub fn check(&self, input: &str) -> Result<(), Vec<MarkupError>> {
+        let errors: Vec<MarkupError> = self.syntax.parse(input, &self.registry).1;
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+
+    /// Chain together the tokens of every span, each read by its own mode from
+    ///     the range of the input that the span covers.
+    fn chain<T: 'static>(
+        &self,
+        input: &str,
+        read: impl Fn(&dyn DynMode, Range<usize>) -> Box<dyn Iterator<Item=T>>,
+    ) -> Box<dyn Iterator<Item=T>> {
+        let mut modes: Vec<Box<dyn DynMode>> = Vec::new();
+        let mut tokenizers: Vec<Box<dyn Iterator<Item=T>>> = Vec::new();
+
+        for element in self.parse(input) {
+            match element {
+                Element::Text(range) => {
+                    let mode: &dyn DynMode = modes.last().unwrap_or(&self.base).as_ref();
+                    tokenizers.push(read(mode, range));
+                }
+                Element::Open(_, name) => {
+                    modes.push(self.registry.get(name).expect("mode is not registered"));
+                }
+                Element::Close(_) => {
+                    modes.pop();
+                }
+            }
+        }
+
+        Box::new(tokenizers.into_iter().flatten())
+    }
+
+    /// Chain together the tokens of every span, each in its own mode.
+    fn spanned(
+        &self,
+        input: &str,
+        trace: Option<&SharedTrace>,
+        directives: bool,
+    ) -> BoxedSpanned {
+        self.chain(input, |mode, range| {
+            let start: usize = range.start;
+            let chars: usize = input[..start].chars().count();
+
+            let spanned: BoxedSpanned = match trace {
+                _ if directives => mode.boxed_directed(&input[range], trace),
+                Some(trace) => mode.boxed_traced(&input[range], trace),
+                None => mode.boxed_spanned(&input[range]),
+            };
+
+            Box::new(spanned.map(move |(token, span)| (token, span.shift(start, chars))))
+        })
+    }
+}
+
+impl DynMode for Markup {
+    fn boxed_spanned(&self, input: &str) -> BoxedSpanned {
+        self.spanned(input, None, false)
+    }
+
+    fn boxed_traced(&self, input: &str, trace: &SharedTrace) -> BoxedSpanned {
+        self.spanned(input, Some(trace), false)
+    }
+
+    fn boxed_directed(&self, input: &str, trace: Option<&SharedTrace>) -> BoxedSpanned {
+        self.spanned(input, trace, true)
+    }
+
+    #[cfg(feature = "policy-custom")]
+    fn boxed_tokenizer_in(
+        &self,
+        input: &str,
+        policy: DataPolicy,
+        directives: bool,
+    ) -> BoxedTokenizer<DataPolicy> {
+        self.chain(input, |mode, range| {
+            mode.boxed_tokenizer_in(&input[range], policy, directives)
+        })
+    }
+}
@@ -1,75 +1,76 @@
-use crate::{characters::*, Token};
-use super::{ParseAction, TengwarMode};
+use crate::{characters::*, policy::Policy, Token};
+use super::{table::{lookup, table, Table}, ParseAction, TengwarMode};
 
 
 pub const CARRIER_DIPH_I: char = TENGWA_YANTA;
 pub const CARRIER_DIPH_U: char = TENGWA_URE;
 
 
+pub const CONSONANTS: Table<char> = table! {
+    //  Tincotéma.
+    ['t']           /**/ => TEMA_TINCO.single_dn,
+    ['d']
+    | ['n', 'd']    /**/ => TEMA_TINCO.double_dn,
+    ['t', 'h']
+    | ['þ'] | ['θ'] /**/ => TEMA_TINCO.single_up,
+    ['n', 't']      /**/ => TEMA_TINCO.double_up,
+    ['n']           /**/ => TEMA_TINCO.double_sh,
+    ['r']           /**/ => TEMA_TINCO.single_sh,
+
+    //  Parmatéma.
+    ['p']           /**/ => TEMA_PARMA.single_dn,
+    ['b']
+    | ['m', 'b']    /**/ => TEMA_PARMA.double_dn,
+    ['f'] | ['φ']   /**/ => TEMA_PARMA.single_up,
+    ['m', 'p']      /**/ => TEMA_PARMA.double_up,
+    ['m']           /**/ => TEMA_PARMA.double_sh,
+    ['v']           /**/ => TEMA_PARMA.single_sh,
+
+    //  Calmatéma.
+    ['c'] | ['k']   /**/ => TEMA_CALMA.single_dn,
+    ['g']
+    | ['n', 'g']    /**/ => TEMA_CALMA.double_dn,
+    ['c', 'h']
+    | ['k', 'h']    /**/ => TEMA_CALMA.single_up,
+    ['n', 'c']      /**/ => TEMA_CALMA.double_up,
+    ['ñ']           /**/ => TEMA_CALMA.double_sh,
+    // ['y']           /**/ => TEMA_CALMA.single_sh, // Special case.
+
+    //  NOTE: This sound vanished from Quenya very early. However, it may
+    //      still be useful to have a way to write, either for representing
+    //      very old texts or (more likely) to represent a "vanished"
+    //      consonant (such as in "ʒalda").
+    //  https://at.mansbjorkman.net/teng_quenya.htm#note_anna
+    ['g', 'h']
+    | ['ɣ'] | ['ʒ'] /**/ => TEMA_CALMA.single_sh,
+
+    //  Qessetéma.
+    ['q']
+    | ['q', 'u']
+    | ['c', 'w']
+    | ['k', 'w']    /**/ => TEMA_QESSE.single_dn,
+    ['n', 'g', 'w'] /**/ => TEMA_QESSE.double_dn,
+    ['h', 'w']      /**/ => TEMA_QESSE.single_up,
+    ['n', 'q', 'u']
+    | ['n', 'q']    /**/ => TEMA_QESSE.double_up,
+    ['ñ', 'w']      /**/ => TEMA_QESSE.double_sh,
+    ['w']           /**/ => TEMA_QESSE.single_sh,
+
+    //  Irregulars.
+    // ['r']           /**/ => TENGWA_ROMEN, // Only with following vowel.
+    ['r', 'd']      /**/ => TENGWA_ARDA,
+    ['l']           /**/ => TENGWA_LAMBE,
+    ['l', 'd']      /**/ => TENGWA_ALDA,
+    ['s']           /**/ => TENGWA_SILME,
+    ['s', 's']
+    | ['z'] | ['ß'] /**/ => TENGWA_ESSE,
+
+    ['h']           /**/ => TENGWA_HYARMEN,
+};
+
+
 pub const fn consonant_char(slice: &[char]) -> Option<char> {
-    Some(match slice {
-        //  Tincotéma.
-        ['t']           /**/ => TEMA_TINCO.single_dn,
-        ['d']
-        | ['n', 'd']    /**/ => TEMA_TINCO.double_dn,
-        ['t', 'h']
-        | ['þ'] | ['θ'] /**/ => TEMA_TINCO.single_up,
-        ['n', 't']      /**/ => TEMA_TINCO.double_up,
-        ['n']           /**/ => TEMA_TINCO.double_sh,
-        ['r']           /**/ => TEMA_TINCO.single_sh,
-
-        //  Parmatéma.
-        ['p']           /**/ => TEMA_PARMA.single_dn,
-        ['b']
-        | ['m', 'b']    /**/ => TEMA_PARMA.double_dn,
-        ['f'] | ['φ']   /**/ => TEMA_PARMA.single_up,
-        ['m', 'p']      /**/ => TEMA_PARMA.double_up,
-        ['m']           /**/ => TEMA_PARMA.double_sh,
-        ['v']           /**/ => TEMA_PARMA.single_sh,
-
-        //  Calmatéma.
-        ['c'] | ['k']   /**/ => TEMA_CALMA.single_dn,
-        ['g']
-        | ['n', 'g']    /**/ => TEMA_CALMA.double_dn,
-        ['c', 'h']
-        | ['k', 'h']    /**/ => TEMA_CALMA.single_up,
-        ['n', 'c']      /**/ => TEMA_CALMA.double_up,
-        ['ñ']           /**/ => TEMA_CALMA.double_sh,
-        // ['y']           /**/ => TEMA_CALMA.single_sh, // Special case.
-
-        //  NOTE: This sound vanished from Quenya very early. However, it may
-        //      still be useful to have a way to write, either for representing
-        //      very old texts or (more likely) to represent a "vanished"
-        //      consonant (such as in "ʒalda").
-        //  https://at.mansbjorkman.net/teng_quenya.htm#note_anna
-        ['g', 'h']
-        | ['ɣ'] | ['ʒ'] /**/ => TEMA_CALMA.single_sh,
-
-        //  Qessetéma.
-        ['q']
-        | ['q', 'u']
-        | ['c', 'w']
-        | ['k', 'w']    /**/ => TEMA_QESSE.single_dn,
-        ['n', 'g', 'w'] /**/ => TEMA_QESSE.double_dn,
-        ['h', 'w']      /**/ => TEMA_QESSE.single_up,
-        ['n', 'q', 'u']
-        | ['n', 'q']    /**/ => TEMA_QESSE.double_up,
-        ['ñ', 'w']      /**/ => TEMA_QESSE.double_sh,
-        ['w']           /**/ => TEMA_QESSE.single_sh,
-
-        //  Irregulars.
-        // ['r']           /**/ => TENGWA_ROMEN, // Only with following vowel.
-        ['r', 'd']      /**/ => TENGWA_ARDA,
-        ['l']           /**/ => TENGWA_LAMBE,
-        ['l', 'd']      /**/ => TENGWA_ALDA,
-        ['s']           /**/ => TENGWA_SILME,
-        ['s', 's']
-        | ['z'] | ['ß'] /**/ => TENGWA_ESSE,
-
-        ['h']           /**/ => TENGWA_HYARMEN,
-
-        _ => { return None; }
-    })
+    lookup(CONSONANTS, slice)
 }
 
 
@@ -87,37 +88,40 @@ pub const fn get_consonant(slice: &[char]) -> Option<Glyph> {
 }
 
 
-pub const fn get_diphthong(slice: &[char]) -> Option<Glyph> {
-    match slice {
-        ['a', 'i'] => Some(Glyph::new_both(CARRIER_DIPH_I, TEHTA_A)),
-        ['o', 'i'] => Some(Glyph::new_both(CARRIER_DIPH_I, TEHTA_O)),
-        ['u', 'i'] => Some(Glyph::new_both(CARRIER_DIPH_I, TEHTA_U)),
+pub const DIPHTHONGS: Table<Glyph> = table! {
+    ['a', 'i'] => Glyph::new_both(CARRIER_DIPH_I, TEHTA_A),
+    ['o', 'i'] => Glyph::new_both(CARRIER_DIPH_I, TEHTA_O),
+    ['u', 'i'] => Glyph::new_both(CARRIER_DIPH_I, TEHTA_U),
 
-        ['a', 'u'] => Some(Glyph::new_both(CARRIER_DIPH_U, TEHTA_A)),
-        ['e', 'u'] => Some(Glyph::new_both(CARRIER_DIPH_U, TEHTA_E)),
-        ['i', 'u'] => Some(Glyph::new_both(CARRIER_DIPH_U, TEHTA_I)),
+    ['a', 'u'] => Glyph::new_both(CARRIER_DIPH_U, TEHTA_A),
+    ['e', 'u'] => Glyph::new_both(CARRIER_DIPH_U, TEHTA_E),
+    ['i', 'u'] => Glyph::new_both(CARRIER_DIPH_U, TEHTA_I),
+};
 
-        _ => None,
-    }
+
+pub const fn get_diphthong(slice: &[char]) -> Option<Glyph> {
+    lookup(DIPHTHONGS, slice)
 }
 
 
+/// Each vowel is a [`Tehta`], and whether it is long.
+pub const TEHTAR: Table<(Tehta, bool)> = table! {
+    ['a'] | ['ä'] => (TEHTA_A, false),
+    ['e'] | ['ë'] => (TEHTA_E, false),
+    ['i'] | ['ï'] => (TEHTA_I, false),
+    ['o'] | ['ö'] => (TEHTA_O, false),
+    ['u'] | ['ü'] => (TEHTA_U, false),
+
+    ['á'] | ['ā'] | ['a', 'a'] => (TEHTA_A, true),
+    ['é'] | ['ē'] | ['e', 'e'] => (TEHTA_E, true),
+    ['í'] | ['ī'] | ['i', 'i'] => (TEHTA_I, true),
+    ['ó'] | ['ō'] | ['o', 'o'] => (TEHTA_O, true),
+    ['ú'] | ['ū'] | ['u', 'u'] => (TEHTA_U, true),
+};
+
+
 pub const fn get_tehta(slice: &[char]) -> Option<(Tehta, bool)> {
-    match slice {
-        ['a'] | ['ä'] => Some((TEHTA_A, false)),
-        ['e'] | ['ë'] => Some((TEHTA_E, false)),
-        ['i'] | ['ï'] => Some((TEHTA_I, false)),
-        ['o'] | ['ö'] => Some((TEHTA_O, false)),
-        ['u'] | ['ü'] => Some((TEHTA_U, false)),
-
-        ['á'] | ['ā'] | ['a', 'a'] => Some((TEHTA_A, true)),
-        ['é'] | ['ē'] | ['e', 'e'] => Some((TEHTA_E, true)),
-        ['í'] | ['ī'] | ['i', 'i'] => Some((TEHTA_I, true)),
-        ['ó'] | ['ō'] | ['o', 'o'] => Some((TEHTA_O, true)),
-        ['ú'] | ['ū'] | ['u', 'u'] => Some((TEHTA_U, true)),
-
-        _ => None,
-    }
+    lookup(TEHTAR, slice)
 }
 
 
@@ -141,12 +145,16 @@ pub struct Quenya {
 }
 
 impl TengwarMode for Quenya {
-    fn finish_current(&mut self) -> Option<Token> {
+    fn finish_current<P: Policy>(&mut self) -> Option<Token<P>> {
         self.previous = self.current.take();
-        self.previous.map(Token::Glyph)
+        self.previous.map(|g| Token::Glyph(g.change_policy()))
+    }
+
+    fn process<P: Policy>(&mut self, chunk: &[char]) -> ParseAction<P> {
+        self.process_in(chunk, None)
     }
 
-    fn process(&mut self, chunk: &[char]) -> ParseAction {
+    fn process_in<P: Policy>(&mut self, chunk: &[char], policy: Option<P>) -> ParseAction<P> {
         macro_rules! finish {
             ($glyph:expr) => {finish!($glyph, 0)};
             ($glyph:expr, $len:expr) => {replace!($glyph, None, $len)}
@@ -158,7 +166,7 @@ impl TengwarMode for Quenya {
                 self.previous = Some(finished);
 
                 ParseAction::MatchedToken {
-                    token: Token::Glyph(finished),
+                    token: Token::Glyph(finished.change_policy()),
                     len: $len,
                 }
             }};
@@ -168,7 +176,7 @@ impl TengwarMode for Quenya {
 
         if let [ESC, ESC_NOP, ..] = chunk {
             self.previous = None;
-            ParseAction::matched_opt(self.current.take().map(Token::Glyph), 2)
+            ParseAction::matched_opt(self.current.take().map(|g| Token::Glyph(g.change_policy())), 2)
         } else if let [ESC, _, ..] = chunk {
             ParseAction::ESC_BACKSLASH
         } else if let Some(current) = &mut self.current {
@@ -181,12 +189,7 @@ impl TengwarMode for Quenya {
                         current.palatal = true;
                         ParseAction::MatchedPart(1)
                     }
-                    ['s' | 'z'] if current.can_take_rince() => {
-                        //  FIXME: This is a POLICY LEVEL DECISION. It CANNOT be
-                        //      made at the Mode level while also assuming the
-                        //      Policy to be Standard.
-                        //  TODO: Make TengwarMode generic over Policy, or find
-                        //      a way to defer this decision to the TokenIter.
+                    ['s' | 'z'] if current.with_policy(policy).can_take_rince() => {
                         current.rince = true;
                         ParseAction::MatchedPart(1)
                     }
@@ -274,7 +277,7 @@ impl TengwarMode for Quenya {
             else if let Some(new) = get_vowel_glyph(chunk) {
                 self.previous = Some(new);
                 ParseAction::MatchedToken {
-                    token: Token::Glyph(new),
+                    token: Token::Glyph(new.change_policy()),
                     len: chunk.len(),
                 }
             } else {
@@ -0,0 +1,430 @@
+//! This module defines a registry of modes, which can be selected by name at
+//!     runtime.
+//!
+//! Because the [`TengwarMode`] trait has associated constants and generic
+//!     methods, it cannot be made into a trait object. The [`DynMode`] trait
+//!     is an object-safe counterpart, implemented for every mode that can be
+//!     cloned, and the [`ModeRegistry`] stores factories that produce boxed
+//!     instances of it.
+//!
+//! # Example
+//! ```
+//! use tengwar::{mode::ModeRegistry, Quenya, TengwarMode, TranscriberSettings};
+//!
+//! let registry = ModeRegistry::new();
+//! let mode = registry.get("quenya").unwrap();
+//!
+//! let text: String = mode.transcribe_with("namárië", TranscriberSettings::new());
+//! assert_eq!(text, Quenya::transcribe::<String>("namárië"));
+//! ```
+
+use std::fmt::{Debug, Formatter};
+#[cfg(feature = "policy-custom")]
+use crate::policy::data::DataPolicy;
+use crate::{
+    cirth,
+    span::{Mapped, Span, SpanIter},
+    trace::SharedTrace,
+    Token,
+    TokenIter,
+    TranscriberSettings,
+};
+use super::*;
+
+
+/// An iterator over the [`Token`]s of a transcription, with its mode erased.
+pub type BoxedTranscriber = Box<dyn Iterator<Item=Token>>;
+
+/// An iterator over the [`Token`]s produced directly by a mode, before the
+///     rules of a [`TokenIter`] are applied, with its mode erased.
+pub type BoxedTokenizer<P = Standard> = Box<dyn Iterator<Item=Token<P>>>;
+
+/// An iterator over the [`Token`]s produced directly by a mode, along with
+///     their [`Span`]s in the input, with its mode erased.
+pub type BoxedSpanned = Box<dyn Iterator<Item=(Token, Span)>>;
+
+/// An iterator over the [`Token`]s of a transcription, along with their
+///     positions in the input and output, with its mode erased.
+pub type BoxedMapped = Box<dyn Iterator<Item=Mapped>>;
+
+
+/// An object-safe counterpart to [`TengwarMode`]. This is implemented for any
+///     mode that can be cloned, allowing it to be boxed and selected at
+///     runtime. A boxed mode can be shared between threads.
+pub trait DynMode: Send + Sync {
+    /// Set up a [`Tokenizer`] over the characters of an input string, using a
+    ///     copy of the current state of this mode, and yield each [`Token`]
+    ///     with its [`Span`] in the input.
+    fn boxed_spanned(&self, input: &str) -> BoxedSpanned;
+
+    /// Set up a [`Tokenizer`] in the same way as [`Self::boxed_spanned`], and
+    ///     record the steps it takes in a [`Trace`].
+    ///
+    /// By default, nothing is recorded.
+    ///
+    /// [`Trace`]: crate::trace::Trace
+    fn boxed_traced(&self, input: &str, _trace: &SharedTrace) -> BoxedSpanned {
+        self.boxed_spanned(input)
+    }
+
+    /// Set up a [`Tokenizer`] in the same way as [`Self::boxed_spanned`], or as
+    ///     [`Self::boxed_traced`] if there is a [`Trace`], which also reads any
+    ///     inline [`Directive`]s in the input.
+    ///
+    /// By default, directives are not read, and are transcribed as text.
+    ///
+    /// [`Directive`]: crate::Directive
+    /// [`Trace`]: crate::trace::Trace
+    fn boxed_directed(&self, input: &str, trace: Option<&SharedTrace>) -> BoxedSpanned {
+        match trace {
+            Some(trace) => self.boxed_traced(input, trace),
+            None => self.boxed_spanned(input),
+        }
+    }
+
+    /// Set up a [`Tokenizer`] over the characters of an input string, using a
+    ///     copy of the current state of this mode.
+    fn boxed_tokenizer(&self, input: &str) -> BoxedTokenizer {
+        Box::new(self.boxed_spanned(input).map(|(token, _)| token))
+    }
+
+    /// Set up a [`Tokenizer`] in the same way as [`Self::boxed_tokenizer`], or
+    ///     as [`Self::boxed_directed`] if `directives` is `true`, under a
+    ///     [`DataPolicy`]. The mode consults the rules of the policy while it
+    ///     reads the input, such as whether a tengwa may take a sa-rincë.
+    ///
+    /// By default, the input is read under the [`Standard`] policy, and only
+    ///     the tokens produced are given the `DataPolicy`.
+    #[cfg(feature = "policy-custom")]
+    fn boxed_tokenizer_in(
+        &self,
+        input: &str,
+        policy: DataPolicy,
+        directives: bool,
+    ) -> BoxedTokenizer<DataPolicy> {
+        let spanned: BoxedSpanned = match directives {
+            true => self.boxed_directed(input, None),
+            false => self.boxed_spanned(input),
+        };
+
+        Box::new(spanned.map(move |(token, _)| token.with_policy(Some(policy))))
+    }
+
+    /// Set up a [`Transcriber`] over the characters of an input string, using
+    ///     a copy of the current state of this mode, and yield each [`Token`]
+    ///     with its positions in the input and the output.
+    fn boxed_mapped(&self, input: &str, settings: TranscriberSettings) -> BoxedMapped {
+        Box::new(SpanIter::new(self.boxed_spanned(input), settings))
+    }
+
+    /// Set up a [`Transcriber`] in the same way as [`Self::boxed_mapped`], and
+    ///     record every step of the transcription in a [`Trace`].
+    ///
+    /// [`Trace`]: crate::trace::Trace
+    fn boxed_explained(
+        &self,
+        input: &str,
+        settings: TranscriberSettings,
+        trace: &SharedTrace,
+    ) -> BoxedMapped {
+        Box::new(SpanIter::traced(self.boxed_traced(input, trace), settings, trace.clone()))
+    }
+
+    /// Set up a [`Transcriber`] over the characters of an input string, using
+    ///     a copy of the current state of this mode.
+    fn boxed_transcriber(
+        &self,
+        input: &str,
+        settings: TranscriberSettings,
+    ) -> BoxedTranscriber {
+        Box::new(TokenIter::new(self.boxed_tokenizer(input)).with_settings(settings))
+    }
+
+    /// Transcribe an input string, and collect the output into a [`String`].
+    fn transcribe_with(&self, input: &str, settings: TranscriberSettings) -> String {
+        self.boxed_transcriber(input, settings).collect()
+    }
+}
+
+impl<M: TengwarMode + Clone + Send + Sync + 'static> DynMode for M {
+    fn boxed_spanned(&self, input: &str) -> BoxedSpanned {
+        Box::new(Tokenizer::<M>::with_mode(input, self.clone()).spanned())
+    }
+
+    fn boxed_traced(&self, input: &str, trace: &SharedTrace) -> BoxedSpanned {
+        Box::new(Tokenizer::<M>::with_mode(input, self.clone())
+            .with_trace(trace.clone())
+            .spanned())
+    }
+
+    fn boxed_directed(&self, input: &str, trace: Option<&SharedTrace>) -> BoxedSpanned {
+        let tokenizer = Tokenizer::<M>::with_mode(input, self.clone()).with_directives();
+
+        match trace {
+            Some(trace) => Box::new(tokenizer.with_trace(trace.clone()).spanned()),
+            None => Box::new(tokenizer.spanned()),
+        }
+    }
+
+    fn boxed_tokenizer(&self, input: &str) -> BoxedTokenizer {
+        Box::new(Tokenizer::<M>::with_mode(input, self.clone()))
+    }
+
+    #[cfg(feature = "policy-custom")]
+    fn boxed_tokenizer_in(
+        &self,
+        input: &str,
+        policy: DataPolicy,
+        directives: bool,
+    ) -> BoxedTokenizer<DataPolicy> {
+        let tokenizer = Tokenizer::<M, DataPolicy>::with_mode(input, self.clone())
+            .with_policy(policy);
+
+        match directives {
+            true => Box::new(tokenizer.with_directives()),
+            false => Box::new(tokenizer),
+        }
+    }
+}
+
+
+/// A boxed mode which reads inline [`Directive`]s from all of its input.
+///
+/// [`Directive`]: crate::Directive
+pub struct Directed(pub Box<dyn DynMode>);
+
+impl DynMode for Directed {
+    fn boxed_spanned(&self, input: &str) -> BoxedSpanned {
+        self.0.boxed_directed(input, None)
+    }
+
+    fn boxed_traced(&self, input: &str, trace: &SharedTrace) -> BoxedSpanned {
+        self.0.boxed_directed(input, Some(trace))
+    }
+
+    fn boxed_directed(&self, input: &str, trace: Option<&SharedTrace>) -> BoxedSpanned {
+        self.0.boxed_directed(input, trace)
+    }
+
+    #[cfg(feature = "policy-custom")]
+    fn boxed_tokenizer_in(
+        &self,
+        input: &str,
+        policy: DataPolicy,
+        _directives: bool,
+    ) -> BoxedTokenizer<DataPolicy> {
+        self.0.boxed_tokenizer_in(input, policy, true)
+    }
+}
+
+
+/// A function producing a new instance of a mode.
+type Factory = Box<dyn Fn() -> Box<dyn DynMode> + Send + Sync>;
+
+
+/// A mode stored in a [`ModeRegistry`], under a name and any number of
+///     aliases.
+pub struct ModeEntry {
+    pub name: String,
+    pub aliases: Vec<String>,
+    /// A short description of the mode, suitable for display to a user.
+    pub description: String,
+    factory: Factory,
+}
+
+impl ModeEntry {
+    /// Define an entry which produces modes from a function.
+    pub fn new(
+        name: impl Into<String>,
+        aliases: &[&str],
+        description: impl Into<String>,
+        factory: impl Fn() -> Box<dyn DynMode> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            aliases: aliases.iter().map(|&s| s.into()).collect(),
+            description: description.into(),
+            factory: Box::new(factory),
+        }
+    }
+
+    /// Define an entry which produces the default state of a mode type.
+    pub fn of<M: TengwarMode + Clone + Default + Send + Sync + 'static>(
+        name: impl Into<String>,
+        aliases: &[&str],
+        description: impl Into<String>,
+    ) -> Self {
+        Self::new(name, aliases, description, || Box::new(M::default()))
+    }
+
+    /// Create a new instance of the mode.
+    pub fn create(&self) -> Box<dyn DynMode> {
+        (self.factory)()
+    }
+
+    /// Return `true` if the given name is an alias of this mode. Case is
+    ///     ignored.
+    pub fn has_alias(&self, name: &str) -> bool {
+        self.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name))
+    }
+
+    /// Return `true` if the given name is the name of this mode. Case is
+    ///     ignored.
+    pub fn has_name(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+}
+
+impl Debug for ModeEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModeEntry")
+            .field("name", &self.name)
+            .field("aliases", &self.aliases)
+            .field("description", &self.description)
+            .finish_non_exhaustive()
+    }
+}
+
+
+/// A collection of modes, which can be looked up by name. A new registry
+///     contains all the default modes, and more may be added at runtime.
+///
+/// When a name is looked up, the names of modes are checked before their
+///     aliases, and the most recently registered mode is found first.
+#[derive(Debug)]
+pub struct ModeRegistry {
+    entries: Vec<ModeEntry>,
+}
+
+impl ModeRegistry {
+    /// Create a registry with no modes.
+    pub const fn empty() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    /// Create a registry with all the default modes.
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+
+        registry
+            .register(ModeEntry::of::<Quenya>(
+                "classical", &["c", "quenya", "q"],
+                "The Classical Mode, used for Quenya.",
+            ))
+            .register(ModeEntry::of::<Gondor>(
+                "gondor", &["g", "sindarin", "s"],
+                "The Mode of Gondor, used for Sindarin.",
+            ))
+            .register(ModeEntry::of::<GondorFull>(
+                "gondor-full", &["gf"],
+                "The \"Full\" Mode of Gondor, used for Sindarin.",
+            ))
+            .register(ModeEntry::of::<Beleriand>(
+                "beleriand", &["b"],
+                "The Mode of Beleriand, used for Sindarin.",
+            ))
+            .register(ModeEntry::of::<General>(
+                "english", &["e", "general"],
+                "An orthographic \"general\" mode for English.",
+            ))
+            .register(ModeEntry::of::<cirth::Daeron>(
+                "angerthas-daeron", &["daeron"],
+                "The Angerthas Daeron, used for writing the Cirth.",
+            ))
+            .register(ModeEntry::of::<cirth::Moria>(
+                "angerthas-moria", &["moria"],
+                "The Angerthas Moria, used for writing the Cirth.",
+            ))
+            .register(ModeEntry::new(
+                "auto", &["a"],
+                "Detect Quenya or Sindarin for each word, and use its primary mode.",
+                || Box::new(Auto::new(auto::Unit::Word)),
+            ))
+            .register(ModeEntry::new(
+                "auto-line", &["al"],
+                "Detect Quenya or Sindarin for each line, and use its primary mode.",
+                || Box::new(Auto::new(auto::Unit::Line)),
+            ));
+
+        registry
+    }
+
+    /// Add a mode to the registry. Any existing mode with the same name is
+    ///     removed.
+    pub fn register(&mut self, entry: ModeEntry) -> &mut Self {
+        self.entries.retain(|old| !old.has_name(&entry.name));
+        self.entries.push(entry);
+        self
+    }
+
+    /// Add a mode type to the registry, under a name and any number of
+    ///     aliases. Any existing mode with the same name is removed.
+    pub fn register_mode<M: TengwarMode + Clone + Default + Send + Sync + 'static>(
+        &mut self,
+        name: impl Into<String>,
+        aliases: &[&str],
+        description: impl Into<String>,
+    ) -> &mut Self {
+        self.register(ModeEntry::of::<M>(name, aliases, description))
+    }
+
+    /// Add a mode defined by a file to the registry. The file is resolved into
+    ///     a new [`CustomMode`] each time the mode is requested.
+    #[cfg(feature = "mode-custom")]
+    pub fn register_file(
+        &mut self,
+        name: impl Into<String>,
+        aliases: &[&str],
+        description: impl Into<String>,
+        file: custom::file::ModeFile,
+    ) -> &mut Self {
+        self.register(ModeEntry::new(name, aliases, description, move || {
+            Box::new(file.resolve())
+        }))
+    }
+
+    /// Remove a mode from the registry by its name. Returns the entry of the
+    ///     mode, if there was one.
+    pub fn unregister(&mut self, name: &str) -> Option<ModeEntry> {
+        let idx: usize = self.entries.iter().position(|e| e.has_name(name))?;
+        Some(self.entries.remove(idx))
+    }
+
+    /// Find the entry for a mode, by its name or one of its aliases.
+    pub fn entry(&self, name: &str) -> Option<&ModeEntry> {
+        let name: &str = name.trim();
+
+        self.entries.iter().rev().find(|e| e.has_name(name))
+            .or_else(|| self.entries.iter().rev().find(|e| e.has_alias(name)))
+    }
+
+    /// Create a new instance of a mode, by its name or one of its aliases.
+    pub fn get(&self, name: &str) -> Option<Box<dyn DynMode>> {
+        self.entry(name).map(ModeEntry::create)
+    }
+
+    /// Return `true` if a mode can be found with the given name or alias.
+    pub fn contains(&self, name: &str) -> bool {
+        self.entry(name).is_some()
+    }
+
+    /// Iterate over the entries of all modes, in the order of registration.
+    pub fn list(&self) -> impl Iterator<Item=&ModeEntry> {
+        self.entries.iter()
+    }
+
+    /// Iterate over the names of all modes, in the order of registration.
+    pub fn names(&self) -> impl Iterator<Item=&str> {
+        self.entries.iter().map(|e| e.name.as_str())
+    }
+
+    /// Iterate over the entries of all modes that may be used for a language,
+    ///     in the order of registration.
+    pub fn for_language(&self, language: Language) -> impl Iterator<Item=&ModeEntry> {
+        self.entries.iter().filter(move |e| language.supports(&e.name))
+    }
+}
+
+impl Default for ModeRegistry {
+    fn default() -> Self { Self::new() }
+}
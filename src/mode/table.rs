@@ -0,0 +1,59 @@
+//! Tables mapping sequences of input to their representations in the Tengwar.
+//!
+//! The tables of the default modes are defined as constant data, rather than as
+//!     `match` expressions, so that they can be enumerated; This allows a mode
+//!     to be inspected, or exported as a mode file. Lookups are still `const`.
+
+
+/// A table of values, each of which is produced by a sequence of input
+///     [`char`]s. If a sequence appears more than once, the first is used.
+pub type Table<T> = &'static [(&'static [char], T)];
+
+
+/// Return `true` if two sequences of [`char`]s are identical.
+const fn chars_eq(a: &[char], b: &[char]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i: usize = 0;
+
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+
+        i += 1;
+    }
+
+    true
+}
+
+
+/// Find the value produced by a sequence of input in a [`Table`].
+pub const fn lookup<T: Copy>(table: &[(&[char], T)], slice: &[char]) -> Option<T> {
+    let mut i: usize = 0;
+
+    while i < table.len() {
+        let (key, value) = table[i];
+
+        if chars_eq(key, slice) {
+            return Some(value);
+        }
+
+        i += 1;
+    }
+
+    None
+}
+
+
+/// Define a [`Table`] with the syntax of a `match` expression. Any number of
+///     patterns may be given for the same value.
+macro_rules! table {
+    ($($([$($c:literal),+])|+ => $value:expr),* $(,)?) => {
+        &[$($((&[$($c),+], $value),)+)*]
+    };
+}
+
+pub(crate) use table;
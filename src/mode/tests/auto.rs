@@ -0,0 +1,77 @@
+use crate::mode::{auto::*, DynMode, Language};
+use crate::{Gondor, Quenya, TengwarMode, TranscriberSettings};
+
+
+#[test]
+fn test_auto_detect() {
+    //  Quenya.
+    for word in ["namárië", "laurië", "Quenya", "Oiolossë", "Eldalië", "hyarmen", "tyelpë"] {
+        assert!(score_word(word) < 0, "{word}: {}", score_word(word));
+    }
+
+    //  Sindarin.
+    for word in [
+        "Ennyn", "Durin", "pedo", "Elbereth", "Gilthoniel", "Edhellen",
+        "galadhremmin", "Annûn", "Nimloth", "ennorath", "Caradhras", "aegnor",
+    ] {
+        assert!(score_word(word) > 0, "{word}: {}", score_word(word));
+    }
+
+    //  Either.
+    for word in [
+        "aran", "mellon", "Elen", "síla", "lúmenn'", "lúmenn", "omentielvo", "a",
+        "{long=unique}", "{+alt_a}aran",
+    ] {
+        assert_eq!(score_word(word), 0, "{word}");
+    }
+
+    assert_eq!(detect("Ai! laurië lantar lassi súrinen"), Some(Language::Quenya));
+    assert_eq!(detect("A Elbereth Gilthoniel"), Some(Language::Sindarin));
+    assert_eq!(detect("Elen síla lúmenn' omentielvo"), None);
+    assert_eq!(detect("Elen síla lúmenn omentielvo"), None);
+}
+
+
+#[test]
+fn test_auto_segments() {
+    let text = "Elen síla namárië, Ennyn Durin aran Moria";
+    let segments = Auto::new(Unit::Word).segments(text);
+
+    let split: usize = text.find("Ennyn").unwrap();
+
+    assert_eq!(segments, [
+        Segment { range: 0..split, language: Language::Quenya, score: -3 },
+        Segment { range: split..text.len(), language: Language::Sindarin, score: 5 },
+    ]);
+
+    //  Each line is given one language.
+    let text = "A Elbereth Gilthoniel\nsilivren penna míriel\n\nnamárië\n";
+    let segments = Auto::new(Unit::Line).segments(text);
+
+    assert_eq!(segments.len(), 2);
+    assert_eq!(&text[segments[0].range.clone()], "A Elbereth Gilthoniel\nsilivren penna míriel\n\n");
+    assert_eq!(segments[0].language, Language::Sindarin);
+    assert_eq!(&text[segments[1].range.clone()], "namárië\n");
+    assert_eq!(segments[1].language, Language::Quenya);
+
+    //  With no evidence at all, the fallback is used.
+    let mut auto = Auto::new(Unit::Word);
+    assert_eq!(auto.segments("aran mellon")[0].language, Language::Quenya);
+    auto.fallback = Language::Sindarin;
+    assert_eq!(auto.segments("aran mellon")[0].language, Language::Sindarin);
+    assert!(auto.segments("").is_empty());
+}
+
+
+#[test]
+fn test_auto_transcribe() {
+    let settings = TranscriberSettings::new();
+    let conv = |unit: Unit, text: &str| Auto::new(unit).transcribe_with(text, settings);
+
+    assert_eq!(conv(Unit::Word, "namárië"), Quenya::transcribe::<String>("namárië"));
+    assert_eq!(conv(Unit::Word, "Ennyn Durin"), Gondor::transcribe::<String>("Ennyn Durin"));
+    assert_eq!(
+        conv(Unit::Line, "namárië\nEnnyn Durin\n"),
+        Quenya::transcribe::<String>("namárië\n") + &Gondor::transcribe::<String>("Ennyn Durin\n"),
+    );
+}
@@ -0,0 +1,544 @@
+#![cfg(feature = "mode-custom")]
+
+use crate::mode::{
+    custom::{base::BaseMode, file::*, CustomMode},
+    quenya::*,
+    Beleriand,
+    Gondor,
+    TengwarMode,
+};
+use super::*;
+
+
+fn tehta(tehta: Tehta) -> TehtaSpec {
+    TehtaSpec {
+        base: tehta.base,
+        alternate: tehta.alternate,
+        can_double: tehta.can_double,
+        long: false,
+    }
+}
+
+
+fn modifier(pattern: &str, after_vowel: Allowance) -> Modifier {
+    Modifier {
+        pattern: pattern.into(),
+        valid: Position {
+            after_consonant: Allowance::Require,
+            after_vowel,
+            ..Default::default()
+        },
+    }
+}
+
+
+/// Define a subset of the [`Quenya`] mode as a [`ModeFile`].
+fn quenya_file() -> ModeFile {
+    use CharOr::*;
+
+    ModeFile {
+        extends: None,
+        chunks: 3,
+        vowels_first: false,
+        checks_mod: vec![
+            Check::Palatal,
+            Check::Rince,
+            Check::Replacements,
+            Check::Diphthong,
+            Check::Vowel,
+        ],
+        checks_new: vec![Check::Consonant, Check::Diphthong, Check::Vowel],
+        allow_rince: true,
+        allow_nuquernar: false,
+        consonants: [
+            ("t", Char(TENGWA_TINCO)),
+            ("p", Char(TENGWA_PARMA)),
+            ("c", Char(TENGWA_CALMA)),
+            ("k", Char(TENGWA_CALMA)),
+            ("nd", Char(TENGWA_ANDO)),
+            ("n", Char(TENGWA_NUMEN)),
+            ("m", Char(TENGWA_MALTA)),
+            ("r", Char(TENGWA_ORE)),
+            ("l", Char(TENGWA_LAMBE)),
+            ("ld", Char(TENGWA_ALDA)),
+            ("s", Char(TENGWA_SILME)),
+            ("ss", Char(TENGWA_ESSE)),
+            ("v", Char(TENGWA_VALA)),
+            ("ng", Char(TENGWA_ANGA)),
+            ("h", Char(TENGWA_HYARMEN)),
+            ("x", Struct(GlyphSpec {
+                tengwa: Some(TENGWA_CALMA),
+                rince: true,
+                ..Default::default()
+            })),
+            ("y", Struct(GlyphSpec {
+                tengwa: Some(TENGWA_ANNA),
+                palatal: true,
+                ..Default::default()
+            })),
+        ].into_iter().map(|(k, v)| (k.into(), v)).collect(),
+        diphthongs: [
+            ("ai", CARRIER_DIPH_I, TEHTA_A),
+            ("oi", CARRIER_DIPH_I, TEHTA_O),
+            ("ui", CARRIER_DIPH_I, TEHTA_U),
+            ("au", CARRIER_DIPH_U, TEHTA_A),
+            ("eu", CARRIER_DIPH_U, TEHTA_E),
+            ("iu", CARRIER_DIPH_U, TEHTA_I),
+        ].into_iter().map(|(k, base, t)| (k.into(), Struct(GlyphSpec {
+            tengwa: Some(base),
+            tehta: Some(tehta(t)),
+            ..Default::default()
+        }))).collect(),
+        vowels: [
+            ("a", TEHTA_A),
+            ("e", TEHTA_E),
+            ("ë", TEHTA_E),
+            ("i", TEHTA_I),
+            ("o", TEHTA_O),
+            ("u", TEHTA_U),
+        ].into_iter().map(|(k, t)| (k.into(), Struct(tehta(t)))).collect(),
+        sequences: vec![],
+        rince: vec![modifier("s", Allowance::Forbid)],
+        labial: vec![],
+        nasal: vec![],
+        palatal: vec![modifier("y", Allowance::Forbid)],
+        replacements: vec![Replacement { old: TENGWA_ORE, new: TENGWA_ROMEN }],
+        rules: vec![
+            //  Initial NG is represented by Ñoldo, not Anga.
+            Rule {
+                old: TENGWA_ANGA,
+                new: TENGWA_NOLDO,
+                valid: Position {
+                    word_initial: Allowance::Require,
+                    ..Default::default()
+                },
+                before: vec![],
+                tehta: Allowance::Allow,
+            },
+            //  Medial H is represented by Aha, not Hyarmen.
+            Rule {
+                old: TENGWA_HYARMEN,
+                new: TENGWA_AHA,
+                valid: Position {
+                    word_initial: Allowance::Forbid,
+                    ..Default::default()
+                },
+                before: vec![],
+                tehta: Allowance::Allow,
+            },
+            //  Any remaining H with no vowel, before L or R, is represented by
+            //      Halla.
+            Rule {
+                old: TENGWA_HYARMEN,
+                new: TENGWA_HALLA,
+                valid: Default::default(),
+                before: vec![TENGWA_LAMBE, TENGWA_ORE, TENGWA_ROMEN],
+                tehta: Allowance::Forbid,
+            },
+        ],
+        tests: vec![],
+    }
+}
+
+
+/// Words from the tests of the [`Quenya`] mode, which a mode file extending it
+///     must transcribe in the same way.
+const QUENYA_WORDS: &[&str] = &[
+    "a", "aha", "ai", "airë", "alda", "anga", "au", "aulë", "calma", "chë",
+    "ecë", "efë", "ehwë", "ehë", "eleni sílar", "embë", "empë", "emë", "encë",
+    "endë", "engwë", "engë", "enqë", "entë", "enë", "epë", "eqë", "essë",
+    "etyañoldo", "etë", "eu", "eñwë", "eñë", "eþë", "ghë", "helcaraxë", "hlócë",
+    "hrívë", "hwesta", "hyarmen", "hárar", "iu", "khyarmen", "lá", "maria",
+    "mariá", "mixa", "mixar", "mára", "nahta", "namárië", "númen", "ohta", "oi",
+    "otso", "otsor", "quenya", "rauca", "silmë", "síla", "tas", "tsa", "ui",
+    "ungwë", "ára", "ñoldo", "ñwalmë", "ñávëa", "ñólë", "þúlë", "ʒalda",
+    "Elen síla lúmenn' omentielvo :", "lassi", "tyelpë", "laurië", "yulma",
+    "cirya", "ngoldo", "hlapu", "ómaryo",
+];
+
+/// Words from the tests of the [`Gondor`] and [`Beleriand`] modes, which a mode
+///     file extending either of them must transcribe in the same way.
+const SINDARIN_WORDS: &[&str] = &[
+    "acharn", "adan", "ae", "ai", "alaf", "alfirin", "andaith", "andûnië",
+    "aphadon", "ath", "au", "axë", "calen", "dior", "edain", "edhellen", "ei",
+    "estel", "eth", "grist", "hebin", "iorhael", "ithil", "lhûg", "lossen",
+    "mhellyn", "namma", "oe", "oth", "parf", "rhûn", "telch", "ui", "uth",
+    "venn", "wethrin", "yth", "áth", "éth", "ísa", "íth", "óth", "úth", "ýth",
+    "ando", "aran", "edhelhar", "iaur", "mellon", "annon", "ast", "aerlinn",
+    "nef", "ylf", "nædh", "nœl", "rhaw", "iant", "alph", "lhaf", "fuin", "faen",
+    "naur an edraith ammen", "Ennyn Durin Aran Moria: pedo mellon a minno",
+];
+
+
+fn custom(input: &str) -> String {
+    let mode: CustomMode = quenya_file().resolve();
+    mode.into_transcriber(input).collect()
+}
+
+
+#[test]
+fn test_custom_quenya() {
+    for input in [
+        "lassi",
+        "tyelpë",
+        "aran",
+        "laurë",
+        "yulma",
+        "otso",
+        "ando",
+        "maxa",
+        "cirya",
+        "tyeucë",
+        "aira",
+        "ar",
+        "nai",
+        "ngoldo",
+        "hlapu",
+        "hrive",
+        "aha",
+        "ahlo",
+        "anga",
+    ] {
+        assert_eq!(Quenya::transcribe::<String>(input), custom(input),
+            "Custom mode does not match Quenya for {input:?}.");
+    }
+}
+
+
+#[test]
+fn test_custom_modifiers() {
+    //  Palatal Y and sa-rincë may only follow a bare consonant.
+    assert_eq!(custom("tya"), [
+        TENGWA_TINCO, MOD_PALATAL, TEHTA_A.base,
+    ].iter().collect::<String>());
+    assert_eq!(custom("tas"), [
+        TENGWA_TINCO, TEHTA_A.base, TENGWA_SILME,
+    ].iter().collect::<String>());
+
+    //  Órë becomes Rómen only before a vowel.
+    assert_eq!(custom("re"), [
+        TENGWA_ROMEN, TEHTA_E.base,
+    ].iter().collect::<String>());
+    assert_eq!(custom("rt"), [
+        TENGWA_ORE, TENGWA_TINCO,
+    ].iter().collect::<String>());
+}
+
+
+#[test]
+fn test_custom_rules() {
+    let mut file: ModeFile = quenya_file();
+
+    //  Replace Órë with Rómen by a rule, instead of during parsing.
+    file.replacements.clear();
+    file.rules.push(Rule {
+        old: TENGWA_ORE,
+        new: TENGWA_ROMEN,
+        valid: Position {
+            before_vowel: Allowance::Require,
+            ..Default::default()
+        },
+        before: vec![],
+        tehta: Allowance::Allow,
+    });
+
+    //  Mark a word-final Silmë by replacing it with Essë.
+    file.rules.push(Rule {
+        old: TENGWA_SILME,
+        new: TENGWA_ESSE,
+        valid: Position {
+            after_vowel: Allowance::Require,
+            word_final: Allowance::Require,
+            ..Default::default()
+        },
+        before: vec![],
+        tehta: Allowance::Allow,
+    });
+
+    let mode: CustomMode = file.resolve();
+    let conv = |s: &str| -> String { mode.clone().into_transcriber(s).collect() };
+
+    assert_eq!(conv("re"), custom("re"));
+    assert_eq!(conv("rt"), custom("rt"));
+    assert_eq!(conv("rai"), custom("rai"));
+    assert_eq!(conv("ara"), custom("ara"));
+    assert_eq!(conv("ar"), custom("ar"));
+
+    assert_eq!(conv("tas"), [
+        TENGWA_TINCO, TEHTA_A.base, TENGWA_ESSE,
+    ].iter().collect::<String>());
+    assert_eq!(conv("tas ta"), [
+        TENGWA_TINCO, TEHTA_A.base, TENGWA_ESSE, ' ', TENGWA_TINCO, TEHTA_A.base,
+    ].iter().collect::<String>());
+    assert_eq!(conv("tasta"), [
+        TENGWA_TINCO, TEHTA_A.base, TENGWA_SILME, TENGWA_TINCO, TEHTA_A.base,
+    ].iter().collect::<String>());
+    assert_eq!(conv("sa"), [
+        TENGWA_SILME, TEHTA_A.base,
+    ].iter().collect::<String>());
+}
+
+
+#[test]
+fn test_custom_nasal() {
+    let mut file: ModeFile = quenya_file();
+    file.checks_new.insert(0, Check::Nasal);
+    file.nasal.push(Modifier {
+        pattern: "n".into(),
+        valid: Default::default(),
+    });
+
+    let mode: CustomMode = file.resolve();
+    let out: String = mode.into_transcriber("nt").collect();
+
+    assert_eq!(out, [TENGWA_TINCO, MOD_NASAL].iter().collect::<String>());
+}
+
+
+const QUENYA_TOML: &str = r#"
+chunks = 3
+checks_mod = ["Palatal", "Rince", "Replacements", "Diphthong", "Vowel"]
+checks_new = ["Consonant", "Diphthong", "Vowel"]
+
+replacements = [{ old = "\uE014", new = "\uE020" }]
+
+[consonants]
+t = "\uE000"
+l = "\uE022"
+r = "\uE014"
+s = "\uE024"
+h = "\uE028"
+ss = "\uE026"
+y = { tengwa = "\uE016", palatal = true }
+
+[diphthongs]
+ai = { tengwa = "\uE02A", tehta = { base = "\uE040" } }
+
+[vowels]
+a = "\uE040"
+e = { base = "\uE046", alt = "\uE048" }
+"ë" = { base = "\uE046", alt = "\uE048" }
+i = "\uE044"
+
+[[rince]]
+pattern = "s"
+valid = { after_consonant = "Require", after_vowel = "Forbid" }
+
+[[palatal]]
+pattern = "y"
+valid = { after_consonant = "Require", after_vowel = "Forbid" }
+
+[[rules]]
+old = "\uE028"
+new = "\uE00A"
+valid = { word_initial = "Forbid" }
+
+[[rules]]
+old = "\uE028"
+new = "\uE02D"
+before = ["\uE022", "\uE014", "\uE020"]
+"#;
+
+
+#[test]
+fn test_custom_toml() {
+    let mode: CustomMode = ModeFile::from_toml_str(QUENYA_TOML).unwrap().resolve();
+
+    for input in ["lassi", "tyelë", "taira", "tas", "ter", "aha", "hlas", "ahlai"] {
+        let out: String = mode.clone().into_transcriber(input).collect();
+        assert_eq!(Quenya::transcribe::<String>(input), out,
+            "Custom mode does not match Quenya for {input:?}.");
+    }
+}
+
+
+#[test]
+fn test_custom_toml_errors() {
+    fn error(text: &str) -> String {
+        ModeFile::from_toml_str(text).unwrap_err().to_string()
+    }
+
+    let wrong_type = error(&QUENYA_TOML.replace("chunks = 3", "chunks = \"3\""));
+    assert!(wrong_type.starts_with("line 2, column 10: at `chunks`:"), "{wrong_type}");
+
+    let unknown = error(&QUENYA_TOML.replace("chunks = 3", "chunks = 3\nchunk = 3"));
+    assert!(unknown.starts_with("line 3, column 1: at `chunk`:"), "{unknown}");
+
+    let bad_char = error(&QUENYA_TOML.replace("t = \"\\uE000\"", "t = \"tinco\""));
+    assert!(bad_char.starts_with("line 9, column 5: at `consonants.t`:"), "{bad_char}");
+
+    let bad_table = error(&QUENYA_TOML.replace("palatal = true", "palatal = 1"));
+    assert!(bad_table.starts_with("line 15, column 36: at `consonants.y.palatal`:"), "{bad_table}");
+    assert!(bad_table.contains("expected a boolean"), "{bad_table}");
+
+    let missing = error(&QUENYA_TOML.replace("new = \"\\uE02D\"", ""));
+    assert!(missing.starts_with("line 39, column 1: at `rules`:"), "{missing}");
+    assert!(missing.contains("missing field `new`"), "{missing}");
+
+    //  Comments and escaped quotes do not confuse the key.
+    let comment = error(&QUENYA_TOML.replace("[diphthongs]", "[extra] # x"));
+    assert!(comment.starts_with("line 17, column 2: at `extra`:"), "{comment}");
+
+    let escaped = error(&QUENYA_TOML.replace(
+        "{ tengwa = \"\\uE016\", palatal = true }",
+        "{ tengwa = \"\\\"\", palatal = 1 }",
+    ));
+    assert!(escaped.starts_with("line 15, column 32: at `consonants.y.palatal`:"), "{escaped}");
+}
+
+
+#[test]
+fn test_custom_extends() {
+    fn extend(base: BaseMode, input: &str) -> String {
+        ModeFile::new(Some(base)).resolve().into_transcriber(input).collect()
+    }
+
+    for input in QUENYA_WORDS {
+        assert_eq!(Quenya::transcribe::<String>(input), extend(BaseMode::Quenya, input),
+            "Extended mode does not match Quenya for {input:?}.");
+    }
+
+    for input in SINDARIN_WORDS {
+        assert_eq!(Gondor::transcribe::<String>(input), extend(BaseMode::Gondor, input),
+            "Extended mode does not match Gondor for {input:?}.");
+        assert_eq!(Beleriand::transcribe::<String>(input), extend(BaseMode::Beleriand, input),
+            "Extended mode does not match Beleriand for {input:?}.");
+    }
+}
+
+
+#[test]
+fn test_custom_sequences() {
+    let file = ModeFile::from_toml_str(r#"
+        extends = "quenya"
+
+        [[sequences]]
+        pattern = "x"
+        glyphs = ["\uE002", "\uE024"]
+
+        [[sequences]]
+        pattern = "s"
+        glyphs = ["\uE026"]
+        valid = { word_final = "Require" }
+    "#).unwrap();
+
+    let mode: CustomMode = file.resolve();
+    let conv = |s: &str| -> String { mode.clone().into_transcriber(s).collect() };
+    let chars = |c: &[char]| -> String { c.iter().collect() };
+
+    //  A sequence may be written with several glyphs.
+    assert_eq!(conv("xa"), chars(&[TENGWA_CALMA, TENGWA_SILME, TEHTA_A.base]));
+
+    //  A word-final sequence looks ahead for the next glyph.
+    assert_eq!(conv("tas"), chars(&[TENGWA_TINCO, TEHTA_A.base, TENGWA_ESSE]));
+    assert_eq!(conv("tas ta"), conv("tas") + " " + &conv("ta"));
+    assert_eq!(conv("tasta"), Quenya::transcribe::<String>("tasta"));
+}
+
+
+#[test]
+fn test_custom_extends_override() {
+    let file = ModeFile::from_toml_str(r#"
+        extends = "quenya"
+
+        [consonants]
+        w = "\uE031"
+        qu = "\uE037"
+    "#).unwrap();
+
+    let mode: CustomMode = file.resolve();
+    let conv = |s: &str| -> String { mode.clone().into_transcriber(s).collect() };
+
+    //  Overridden tengwar.
+    assert_eq!(conv("wa"), [TENGWA_BOMBADIL_W, TEHTA_A.base].iter().collect::<String>());
+    assert_eq!(conv("qua"), [TENGWA_CHRISTOPHER_QU, TEHTA_A.base].iter().collect::<String>());
+
+    //  Everything else falls back to the built-in mode.
+    for input in ["lassi", "tyelpë", "aran", "laurië", "ngoldo"] {
+        assert_eq!(Quenya::transcribe::<String>(input), conv(input),
+            "Extended mode does not match Quenya for {input:?}.");
+    }
+}
+
+
+#[test]
+fn test_custom_vectors() {
+    use crate::mode::custom::vector::*;
+
+    let file = ModeFile::from_toml_str(r#"
+        extends = "quenya"
+
+        [[test]]
+        input = "ta"
+        expected = "\uE000\uE040"
+
+        [[test]]
+        input = "ta"
+        expected = "\uE000\uE040"
+        settings = { alt_a = true }
+    "#).unwrap();
+    let mode: CustomMode = file.resolve();
+
+    assert_eq!(file.tests.len(), 2);
+    assert!(!file.tests[0].settings.alt_a);
+    assert!(file.tests[1].settings.alt_a);
+
+    //  The first vector matches, but the second uses the alternate A-tehta.
+    assert!(file.tests[0].run(&mode).passed());
+    assert_eq!(file.tests[1].run(&mode).mismatches, [Mismatch {
+        index: 0,
+        expected: Some(format!("{TENGWA_TINCO}{}", TEHTA_A.base)),
+        received: Some(format!("{TENGWA_TINCO}{}", TEHTA_YANTA.base)),
+    }]);
+
+    //  A missing glyph is reported at its own position.
+    let short = TestVector {
+        input: "tata".into(),
+        expected: format!("{TENGWA_TINCO}{}", TEHTA_A.base),
+        settings: Default::default(),
+    };
+    assert_eq!(short.run(&mode).mismatches, [Mismatch {
+        index: 1,
+        expected: None,
+        received: Some(format!("{TENGWA_TINCO}{}", TEHTA_A.base)),
+    }]);
+
+    assert_eq!(glyphs("\u{E000}\u{E040}\u{E022}\u{E059} \u{E001}"), [
+        "\u{E000}\u{E040}", "\u{E022}\u{E059}", " ", "\u{E001}",
+    ]);
+}
+
+
+#[test]
+fn test_custom_export() {
+    fn export(base: BaseMode) -> CustomMode {
+        let text: String = base.export().to_toml_string().unwrap();
+        let file: ModeFile = ModeFile::from_toml_str(&text).unwrap();
+
+        //  The Tengwar are written as escape sequences.
+        assert!(text.contains("\\uE000"));
+        assert!(text.chars().all(|c| !('\u{E000}'..='\u{E07F}').contains(&c)));
+
+        assert_eq!(file.extends, None);
+        file.resolve()
+    }
+
+    let quenya = export(BaseMode::Quenya);
+    let gondor = export(BaseMode::Gondor);
+    let beleriand = export(BaseMode::Beleriand);
+
+    for input in QUENYA_WORDS.iter().chain(&["anna", "tinco"]) {
+        assert_eq!(Quenya::transcribe::<String>(input),
+            quenya.clone().into_transcriber(input).collect::<String>(),
+            "Exported mode does not match Quenya for {input:?}.");
+    }
+
+    for input in SINDARIN_WORDS.iter().chain(&["dûr"]) {
+        assert_eq!(Gondor::transcribe::<String>(input),
+            gondor.clone().into_transcriber(input).collect::<String>(),
+            "Exported mode does not match Gondor for {input:?}.");
+        assert_eq!(Beleriand::transcribe::<String>(input),
+            beleriand.clone().into_transcriber(input).collect::<String>(),
+            "Exported mode does not match Beleriand for {input:?}.");
+    }
+}
@@ -0,0 +1,129 @@
+use crate::mode::general::*;
+use super::*;
+
+
+#[test]
+fn consonants() {
+    test_tengwar!(General, "church" => [
+        TENGWA_CALMA, TEHTA_U.base, // chu
+        TENGWA_ORE, // r
+        TENGWA_CALMA, // ch
+    ]);
+    test_tengwar!(General, "shining" => [
+        TENGWA_AHA, TEHTA_I.base, // shi
+        TENGWA_NUMEN, TEHTA_I.base, // ni
+        TENGWA_NWALME, // ng
+    ]);
+    test_tengwar!(General, "thick" => [
+        TENGWA_THULE, TEHTA_I.base, // thi
+        TENGWA_QESSE, // ck
+    ]);
+    test_tengwar!(General, "queen" => [
+        TENGWA_QESSE, MOD_LABIAL, TEHTA_E.base, TEHTA_E.base, // quee
+        TENGWA_NUMEN, // n
+    ]);
+    test_tengwar!(General, "will" => [
+        TENGWA_VALA, TEHTA_I.base, // wi
+        TENGWA_LAMBE, MOD_LONG_CONS, // ll
+    ]);
+}
+
+
+#[test]
+fn nasals() {
+    test_tengwar!(General, "bent" => [
+        TENGWA_UMBAR, TEHTA_E.base, // be
+        TENGWA_TINCO, MOD_NASAL, // nt
+    ]);
+    test_tengwar!(General, "lamp" => [
+        TENGWA_LAMBE, TEHTA_A.base, // la
+        TENGWA_PARMA, MOD_NASAL, // mp
+    ]);
+    //  A nasal cannot be written above a tengwa of another series.
+    test_tengwar!(General, "anp" => [
+        CARRIER_SHORT, TEHTA_A.base, // a
+        TENGWA_NUMEN, // n
+        TENGWA_PARMA, // p
+    ]);
+}
+
+
+#[test]
+fn silent_e() {
+    test_tengwar!(General, "stone" => [
+        TENGWA_SILME, // s
+        TENGWA_TINCO, TEHTA_O.base, // to
+        TENGWA_NUMEN, DC_UNDER_DOT_1, // ne
+    ]);
+    test_tengwar!(General, "there" => [
+        TENGWA_THULE, TEHTA_E.base, // the
+        TENGWA_ORE, DC_UNDER_DOT_1, // re
+    ]);
+
+    //  A single E in a word is not silent.
+    test_tengwar!(General, "me" => [
+        TENGWA_MALTA, TEHTA_E.base, // me
+    ]);
+
+    //  Only a final E is silent.
+    test_tengwar!(General, "stoned" => [
+        TENGWA_SILME, // s
+        TENGWA_TINCO, TEHTA_O.base, // to
+        TENGWA_NUMEN, TEHTA_E.base, // ne
+        TENGWA_ANDO, // d
+    ]);
+}
+
+
+#[test]
+fn vowels() {
+    test_tengwar!(General, "idea" => [
+        CARRIER_SHORT, TEHTA_I.base, // i
+        TENGWA_ANDO, TEHTA_E.base, // de
+        CARRIER_SHORT, TEHTA_A.base, // a
+    ]);
+    test_tengwar!(General, "moon" => [
+        TENGWA_MALTA, TEHTA_O.base, TEHTA_O.base, // moo
+        TENGWA_NUMEN, // n
+    ]);
+    test_tengwar!(General, "roar" => [
+        TENGWA_ROMEN, TEHTA_O.base, // ro
+        CARRIER_SHORT, TEHTA_A.base, // a
+        TENGWA_ORE, // r
+    ]);
+    test_tengwar!(General, "yes" => [
+        CONSONANT_Y, TEHTA_E.base, // ye
+        TENGWA_SILME, // s
+    ]);
+    test_tengwar!(General, "day" => [
+        TENGWA_ANDO, TEHTA_A.base, // da
+        CONSONANT_Y, // y
+    ]);
+    test_tengwar!(General, "my" => [
+        TENGWA_MALTA, TEHTA_Y.base, // my
+    ]);
+}
+
+
+#[test]
+fn words() {
+    test_tengwar!(General, "the" => [WORD_THE, DC_UNDER_DOT_1]);
+    test_tengwar!(General, "of" => [WORD_OF]);
+    test_tengwar!(General, "and" => [WORD_AND, MOD_NASAL]);
+
+    test_tengwar!(General, "the lord of the rings" => [
+        WORD_THE, DC_UNDER_DOT_1, ' ', // the
+        TENGWA_LAMBE, TEHTA_O.base, // lo
+        TENGWA_ORE, // r
+        TENGWA_ANDO, ' ', // d
+        WORD_OF, ' ', // of
+        WORD_THE, DC_UNDER_DOT_1, ' ', // the
+        TENGWA_ROMEN, TEHTA_I.base, // ri
+        TENGWA_NWALME, SA_RINCE, // ngs
+    ]);
+
+    //  Words are not abbreviated inside other words.
+    test_tengwar!(General, "then" != "the");
+    test_tengwar!(General, "often" != "of");
+    test_tengwar!(General, "sand" != "and");
+}
@@ -0,0 +1,63 @@
+use crate::mode::{beleriand::{VOWEL_A, VOWEL_E, VOWEL_I, VOWEL_O}, gondor_full::*};
+use super::*;
+
+
+#[test]
+fn consonants() {
+    //  Consonants follow the Mode of Gondor, rather than that of Beleriand.
+    test_tengwar!(GondorFull, "mellon" => [
+        TENGWA_MALTA, // m
+        VOWEL_E, // e
+        TENGWA_LAMBE, MOD_LONG_CONS, // ll
+        VOWEL_O, // o
+        TENGWA_NUMEN, // n
+    ]);
+    test_tengwar!(GondorFull, "calen" => [
+        TENGWA_QESSE, // c
+        VOWEL_A, // a
+        TENGWA_LAMBE, // l
+        VOWEL_E, // e
+        TENGWA_NUMEN, // n
+    ]);
+    test_tengwar!(GondorFull, "ando" => [
+        VOWEL_A, // a
+        TENGWA_ANDO, MOD_NASAL, // nd
+        VOWEL_O, // o
+    ]);
+}
+
+
+#[test]
+fn ore() {
+    test_tengwar!(GondorFull, "aran" => [
+        VOWEL_A, // a
+        TENGWA_ROMEN, // r
+        VOWEL_A, // a
+        TENGWA_NUMEN, // n
+    ]);
+    test_tengwar!(GondorFull, "edhelhar" => [
+        VOWEL_E, // e
+        TENGWA_ANTO, // dh
+        VOWEL_E, // e
+        TENGWA_LAMBE, // l
+        TENGWA_HYARMEN, // h
+        VOWEL_A, // a
+        TENGWA_ORE, // r
+    ]);
+}
+
+
+#[test]
+fn consonant_i() {
+    test_tengwar!(GondorFull, "iaur" == "jaur" => [
+        CONSONANT_I, // i
+        VOWEL_A, MOD_LABIAL, // au
+        TENGWA_ORE, // r
+    ]);
+    test_tengwar!(GondorFull, "ithil" => [
+        VOWEL_I, // i
+        TENGWA_THULE, // th
+        VOWEL_I, // i
+        TENGWA_LAMBE, // l
+    ]);
+}
@@ -53,7 +53,10 @@ macro_rules! test_tengwar {
     ($mode:ty $([$($t:tt)*])?, $lhs:tt => $rhs:tt) => {{
         let conversion = tengwar!($mode $([$($t)*])?, $lhs);
         let (input, output) = &conversion;
-        let expected: String = $rhs.into_iter().collect();
+        //  The expected `char`s are given in the CSUR block, but the output is
+        //      written in the default encoding.
+        let expected: String = $crate::characters::encoding::Encoding::DEFAULT
+            .recode(&$rhs.into_iter().collect::<String>());
 
         println!(
             // "[{file}:{line:0>3}] {mode}: {input:?} -> {output}{params}",
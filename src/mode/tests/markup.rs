@@ -0,0 +1,118 @@
+use crate::mode::{markup::*, DynMode, ModeRegistry};
+use crate::{Beleriand, Gondor, Quenya, TengwarMode, TranscriberSettings};
+
+
+#[test]
+fn test_markup_parse() {
+    let markup = Markup::new(Box::new(Gondor::default()));
+    let text = "a {q:b {b:c} d} e";
+
+    assert_eq!(markup.parse(text), [
+        Element::Text(0..2),
+        Element::Open(2..5, "q"),
+        Element::Text(5..7),
+        Element::Open(7..10, "b"),
+        Element::Text(10..11),
+        Element::Close(11..12),
+        Element::Text(12..14),
+        Element::Close(14..15),
+        Element::Text(15..17),
+    ]);
+    assert_eq!(markup.check(text), Ok(()));
+
+    //  Escaped, incomplete, and unmatched markup is left as text.
+    for text in ["\\{q:a}", "{q a}", "{:a}", "a}", "{ q:a}"] {
+        assert_eq!(markup.parse(text), [Element::Text(0..text.len())], "{text:?}");
+    }
+
+    //  Unknown modes are left as text, along with their ends.
+    let text = "{s:a {x:b} c}";
+    assert_eq!(markup.parse(text), [
+        Element::Open(0..3, "s"),
+        Element::Text(3..12),
+        Element::Close(12..13),
+    ]);
+    assert_eq!(markup.check(text), Err(vec![
+        MarkupError::UnknownMode { name: "x".into(), offset: 5 },
+    ]));
+
+    //  Inline directives are left as text, even if they look like markup.
+    let markup_eq = Markup::new(Box::new(Gondor::default()))
+        .with_syntax("{=}".parse().unwrap());
+    assert_eq!(markup_eq.parse("{long=unique}a"), [Element::Text(0..14)]);
+    assert_eq!(markup_eq.check("{long=unique}a"), Ok(()));
+    assert_eq!(markup.parse("{q:{+alt_a}b}"), [
+        Element::Open(0..3, "q"),
+        Element::Text(3..12),
+        Element::Close(12..13),
+    ]);
+
+    //  Unclosed spans are reported.
+    assert_eq!(markup.check("{q:a {b:c"), Err(vec![
+        MarkupError::Unclosed { name: "q".into(), offset: 0 },
+        MarkupError::Unclosed { name: "b".into(), offset: 5 },
+    ]));
+}
+
+
+#[test]
+fn test_markup_syntax() {
+    assert_eq!("{:}".parse(), Ok(Syntax::DEFAULT));
+    assert_eq!("[|]".parse(), Ok(Syntax { open: '[', separator: '|', close: ']' }));
+    assert!("{}".parse::<Syntax>().is_err());
+    assert!("{::}".parse::<Syntax>().is_err());
+    assert!("{{}".parse::<Syntax>().is_err());
+    assert!("{a}".parse::<Syntax>().is_err());
+    assert!("\\:}".parse::<Syntax>().is_err());
+
+    let markup = Markup::new(Box::new(Gondor::default()))
+        .with_syntax("[|]".parse().unwrap());
+
+    assert_eq!(markup.parse("{q:a} [q|b]"), [
+        Element::Text(0..6),
+        Element::Open(6..9, "q"),
+        Element::Text(9..10),
+        Element::Close(10..11),
+    ]);
+}
+
+
+#[test]
+fn test_markup_transcribe() {
+    let settings = TranscriberSettings::new();
+    let markup = Markup::new(Box::new(Gondor::default()));
+    let conv = |text: &str| markup.transcribe_with(text, settings);
+
+    assert_eq!(conv("mellon"), Gondor::transcribe::<String>("mellon"));
+    assert_eq!(conv("{q:namárië}"), Quenya::transcribe::<String>("namárië"));
+    assert_eq!(conv("{quenya:namárië}"), Quenya::transcribe::<String>("namárië"));
+    assert_eq!(conv("{b:mellon}"), Beleriand::transcribe::<String>("mellon"));
+    assert_eq!(
+        conv("mellon {q:namárië {b:mellon}}!"),
+        Gondor::transcribe::<String>("mellon ")
+            + &Quenya::transcribe::<String>("namárië ")
+            + &Beleriand::transcribe::<String>("mellon")
+            + &Gondor::transcribe::<String>("!"),
+    );
+
+    //  Escaped delimiters are passed through.
+    assert_eq!(conv("\\{q:a}"), Gondor::transcribe::<String>("\\{q:a}"));
+    assert!(conv("\\{q:a}").starts_with("{q"));
+
+    //  Settings apply across spans.
+    let mut alt = settings;
+    alt.alt_a = true;
+    assert_eq!(
+        markup.transcribe_with("calma {q:calma}", alt),
+        Gondor::default().transcribe_with("calma ", alt)
+            + &Quenya::default().transcribe_with("calma", alt),
+    );
+
+    //  Spans can only select modes in the registry.
+    let markup = Markup::new(Box::new(Gondor::default()))
+        .with_registry(ModeRegistry::empty());
+    assert_eq!(
+        markup.transcribe_with("{q:a}", settings),
+        Gondor::transcribe::<String>("{q:a}"),
+    );
+}
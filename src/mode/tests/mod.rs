@@ -3,10 +3,15 @@
 #[macro_use]
 mod macros;
 
+mod auto;
 mod beleriand;
-// mod general;
+mod custom;
+mod general;
 mod gondor;
+mod gondor_full;
+mod markup;
 mod quenya;
+mod registry;
 
 use crate::characters::*;
 use VowelStyle::*;
@@ -992,3 +992,28 @@ fn edge_case() {
         => [TENGWA_SILME_NUQ, TEHTA_A.base]
     );
 }
+
+
+#[test]
+fn policy() {
+    use crate::{characters::encoding::Encoding, policy::{NoPolicy, Policy, Standard}};
+
+    //  The output is compared in the CSUR block.
+    let standard: String = Standard::transcriber::<Quenya>("otso").collect();
+    let nopolicy: String = NoPolicy::transcriber::<Quenya>("otso").collect();
+    let (standard, nopolicy) = (Encoding::Csur.recode(&standard), Encoding::Csur.recode(&nopolicy));
+
+    //  The standard policy allows a sa-rincë on T.
+    assert_eq!(standard, String::from_iter([
+        CARRIER_SHORT, TEHTA_O.base, // o
+        TENGWA_TINCO, SA_RINCE, TEHTA_O.base, // tso
+    ]));
+
+    //  With no policy, a sa-rincë is never valid, so S must be written with its
+    //      own tengwa.
+    assert_eq!(nopolicy, String::from_iter([
+        CARRIER_SHORT, TEHTA_O.base, // o
+        TENGWA_TINCO, // t
+        TENGWA_SILME, TEHTA_O.base, // so
+    ]));
+}
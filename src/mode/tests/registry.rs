@@ -0,0 +1,152 @@
+use crate::{
+    cirth::Moria,
+    mode::{registry::ModeEntry, DynMode, Language, ModeRegistry, TengwarMode},
+    Beleriand,
+    Gondor,
+    Quenya,
+    TranscriberSettings,
+};
+
+
+#[test]
+fn test_registry_builtin() {
+    let registry = ModeRegistry::new();
+    let settings = TranscriberSettings::new();
+    let conv = |name: &str, input: &str| -> String {
+        registry.get(name).unwrap().transcribe_with(input, settings)
+    };
+
+    assert_eq!(conv("quenya", "namárië"), Quenya::transcribe::<String>("namárië"));
+    assert_eq!(conv("Classical", "namárië"), Quenya::transcribe::<String>("namárië"));
+    assert_eq!(conv("c", "namárië"), Quenya::transcribe::<String>("namárië"));
+    assert_eq!(conv("g", "mellon"), Gondor::transcribe::<String>("mellon"));
+    assert_eq!(conv("b", "mellon"), Beleriand::transcribe::<String>("mellon"));
+    assert_eq!(conv("moria", "balin"), Moria::transcribe::<String>("balin"));
+
+    assert!(registry.get("klingon").is_none());
+    assert_eq!(registry.names().collect::<Vec<_>>(), [
+        "classical",
+        "gondor",
+        "gondor-full",
+        "beleriand",
+        "english",
+        "angerthas-daeron",
+        "angerthas-moria",
+        "auto",
+        "auto-line",
+    ]);
+
+    //  Settings are passed through to the transcriber.
+    let mut alt = settings;
+    alt.alt_a = true;
+    assert_eq!(
+        registry.get("c").unwrap().transcribe_with("lasta", alt),
+        Quenya::default_transcriber("lasta").with_settings(alt).into_string(),
+    );
+}
+
+
+#[test]
+fn test_registry_threads() {
+    let registry = ModeRegistry::new();
+    let settings = TranscriberSettings::new();
+
+    //  Boxed modes, and the registry itself, can be shared between threads.
+    let modes: Vec<Box<dyn DynMode>> = ["quenya", "gondor", "auto"].iter()
+        .map(|name| registry.get(name).unwrap())
+        .collect();
+
+    std::thread::scope(|scope| {
+        for mode in &modes {
+            let registry = &registry;
+
+            scope.spawn(move || {
+                let mellon = registry.get("gondor").unwrap().transcribe_with("mellon", settings);
+                assert_eq!(mellon, Gondor::transcribe::<String>("mellon"));
+                mode.transcribe_with("mellon", settings)
+            });
+        }
+    });
+}
+
+
+#[test]
+fn test_registry_language() {
+    let registry = ModeRegistry::new();
+
+    //  Every mode of every language is registered, and the primary mode is
+    //      listed first.
+    for lang in Language::ALL {
+        let modes: Vec<&str> = registry.for_language(lang)
+            .map(|entry| entry.name.as_str())
+            .collect();
+
+        assert_eq!(modes.len(), lang.modes().len(), "{lang}");
+        assert!(lang.modes().iter().all(|mode| modes.contains(mode)), "{lang}");
+        assert_eq!(lang.modes()[0], lang.primary_mode(), "{lang}");
+        assert!(registry.contains(lang.primary_mode()), "{lang}");
+    }
+
+    assert_eq!(Language::Quenya.primary_mode(), "classical");
+    assert_eq!(Language::Sindarin.primary_mode(), "gondor");
+    assert_eq!(Language::English.primary_mode(), "english");
+
+    assert!(Language::Sindarin.supports("Beleriand"));
+    assert!(!Language::Quenya.supports("gondor"));
+    assert!(!Language::English.supports("e"));
+
+    assert_eq!(Language::of_mode("angerthas-moria").collect::<Vec<_>>(), [
+        Language::Sindarin,
+        Language::English,
+    ]);
+    assert_eq!(Language::of_mode("unknown").count(), 0);
+}
+
+#[test]
+fn test_registry_runtime() {
+    let mut registry = ModeRegistry::empty();
+    assert!(registry.get("c").is_none());
+
+    registry.register_mode::<Quenya>("quenya", &["q"], "Quenya.");
+    registry.register(ModeEntry::new("sindarin", &["s", "q"], "Sindarin.", || {
+        Box::new(Gondor::default())
+    }));
+
+    //  Names take precedence over aliases, and later aliases over earlier.
+    let modes: Vec<Box<dyn DynMode>> = ["quenya", "q", "s", "sindarin"].iter()
+        .map(|name| registry.get(name).unwrap())
+        .collect();
+    let out: Vec<String> = modes.iter()
+        .map(|mode| mode.transcribe_with("mellon", Default::default()))
+        .collect();
+
+    assert_eq!(out[0], Quenya::transcribe::<String>("mellon"));
+    assert_eq!(out[1], Gondor::transcribe::<String>("mellon"));
+    assert_eq!(out[2], Gondor::transcribe::<String>("mellon"));
+    assert_eq!(out[3], Gondor::transcribe::<String>("mellon"));
+
+    //  A mode with the same name replaces the old one.
+    registry.register_mode::<Beleriand>("sindarin", &["s"], "Sindarin.");
+    assert_eq!(registry.list().count(), 2);
+    assert_eq!(
+        registry.get("s").unwrap().transcribe_with("mellon", Default::default()),
+        Beleriand::transcribe::<String>("mellon"),
+    );
+
+    assert!(registry.unregister("quenya").is_some());
+    assert!(!registry.contains("q"));
+}
+
+
+#[test]
+#[cfg(feature = "mode-custom")]
+fn test_registry_file() {
+    use crate::mode::custom::{base::BaseMode, file::ModeFile};
+
+    let mut registry = ModeRegistry::new();
+    registry.register_file("custom", &["x"], "Custom.", ModeFile::new(Some(BaseMode::Quenya)));
+
+    let mode = registry.get("x").unwrap();
+    assert_eq!(mode.transcribe_with("ómaryo", Default::default()),
+        Quenya::transcribe::<String>("ómaryo"));
+}
@@ -1,6 +1,15 @@
-use unicode_normalization::UnicodeNormalization;
-use crate::{Token, Transcriber};
-use super::{ParseAction, TengwarMode};
+use std::collections::VecDeque;
+use crate::{
+    characters::ESC,
+    policy::{Policy, Standard},
+    span::{self, Span, SpanIter, Spanned},
+    trace::{record_step, Action, SharedTrace},
+    Directive,
+    Token,
+    Transcriber,
+    TranscriberSettings,
+};
+use super::{Neighbors, ParseAction, TengwarMode};
 
 
 const fn to_lower(c: char) -> char {
@@ -19,13 +28,28 @@ const fn to_lower(c: char) -> char {
 /// The result of a single "step" of a [`Tokenizer`]. Multiple steps can be
 ///     performed for each iteration.
 #[derive(Clone, Debug)]
-enum Step {
+enum Step<P: Policy> {
     /// The iteration is not complete. Another step should be run immediately.
     Incomplete,
     /// The [`Tokenizer`] is exhausted. The iterator should return [`None`].
     Exhausted,
     /// The iteration is complete. The [`Token`] should now be returned.
-    Complete(Token),
+    Complete(Token<P>),
+}
+
+
+/// An inline [`Directive`] that has been removed from the data of a
+///     [`Tokenizer`], to be returned between the tokens around it.
+#[derive(Clone, Debug)]
+struct Held {
+    /// The position within the data vec where the directive was found.
+    pos: usize,
+    /// The directive itself.
+    directive: Directive,
+    /// The position of the directive in the original text.
+    span: Span,
+    /// The lowercase text of the directive, including its braces.
+    text: Vec<char>,
 }
 
 
@@ -33,15 +57,24 @@ enum Step {
 ///     to the rules defined by the methods of a [`TengwarMode`].
 ///
 /// This is a lower-level construct, and performs only minimal post-processing
-///     of the `Token`s, as defined by [`TengwarMode::finalize`]. For a higher
+///     of the `Token`s, as defined by [`TengwarMode::finalize_in`]. For a higher
 ///     level iterator with more powerful rules, consider a [`Transcriber`],
 ///     which can be created using [`Tokenizer::into_transcriber`].
+///
+/// The [`Policy`] of the Tokenizer is passed to the mode, and is used for any
+///     decisions it makes about the [`Glyph`]s it constructs. If the Tokenizer
+///     is given a [value](Self::with_policy) of its policy, that value is also
+///     set on every `Glyph` it yields.
+///
+/// [`Glyph`]: crate::Glyph
 #[derive(Debug)]
-pub struct Tokenizer<M: TengwarMode> {
+pub struct Tokenizer<M: TengwarMode, P: Policy = Standard> {
     /// The original data, with case intact.
     chars: Vec<char>,
     /// Data vec, converted to lowercase for processing.
     lower: Vec<char>,
+    /// The position in the original text of each [`char`] in the data vec.
+    origin: Vec<Span>,
 
     /// Current position within the data vec.
     head: usize,
@@ -50,16 +83,46 @@ pub struct Tokenizer<M: TengwarMode> {
     /// Number of [`char`]s that will be passed through unchanged, starting at
     ///     `chars[head]`.
     skip: usize,
+    /// Position within the data vec where the current token began.
+    start: usize,
+    /// Inline directives that have been removed from the data vec, in order.
+    held: VecDeque<Held>,
+    /// The position and length of each malformed directive in the data vec,
+    ///     which will be passed through unchanged.
+    literals: VecDeque<(usize, usize)>,
 
     /// The operating Mode, which determines the actual tokenization rules.
     pub mode: M,
-    next: Option<Token>,
+    /// Tokens that have been read in advance, but not yet finalized.
+    ahead: VecDeque<Token<P>>,
+    /// The [`Span`] of each token that has been read in advance.
+    ahead_spans: VecDeque<Span>,
+    /// Tokens that have already been finalized, kept for context.
+    behind: VecDeque<Token<P>>,
+    /// A record of the steps taken, if one is being kept.
+    trace: Option<SharedTrace>,
+    /// The value of the [`Policy`] set on every [`Glyph`], if there is one.
+    ///
+    /// [`Glyph`]: crate::Glyph
+    policy: Option<P>,
 }
 
 /// Public functionality.
-impl<M: TengwarMode> Tokenizer<M> {
+impl<M: TengwarMode, P: Policy> Tokenizer<M, P> {
     /// Set up a new Tokenizer over a sequence of [`char`]s.
     pub fn new(chars: Vec<char>, mode: M) -> Self {
+        let mut byte: usize = 0;
+        let origin: Vec<Span> = chars.iter().enumerate().map(|(i, c)| {
+            byte += c.len_utf8();
+            Span { bytes: byte - c.len_utf8()..byte, chars: i..i + 1 }
+        }).collect();
+
+        Self::with_origin(chars, origin, mode)
+    }
+
+    /// Set up a new Tokenizer over a sequence of [`char`]s, each of which came
+    ///     from a known position in the original text.
+    fn with_origin(chars: Vec<char>, origin: Vec<Span>, mode: M) -> Self {
         let size: usize = chars.len().min(M::MAX_CHUNK);
         let mut lower = chars.clone();
 
@@ -70,15 +133,24 @@ impl<M: TengwarMode> Tokenizer<M> {
         Self {
             chars,
             lower,
+            origin,
             head: 0,
             size,
             skip: 0,
+            start: 0,
+            held: VecDeque::new(),
+            literals: VecDeque::new(),
             mode,
-            next: None,
+            ahead: VecDeque::with_capacity(M::LOOKAHEAD),
+            ahead_spans: VecDeque::with_capacity(M::LOOKAHEAD),
+            behind: VecDeque::with_capacity(M::LOOKBEHIND),
+            trace: None,
+            policy: None,
         }
     }
 
     /// Set up a new Tokenizer over UTF-8 text.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: impl AsRef<str>) -> Self
         where M: Default,
     {
@@ -87,12 +159,140 @@ impl<M: TengwarMode> Tokenizer<M> {
 
     /// Set up a new Tokenizer over UTF-8 text with a specific mode instance.
     pub fn with_mode(s: impl AsRef<str>, mode: M) -> Self {
-        Self::new(s.as_ref().nfc().collect(), mode)
+        let (chars, origin) = span::normalize(s.as_ref());
+        Self::with_origin(chars, origin, mode)
+    }
+
+    /// Follow the rules of a value of the [`Policy`], rather than only those of
+    ///     its type. The value is passed to [`TengwarMode::process_in`], and
+    ///     set on every [`Glyph`](crate::Glyph) that is yielded.
+    pub fn with_policy(mut self, policy: P) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Read inline [`Directive`]s from the text. Each directive is removed
+    ///     before the text is passed to the mode, so it does not divide the
+    ///     word around it, and is returned after any token in progress at its
+    ///     position. Text between braces that is not a valid directive is
+    ///     passed through unchanged.
+    ///
+    /// This should be called before any [`Token`] is read.
+    pub fn with_directives(mut self) -> Self {
+        let len: usize = self.lower.len();
+        let mut chars: Vec<char> = Vec::with_capacity(len);
+        let mut lower: Vec<char> = Vec::with_capacity(len);
+        let mut origin: Vec<Span> = Vec::with_capacity(len);
+        let mut idx: usize = 0;
+
+        while idx < len {
+            //  An escaped character is kept, even if it opens a directive.
+            let keep: usize = if self.lower[idx] == ESC { 2 } else {
+                match Directive::find_braced(&self.lower[idx..]) {
+                    Some((Ok(directive), n)) => {
+                        self.held.push_back(Held {
+                            pos: lower.len(),
+                            directive,
+                            span: self.origin[idx].to(&self.origin[idx + n - 1]),
+                            text: self.lower[idx..idx + n].to_vec(),
+                        });
+                        idx += n;
+                        continue;
+                    }
+                    Some((Err(_), n)) => {
+                        self.literals.push_back((lower.len(), n));
+                        n
+                    }
+                    None => 1,
+                }
+            };
+
+            let end: usize = len.min(idx + keep);
+            chars.extend_from_slice(&self.chars[idx..end]);
+            lower.extend_from_slice(&self.lower[idx..end]);
+            origin.extend_from_slice(&self.origin[idx..end]);
+            idx = end;
+        }
+
+        self.size = chars.len().min(M::MAX_CHUNK);
+        self.chars = chars;
+        self.lower = lower;
+        self.origin = origin;
+        self
     }
 
     /// Wrap this [`Tokenizer`] in a [`Transcriber`] that can apply higher-level
     ///     rules.
-    pub fn into_transcriber(self) -> Transcriber<M> { self.into() }
+    pub fn into_transcriber(self) -> Transcriber<M, P> { self.into() }
+
+    /// Yield each [`Token`] along with its [`Span`] in the original text.
+    pub fn spanned(self) -> Spanned<M, P> { Spanned::new(self) }
+
+    /// Apply the rules of a [`Transcriber`] to the [`Token`]s, and yield each
+    ///     of them along with its positions in the original text and in the
+    ///     rendered output.
+    pub fn mapped(self, settings: TranscriberSettings) -> SpanIter<Spanned<M, P>, P> {
+        SpanIter::new(self.spanned(), settings)
+    }
+
+    /// Record the steps taken by this Tokenizer in a [`Trace`].
+    ///
+    /// [`Trace`]: crate::trace::Trace
+    pub fn with_trace(mut self, trace: SharedTrace) -> Self {
+        self.trace = Some(trace);
+        self
+    }
+
+    /// Return the next [`Token`], along with its [`Span`] in the original text.
+    pub fn next_spanned(&mut self) -> Option<(Token<P>, Span)> {
+        let (mut token, span) = match (self.ahead.pop_front(), self.ahead_spans.pop_front()) {
+            (Some(token), Some(span)) => (token, span),
+            _ => self.step_to_next_spanned()?,
+        };
+
+        //  A directive is not finalized, and is not kept for context.
+        if let Token::Directive(_) = token {
+            return Some((token, span));
+        }
+
+        let is_directive = |next: &Token<P>| matches!(next, Token::Directive(_));
+        let mut n_ahead: usize = self.ahead.iter().filter(|next| !is_directive(next)).count();
+
+        while n_ahead < M::LOOKAHEAD {
+            match self.step_to_next_spanned() {
+                Some((next, next_span)) => {
+                    n_ahead += !is_directive(&next) as usize;
+                    self.ahead.push_back(next);
+                    self.ahead_spans.push_back(next_span);
+                }
+                None => break,
+            }
+        }
+
+        let before: Token<P> = token;
+        let neighbors = Neighbors {
+            before: self.behind.make_contiguous(),
+            after: self.ahead.make_contiguous(),
+        };
+        self.mode.finalize_in(&mut token, &neighbors);
+
+        if let Some(trace) = &self.trace {
+            //  The Tokens after this one have already been recorded.
+            let mut trace = trace.borrow_mut();
+            let index: usize = trace.tokens() - 1 - self.ahead.len();
+            trace.finalize(index, &before, &token);
+        }
+
+        if 0 < M::LOOKBEHIND {
+            if self.behind.len() == M::LOOKBEHIND {
+                self.behind.pop_front();
+            }
+
+            self.behind.push_back(token);
+        }
+
+        Some((token, span))
+    }
 
     /// Return the slice of original [`char`]s, corresponding to the ones that
     ///     will be processed in the next step.
@@ -110,11 +310,17 @@ impl<M: TengwarMode> Tokenizer<M> {
 }
 
 /// Internal functionality.
-impl<M: TengwarMode> Tokenizer<M> {
+impl<M: TengwarMode, P: Policy> Tokenizer<M, P> {
     /// Move the read head forward and reset the window width.
     fn advance_head(&mut self, n: usize) {
         self.head += n;
         self.size = self.chars.len().min(M::MAX_CHUNK);
+
+        //  A malformed directive that the mode has read past is not passed
+        //      through on its own.
+        while self.literals.front().is_some_and(|&(pos, _)| pos < self.head) {
+            self.literals.pop_front();
+        }
     }
 
     /// Decrease the width of the slice provided to the [`TengwarMode`]. This is
@@ -133,14 +339,16 @@ impl<M: TengwarMode> Tokenizer<M> {
     fn skip_count_dec(&mut self) { self.skip -= 1; }
 
     /// Perform a single step of parsing. This will result in at most one call
-    ///     to [`TengwarMode::process`], and does not guarantee that a [`Token`]
-    ///     will be complete by the end. Each `Token` may require several steps.
-    fn step(&mut self) -> Step {
+    ///     to [`TengwarMode::process_in`], and does not guarantee that a
+    ///     [`Token`] will be complete by the end. Each `Token` may require
+    ///     several steps.
+    fn step(&mut self) -> Step<P> {
         let data: &[char] = &self.lower;
         let mode: &mut M = &mut self.mode;
         let head: usize = self.head;
         let size: usize = self.size;
         let skip: usize = self.skip;
+        let policy: Option<P> = self.policy;
         let len: usize = data.len();
 
         if len <= head {
@@ -148,7 +356,10 @@ impl<M: TengwarMode> Tokenizer<M> {
             //      being constructed, finish and return it. Otherwise, there is
             //      nothing more to be done, and the tokenizer is now exhausted.
             match mode.finish_current() {
-                Some(token) => Step::Complete(token),
+                Some(token) => {
+                    record_step(&self.trace, &[], Action::Finish);
+                    Step::Complete(token)
+                }
                 None => Step::Exhausted,
             }
         } else { // head < len
@@ -159,22 +370,43 @@ impl<M: TengwarMode> Tokenizer<M> {
                 //      in progress, finish and return it; Otherwise, decrement
                 //      the counter and return one `char` directly.
                 if let Some(token) = mode.finish_current() {
+                    record_step(&self.trace, &[], Action::Finish);
                     self.advance_head(0);
                     Step::Complete(token)
                 } else {
+                    record_step(&self.trace, &data[head..=head], Action::Pass);
                     self.advance_head(1);
                     self.skip_count_dec();
                     Step::Complete(Token::Char(self.chars[head]))
                 }
             }
 
+            //  A malformed directive is passed through unchanged, in the same
+            //      way as a skip. If a token is in progress, finish and return
+            //      it; Otherwise, try again.
+            else if let Some(&(_, n)) = self.literals.front().filter(|(pos, _)| *pos == head) {
+                let finished: Option<Token<P>> = mode.finish_current();
+                self.literals.pop_front();
+                self.skip_count_add(n);
+
+                match finished {
+                    Some(token) => {
+                        record_step(&self.trace, &[], Action::Finish);
+                        Step::Complete(token)
+                    }
+                    None => Step::Incomplete,
+                }
+            }
+
             //  If the width of the check window has not yet narrowed to zero,
             //      try to parse it according to the Mode implementation.
             else if 0 < size { // skip == 0
                 let end: usize = len.min(head + size);
                 let chunk: &[char] = &data[head..end];
+                let action: ParseAction<P> = mode.process_in(chunk, policy);
+                record_step(&self.trace, chunk, Action::of(&action));
 
-                match mode.process(chunk) {
+                match action {
                     ParseAction::MatchedNone => {
                         //  No match. Narrow the window and try again.
                         self.narrow_window();
@@ -195,7 +427,7 @@ impl<M: TengwarMode> Tokenizer<M> {
                         //  Skip specified. Increase the skip counter. Then, if
                         //      a token was in progress, return it; Otherwise,
                         //      try again.
-                        let finished: Option<Token> = mode.finish_current();
+                        let finished: Option<Token<P>> = mode.finish_current();
                         self.skip_count_add(n);
 
                         match finished {
@@ -216,18 +448,21 @@ impl<M: TengwarMode> Tokenizer<M> {
             //  The chunk width has narrowed to zero. Nothing more can be added.
             //      If a token is currently in progress, finish and return it.
             else if let Some(token) = mode.finish_current() {
+                record_step(&self.trace, &[], Action::Finish);
                 self.advance_head(0);
                 Step::Complete(token)
             }
 
             //  Look for any secondary value in the slice ahead.
             else if let Some((token, len)) = mode.find_secondary(&data[head..]) {
+                record_step(&self.trace, &data[head..head + len], Action::Secondary(len));
                 self.advance_head(len);
                 Step::Complete(token)
             }
 
             //  Give up and pass the current `char` through unchanged.
             else {
+                record_step(&self.trace, &data[head..=head], Action::Pass);
                 self.advance_head(1);
                 Step::Complete(Token::Char(self.chars[head]))
             }
@@ -236,28 +471,69 @@ impl<M: TengwarMode> Tokenizer<M> {
 
     /// Repeatedly step the iterator until either a new [`Token`] is ready or
     ///     the iterator is exhausted.
-    fn step_to_next(&mut self) -> Option<Token> {
+    fn step_to_next(&mut self) -> Option<Token<P>> {
         loop {
             match self.step() {
                 Step::Incomplete => continue,
                 Step::Exhausted => break None,
-                Step::Complete(token) => break Some(token),
+                Step::Complete(token) => break Some(match self.policy {
+                    Some(policy) => token.with_policy(Some(policy)),
+                    None => token,
+                }),
             }
         }
     }
+
+    /// Step the iterator to the next [`Token`], and find the [`Span`] of the
+    ///     original text that was read to construct it.
+    fn step_to_next_spanned(&mut self) -> Option<(Token<P>, Span)> {
+        //  A directive is returned once every token that began before it has
+        //      been finished.
+        let (token, span) = match self.held.front() {
+            Some(held) if held.pos <= self.start => self.take_held()?,
+            _ => match self.step_to_next() {
+                Some(token) => (token, self.take_span()),
+                None => self.take_held()?,
+            },
+        };
+
+        if let Some(trace) = &self.trace {
+            trace.borrow_mut().token(&token);
+        }
+
+        Some((token, span))
+    }
+
+    /// Return the next [`Directive`] that was removed from the data vec, along
+    ///     with its [`Span`] in the original text.
+    fn take_held(&mut self) -> Option<(Token<P>, Span)> {
+        let held: Held = self.held.pop_front()?;
+        record_step(&self.trace, &held.text, Action::Directive(held.text.len()));
+
+        Some((Token::Directive(held.directive), held.span))
+    }
+
+    /// Find the [`Span`] of the original text that was read to construct the
+    ///     token that was just finished, and start the next token.
+    fn take_span(&mut self) -> Span {
+        let (start, end) = (self.start, self.head.min(self.origin.len()));
+        self.start = end;
+
+        match (self.origin.get(start), end.checked_sub(1)) {
+            (Some(first), Some(last)) if start < end => first.to(&self.origin[last]),
+            (Some(next), _) => Span::empty(next.bytes.start, next.chars.start),
+            (None, _) => match self.origin.last() {
+                Some(last) => Span::empty(last.bytes.end, last.chars.end),
+                None => Span::default(),
+            },
+        }
+    }
 }
 
-impl<M: TengwarMode> Iterator for Tokenizer<M> {
-    type Item = Token;
+impl<M: TengwarMode, P: Policy> Iterator for Tokenizer<M, P> {
+    type Item = Token<P>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut token: Token = match self.next.take() {
-            Some(stored) => stored,
-            None => self.step_to_next()?,
-        };
-
-        self.next = self.step_to_next();
-        self.mode.finalize(&mut token, self.next.as_ref());
-        Some(token)
+        self.next_spanned().map(|(token, _)| token)
     }
 }
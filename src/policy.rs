@@ -1,4 +1,17 @@
-use crate::{characters::*, TengwarMode, ToTengwar, Transcriber};
+use crate::{
+    characters::{*, encoding::Encoding},
+    mode::Tokenizer,
+    TengwarMode,
+    Token,
+    TokenIter,
+    Transcriber,
+    TranscriberSettings,
+};
+
+#[cfg(feature = "font-coverage")]
+pub mod coverage;
+#[cfg(feature = "policy-custom")]
+pub mod data;
 
 
 /// This trait defines higher-level behavior for rendering Tengwar.
@@ -62,11 +75,62 @@ pub trait Policy: Copy {
     /// Check whether a base tengwa is suitable to receive the alternate rincë.
     fn rince_valid_final(base: char) -> bool { false }
 
-    /// Create a [`Transcriber`] using the given [`TengwarMode`].
-    fn transcriber<M>(input: impl ToTengwar) -> Transcriber<M, Self>
+    /// The same as [`Self::ligates_with_ara`], under this value of the policy.
+    ///
+    /// The methods ending in `_in` are consulted by a [`Glyph`] that carries a
+    ///     value of its policy, as given to a [`Tokenizer`]. This allows a
+    ///     policy to hold rules of its own, rather than only in its type. By
+    ///     default, each calls the associated function of the same name.
+    fn ligates_with_ara_in(&self, base: char) -> bool {
+        Self::ligates_with_ara(base)
+    }
+
+    /// The same as [`Self::telco_ligates_with`], under this value of the policy.
+    fn telco_ligates_with_in(&self, base: char) -> bool {
+        Self::telco_ligates_with(base)
+    }
+
+    /// The same as [`Self::ligature_valid`], under this value of the policy.
+    fn ligature_valid_in<P: Policy>(
+        &self,
+        prev: &Glyph<Self>,
+        next: &Glyph<P>,
+        level: u8,
+    ) -> bool {
+        Self::ligature_valid(prev, next, level)
+    }
+
+    /// The same as [`Self::nuquerna`], under this value of the policy.
+    fn nuquerna_in(&self, base: char) -> char {
+        Self::nuquerna(base)
+    }
+
+    /// The same as [`Self::nuquerna_valid`], under this value of the policy.
+    fn nuquerna_valid_in(&self, base: char) -> bool {
+        Self::nuquerna_valid(base)
+    }
+
+    /// The same as [`Self::rince`], under this value of the policy.
+    fn rince_in(&self, base: char, is_final: bool) -> Rince {
+        Self::rince(base, is_final)
+    }
+
+    /// The same as [`Self::rince_valid`], under this value of the policy.
+    fn rince_valid_in(&self, base: char) -> bool {
+        Self::rince_valid(base)
+    }
+
+    /// The same as [`Self::rince_valid_final`], under this value of the policy.
+    fn rince_valid_final_in(&self, base: char) -> bool {
+        Self::rince_valid_final(base)
+    }
+
+    /// Create a [`Transcriber`] using the given [`TengwarMode`]. The mode will
+    ///     consult this policy while constructing [`Glyph`]s.
+    fn transcriber<M>(input: impl AsRef<str>) -> Transcriber<M, Self>
         where M: TengwarMode + Default,
     {
-        input.transcriber().set_policy()
+        Tokenizer::<M, Self>::from_str(input).into_transcriber()
     }
 }
 
@@ -200,12 +264,12 @@ impl Policy for Standard {
     }
 
     fn rince_valid(base: char) -> bool {
-        match base {
+        !matches!(
+            base,
             TENGWA_ROMEN | TENGWA_ARDA
             | TENGWA_SILME | TENGWA_SILME_NUQ
-            | TENGWA_ESSE | TENGWA_ESSE_NUQ => false,
-            _ => true,
-        }
+            | TENGWA_ESSE | TENGWA_ESSE_NUQ
+        )
     }
 
     fn rince_valid_final(base: char) -> bool {
@@ -223,6 +287,198 @@ impl Policy for Standard {
 }
 
 
+
+/// A [`Policy`] based on the "Tengwar Formal CSUR" typeface. It has dedicated
+///     glyphs for the ligating short carrier and the final sa-rincë, but does
+///     not form any ligatures with zero-width joiners.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FormalCsur;
+
+impl Policy for FormalCsur {
+    fn telco_ligates_with(base: char) -> bool { Standard::telco_ligates_with(base) }
+    fn nuquerna(base: char) -> char { Standard::nuquerna(base) }
+    fn nuquerna_valid(base: char) -> bool { Standard::nuquerna_valid(base) }
+    fn rince_valid(base: char) -> bool { Standard::rince_valid(base) }
+
+    fn rince_valid_final(base: char) -> bool {
+        Standard::rince_valid_final(base)
+            || TEMA_CALMA.contains(base)
+            || TEMA_QESSE.contains(base)
+    }
+}
+
+
+/// A [`Policy`] based on the "Tengwar Alcarin" typeface. Its ligatures are a
+///     superset of those of [`Standard`], and every regular tengwa with a bow
+///     may take the final sa-rincë.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Alcarin;
+
+impl Policy for Alcarin {
+    fn ligates_with_ara(base: char) -> bool {
+        Standard::ligates_with_ara(base) || base == TENGWA_ESSE
+    }
+
+    fn telco_ligates_with(base: char) -> bool { Standard::telco_ligates_with(base) }
+
+    fn ligature_valid<P: Policy>(
+        prev: &Glyph<Self>,
+        next: &Glyph<P>,
+        level: u8,
+    ) -> bool {
+        Standard::ligature_valid(&prev.change_policy(), next, level)
+    }
+
+    fn nuquerna(base: char) -> char { Standard::nuquerna(base) }
+    fn nuquerna_valid(base: char) -> bool { Standard::nuquerna_valid(base) }
+    fn rince_valid(base: char) -> bool { Standard::rince_valid(base) }
+
+    fn rince_valid_final(base: char) -> bool {
+        FormalCsur::rince_valid_final(base)
+    }
+}
+
+
+/// A [`Policy`] based on the Tengwar of the "FreeMono" typeface, of the GNU
+///     FreeFont project. Every glyph has the same width, so there are no
+///     ligatures of any kind, and no final sa-rincë.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FreeMono;
+
+impl Policy for FreeMono {
+    fn nuquerna(base: char) -> char { Standard::nuquerna(base) }
+    fn nuquerna_valid(base: char) -> bool { Standard::nuquerna_valid(base) }
+    fn rince_valid(base: char) -> bool { Standard::rince_valid(base) }
+}
+
+
+/// A [`Policy`] based on the "Tengwar Annatar" typeface, and the other fonts
+///     that use the [Dan Smith](crate::characters::dan_smith) layout. There is
+///     no way to form ligatures in the layout, but the final sa-rincë and the
+///     nuquernë variants have keys of their own.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Annatar;
+
+impl Policy for Annatar {
+    fn nuquerna(base: char) -> char { Standard::nuquerna(base) }
+    fn nuquerna_valid(base: char) -> bool { Standard::nuquerna_valid(base) }
+    fn rince_valid(base: char) -> bool { Standard::rince_valid(base) }
+    fn rince_valid_final(base: char) -> bool { Standard::rince_valid_final(base) }
+}
+
+
+/// A typeface for which a [`Policy`] is defined, which can be selected at
+///     runtime.
+///
+/// Each font also has default [`TranscriberSettings`] suited to its glyphs, and
+///     an [`Encoding`] that it expects its text to be written in.
+///
+/// Every policy accepts a sa-rincë on the same tengwar as [`Standard`], so that
+///     [`Token`]s which were produced under the standard policy can be rendered
+///     for any font.
+///
+/// # Example
+/// ```
+/// use tengwar::{mode::DynMode, policy::Font, Quenya, TranscriberSettings};
+///
+/// let font = Font::FreeMono;
+/// let settings = TranscriberSettings { ligate_zwj: 3, ..font.settings() };
+///
+/// let text: String = font.render(Quenya::default().boxed_tokenizer("istar"), settings);
+/// assert!(!text.contains(tengwar::characters::ZWJ));
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, ValueEnum)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Font {
+    /// Tengwar Telcontar, which uses the standard policy.
+    #[default]
+    Telcontar,
+    /// Tengwar Formal CSUR.
+    #[value(alias = "formal")]
+    FormalCsur,
+    /// Tengwar Alcarin.
+    Alcarin,
+    /// FreeMono, of GNU FreeFont.
+    #[value(alias = "freefont", alias = "freemono")]
+    FreeMono,
+    /// Tengwar Annatar, or any other font in the Dan Smith layout.
+    #[value(alias = "dan-smith")]
+    Annatar,
+}
+
+impl Font {
+    /// Return the default settings for text rendered in this font.
+    pub const fn settings(self) -> TranscriberSettings {
+        let base = TranscriberSettings { nuquerna: true, ..TranscriberSettings::new() };
+
+        match self {
+            Self::Telcontar => TranscriberSettings {
+                ligate_short: true,
+                ligate_zwj: 1,
+                ..base
+            },
+            Self::FormalCsur => TranscriberSettings {
+                ligate_short: true,
+                vowels: VowelStyle::Unique,
+                ..base
+            },
+            Self::Alcarin => TranscriberSettings {
+                alt_rince: true,
+                ligate_short: true,
+                ligate_zwj: 3,
+                ..base
+            },
+            Self::FreeMono => TranscriberSettings {
+                vowels: VowelStyle::Separate,
+                ..base
+            },
+            Self::Annatar => TranscriberSettings {
+                vowels: VowelStyle::Separate,
+                ..base
+            },
+        }
+    }
+
+    /// Return the encoding expected by this font.
+    pub const fn encoding(self) -> Encoding {
+        match self {
+            Self::Annatar => Encoding::DanSmith,
+            _ => Encoding::DEFAULT,
+        }
+    }
+
+    /// Apply the rules of a [`TokenIter`] to a sequence of [`Token`]s, under
+    ///     the policy of this font, and render them. The text is written in
+    ///     the [default encoding](Encoding::DEFAULT), and can then be recoded
+    ///     into the [encoding of the font](Self::encoding).
+    pub fn render(
+        self,
+        tokens: impl Iterator<Item=Token>,
+        settings: TranscriberSettings,
+    ) -> String {
+        self.render_iter(TokenIter::new(tokens).with_settings(settings))
+    }
+
+    /// Render the [`Token`]s of a [`TokenIter`] under the policy of this font,
+    ///     keeping its settings and any [`Transform`]s added to it.
+    ///
+    /// [`Transform`]: crate::transform::Transform
+    pub fn render_iter<I: Iterator<Item=Token>>(self, iter: TokenIter<I, Standard>) -> String {
+        fn render<I: Iterator<Item=Token>, Q: Policy>(iter: TokenIter<I, Standard>) -> String {
+            iter.set_policy::<Q>().collect()
+        }
+
+        match self {
+            Self::Telcontar => render::<I, Standard>(iter),
+            Self::FormalCsur => render::<I, FormalCsur>(iter),
+            Self::Alcarin => render::<I, Alcarin>(iter),
+            Self::FreeMono => render::<I, FreeMono>(iter),
+            Self::Annatar => render::<I, Annatar>(iter),
+        }
+    }
+}
+
 /*pub trait IterPolicyChange<P: Policy> {
     type NewIter<Q: Policy>: Iterator<Item=Token<Q>>;
 
@@ -0,0 +1,270 @@
+//! This module defines the [`FontCoverage`] of a font file, which records the
+//!     codepoints and ligatures that the font supports, and the settings that
+//!     can be used to render text for it.
+//!
+//! A font is read from a TrueType or OpenType file. A codepoint is supported if
+//!     the character map of the font has a glyph for it, in either of the
+//!     blocks of the [`Encoding`]. A ligature is supported if the `GSUB` table
+//!     of the font has a substitution for two tengwar joined by a [zero-width
+//!     joiner](ZWJ). Ligatures defined in any other way, such as by Graphite
+//!     rules, cannot be found, and fonts in the [Dan Smith] layout have no
+//!     codepoints in either block.
+//!
+//! [Dan Smith]: crate::characters::dan_smith
+//!
+//! # Example
+//! ```
+//! use tengwar::{characters::*, policy::coverage::FontCoverage, TranscriberSettings, VowelStyle};
+//!
+//! //  A font with only the basic tengwar and tehtar.
+//! let coverage = FontCoverage {
+//!     codepoints: FontCoverage::all_codepoints()
+//!         .filter(|&c| c != DC_OVER_ACUTE_2 && c != CARRIER_SHORT_LIG)
+//!         .collect(),
+//!     ..Default::default()
+//! };
+//!
+//! let settings = coverage.restrict(TranscriberSettings {
+//!     ligate_short: true,
+//!     ligate_zwj: 3,
+//!     vowels: VowelStyle::Unique,
+//!     ..Default::default()
+//! });
+//!
+//! assert!(!settings.ligate_short);
+//! assert_eq!(settings.ligate_zwj, 0);
+//! assert_eq!(settings.vowels, VowelStyle::Separate);
+//! ```
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::{Display, Formatter},
+    path::Path,
+};
+use ttf_parser::{gsub::SubstitutionSubtable, name_id, Face, FaceParsingError, GlyphId};
+use super::*;
+
+
+/// The codepoints of the dedicated "long" forms of the tehtar, which are used
+///     by [`VowelStyle::Unique`].
+const LONG_TEHTAR: [char; 3] = [DC_OVER_ACUTE_2, DC_OVER_HOOK_R_2, DC_OVER_HOOK_L_2];
+
+/// The codepoints of the nuquernë variants used by [`Standard`].
+const NUQUERNAR: [char; 2] = [TENGWA_SILME_NUQ, TENGWA_ESSE_NUQ];
+
+
+/// An error encountered while reading a font file.
+#[derive(Debug)]
+pub enum FontFileError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file was read, but is not a valid font.
+    Parse(FaceParsingError),
+}
+
+impl Display for FontFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => err.fmt(f),
+            Self::Parse(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for FontFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse(err) => Some(err),
+        }
+    }
+}
+
+impl From<std::io::Error> for FontFileError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<FaceParsingError> for FontFileError {
+    fn from(err: FaceParsingError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+
+/// The parts of the Tengwar that a font supports.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FontCoverage {
+    /// The family name of the font, if it has one.
+    pub name: Option<String>,
+    /// The codepoints written by this crate which the font has glyphs for, in
+    ///     the CSUR block.
+    pub codepoints: BTreeSet<char>,
+    /// The pairs of tengwar, in the CSUR block, which the font joins into a
+    ///     ligature when they are separated by a [zero-width joiner](ZWJ).
+    pub ligatures: BTreeSet<(char, char)>,
+}
+
+impl FontCoverage {
+    /// Return every codepoint that may be written by this crate, in the CSUR
+    ///     block.
+    pub fn all_codepoints() -> impl Iterator<Item=char> {
+        Encoding::Csur.block().into_iter().flatten().filter(|&c| width(c).is_some())
+    }
+
+    /// Read the coverage of a font from the contents of a font file. If the
+    ///     file is a collection, the first font is read.
+    pub fn from_data(data: &[u8]) -> Result<Self, FontFileError> {
+        let face = Face::parse(data, 0)?;
+
+        //  Find the glyph of each codepoint, in either block.
+        let mut glyphs: HashMap<GlyphId, char> = HashMap::new();
+        let mut codepoints: BTreeSet<char> = BTreeSet::new();
+
+        for c in Self::all_codepoints() {
+            let found = [c, Encoding::Smp.encode(c)].into_iter().filter_map(|c| face.glyph_index(c));
+
+            for glyph in found {
+                glyphs.insert(glyph, c);
+                codepoints.insert(c);
+            }
+        }
+
+        let name: Option<String> = face.names().into_iter()
+            .filter(|name| name.name_id == name_id::FAMILY && name.is_unicode())
+            .find_map(|name| name.to_string());
+
+        let mut ligatures: BTreeSet<(char, char)> = BTreeSet::new();
+
+        if let (Some(zwj), Some(gsub)) = (face.glyph_index(ZWJ), face.tables().gsub) {
+            for lookup in gsub.lookups {
+                for subtable in lookup.subtables.into_iter::<SubstitutionSubtable>() {
+                    let SubstitutionSubtable::Ligature(subst) = subtable else { continue; };
+
+                    for &first in glyphs.keys() {
+                        let Some(set) = subst.coverage.get(first)
+                            .and_then(|index| subst.ligature_sets.get(index))
+                            else { continue; };
+
+                        for ligature in set {
+                            let sequence: Vec<GlyphId> = std::iter::once(first)
+                                .chain(ligature.components)
+                                .collect();
+
+                            //  Find every tengwa before a joiner, and the one
+                            //      after it.
+                            for window in sequence.windows(3) {
+                                let &[lhs, joiner, rhs] = window else { continue; };
+
+                                if let (true, Some(&lhs), Some(&rhs))
+                                    = (joiner == zwj, glyphs.get(&lhs), glyphs.get(&rhs))
+                                {
+                                    ligatures.insert((lhs, rhs));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Self { name, codepoints, ligatures })
+    }
+
+    /// Read the coverage of a font from a file.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, FontFileError> {
+        Self::from_data(&std::fs::read(path)?)
+    }
+
+    /// Return `true` if the font has a glyph for a codepoint in the CSUR block.
+    pub fn supports(&self, c: char) -> bool {
+        self.codepoints.contains(&c)
+    }
+
+    /// Return every codepoint written by this crate that the font does not
+    ///     have a glyph for.
+    pub fn missing(&self) -> impl Iterator<Item=char> + '_ {
+        Self::all_codepoints().filter(|c| !self.supports(*c))
+    }
+
+    /// Return the pairs of tengwar that a [`Policy`] joins with a zero-width
+    ///     joiner at a level of ligation, but not at any lower level.
+    fn expected<P: Policy>(level: u8) -> BTreeSet<(char, char)> {
+        let tengwar = || Self::all_codepoints()
+            .filter(|&c| c <= TENGWA_WAIA && width(c) == Some(1));
+
+        let joins = |lhs: char, rhs: char, level: u8| -> bool {
+            match level {
+                0 => false,
+                _ if rhs == CARRIER_LONG => P::ligates_with_ara(lhs),
+                _ => P::ligature_valid(&Glyph::<P>::new_base(lhs), &Glyph::<P>::new_base(rhs), level),
+            }
+        };
+
+        tengwar()
+            .flat_map(|lhs| tengwar().map(move |rhs| (lhs, rhs)))
+            .filter(|&(lhs, rhs)| joins(lhs, rhs, level) && !joins(lhs, rhs, level - 1))
+            .collect()
+    }
+
+    /// Count the ligatures that a [`Policy`] forms at a level of ligation, but
+    ///     not at any lower level. Returns the number that are defined by the
+    ///     font, and the number that the policy may form.
+    pub fn ligation<P: Policy>(&self, level: u8) -> (usize, usize) {
+        let expected = Self::expected::<P>(level);
+        (expected.intersection(&self.ligatures).count(), expected.len())
+    }
+
+    /// Return the highest [level](TranscriberSettings::ligate_zwj) of ligation
+    ///     at which the font defines ligatures of a [`Policy`]. Each level below
+    ///     it must also be supported, if the policy forms any ligatures at it.
+    pub fn ligate_zwj<P: Policy>(&self) -> u8 {
+        let mut supported: u8 = 0;
+
+        for level in 1..=3 {
+            match self.ligation::<P>(level) {
+                (0, 0) => continue,
+                (0, _) => break,
+                _ => supported = level,
+            }
+        }
+
+        supported
+    }
+
+    /// Disable any setting that needs a codepoint or ligature that the font
+    ///     does not support, under the [`Standard`] policy. Dedicated long
+    ///     tehtar are replaced with separate carriers, rather than doubled
+    ///     tehtar, because the doubled forms are usually only drawn well by
+    ///     fonts that also have the dedicated ones.
+    pub fn restrict(&self, settings: TranscriberSettings) -> TranscriberSettings {
+        let has_all = |chars: &[char]| chars.iter().all(|&c| self.supports(c));
+
+        TranscriberSettings {
+            alt_a: settings.alt_a && self.supports(TEHTA_YANTA.base),
+            alt_rince: settings.alt_rince && self.supports(SA_RINCE_FINAL),
+            dot_plain: settings.dot_plain && self.supports(DC_UNDER_DOT_1),
+            ligate_short: settings.ligate_short && self.supports(CARRIER_SHORT_LIG),
+            ligate_zwj: settings.ligate_zwj.min(self.ligate_zwj::<Standard>()),
+            nuquerna: settings.nuquerna && has_all(&NUQUERNAR),
+            vowels: match settings.vowels {
+                VowelStyle::Unique if !has_all(&LONG_TEHTAR) => VowelStyle::Separate,
+                vowels => vowels,
+            },
+            ..settings
+        }
+    }
+
+    /// Return settings that use every form supported by the font, without
+    ///     changing the style of the text otherwise.
+    pub fn settings(&self) -> TranscriberSettings {
+        self.restrict(TranscriberSettings {
+            ligate_short: true,
+            ligate_zwj: u8::MAX,
+            nuquerna: true,
+            vowels: VowelStyle::Unique,
+            ..TranscriberSettings::new()
+        })
+    }
+}
@@ -0,0 +1,274 @@
+//! This module defines the [`DataPolicy`], whose rules are loaded at runtime
+//!     from a [`PolicyFile`], rather than written in Rust.
+//!
+//! The rules of a `DataPolicy` are held by its value, rather than its type. A
+//!     [`Glyph`] follows them when it carries that value, which is set by a
+//!     [`Tokenizer`] that is [given](Tokenizer::with_policy) the policy. By
+//!     its type alone, a `DataPolicy` has no rules at all, in the same way as
+//!     [`NoPolicy`].
+//!
+//! Because a [`Policy`] must be [`Copy`], a `DataPolicy` refers to a file that
+//!     lives for the rest of the program. A file that is loaded at runtime can
+//!     be given that lifetime with [`Box::leak`].
+//!
+//! A file may extend one of the built-in [`Font`] profiles, in which case any
+//!     rule that it does not define is taken from the policy of that font. A
+//!     rule that the file does define replaces that of the font entirely. Sets
+//!     of tengwar are written as strings, with each character of the string
+//!     being a member of the set.
+//!
+//! # Example
+//! ```
+//! use tengwar::{characters::*, mode::Tokenizer, policy::data::*, Quenya, TranscriberSettings};
+//!
+//! //  Allow the final sa-rincë only on Tinco, Ando and Calma.
+//! let file = PolicyFile::from_toml_str(r#"
+//!     extends = "telcontar"
+//!     rince_final = "\uE000\uE004\uE002"
+//! "#).unwrap();
+//! let policy = DataPolicy::new(Box::leak(Box::new(file)));
+//!
+//! let settings = TranscriberSettings { alt_rince: true, ..Default::default() };
+//! let text: String = Tokenizer::<Quenya, DataPolicy>::from_str("lucs")
+//!     .with_policy(policy)
+//!     .into_transcriber()
+//!     .with_settings(settings)
+//!     .collect();
+//!
+//! assert!(text.contains(SA_RINCE_FINAL));
+//! ```
+
+use std::{collections::BTreeMap, path::Path};
+use crate::mode::custom::file::ModeFileError;
+use super::*;
+
+
+/// An error encountered while loading a [`PolicyFile`]. These are reported in
+///     the same way as errors in a mode file.
+pub type PolicyFileError = ModeFileError;
+
+
+/// A rule allowing glyphs to be joined by a [zero-width joiner](ZWJ).
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Ligature {
+    /// The tengwar that may be on the left side of the ligature.
+    pub before: String,
+    /// The tengwar that may be on the right side of the ligature.
+    pub after: String,
+    /// The lowest [level](crate::TranscriberSettings::ligate_zwj) of ligation
+    ///     at which the ligature is formed.
+    #[serde(default = "default_level")]
+    pub level: u8,
+    /// If this is `true`, the ligature is formed even if both glyphs carry a
+    ///     tehta. Otherwise, the tehtar are assumed to be too crowded.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub tehtar: bool,
+}
+
+impl Ligature {
+    /// Determine whether this rule joins two glyphs.
+    fn joins<P: Policy, Q: Policy>(&self, prev: &Glyph<P>, next: &Glyph<Q>, level: u8) -> bool {
+        let lhs = *prev.parts().rhs();
+        let rhs = *next.parts().lhs();
+
+        let (Some(before), Some(after)) = (lhs.tengwa, rhs.tengwa) else {
+            return false;
+        };
+
+        self.level <= level
+            && self.before.contains(*before)
+            && self.after.contains(*after)
+            && (self.tehtar || lhs.tehta.is_none() || rhs.tehta.is_none())
+    }
+}
+
+
+/// A runtime definition of the rules of a [`Policy`], which may be specified
+///     in a file.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyFile {
+    /// A built-in font whose policy is used for any rule not defined by the
+    ///     file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<Font>,
+
+    /// Tengwar that may be joined to a following [long carrier](CARRIER_LONG).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ara: Option<String>,
+    /// Tengwar that a [short carrier](CARRIER_SHORT) may be joined to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub telco: Option<String>,
+    /// Rules for joining glyphs with [zero-width joiners](ZWJ).
+    #[serde(default, alias = "ligature", skip_serializing_if = "Option::is_none")]
+    pub ligatures: Option<Vec<Ligature>>,
+
+    /// Tengwar which have nuquernë variants, mapped to those variants.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "serialize_nuquerna")]
+    pub nuquerna: Option<BTreeMap<char, char>>,
+
+    /// Tengwar that may take a [sa-rincë](SA_RINCE).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rince: Option<String>,
+    /// Tengwar that may take the [final sa-rincë](SA_RINCE_FINAL).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rince_final: Option<String>,
+}
+
+fn default_level() -> u8 { 1 }
+const fn is_false(b: &bool) -> bool { !*b }
+
+/// Write the nuquernë variants with strings as keys, since TOML does not allow
+///     a `char` as a key.
+fn serialize_nuquerna<S: serde::Serializer>(
+    nuquerna: &Option<BTreeMap<char, char>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let map: Option<BTreeMap<String, char>> = nuquerna.as_ref().map(|map| {
+        map.iter().map(|(base, nuq)| (base.to_string(), *nuq)).collect()
+    });
+
+    serde::Serialize::serialize(&map, serializer)
+}
+
+impl PolicyFile {
+    /// Define an empty policy, optionally extending a built-in font.
+    pub fn new(extends: Option<Font>) -> Self {
+        Self { extends, ..Default::default() }
+    }
+
+    /// Read a policy definition from a string of TOML.
+    pub fn from_toml_str(text: &str) -> Result<Self, PolicyFileError> {
+        toml::from_str(text).map_err(|err| ModeFileError::from_toml(text, err))
+    }
+
+    /// Read a policy definition from a TOML file.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, PolicyFileError> {
+        Self::from_toml_str(&std::fs::read_to_string(path)?)
+    }
+
+    /// Write this policy definition as a string of TOML.
+    pub fn to_toml_string(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+}
+
+
+/// Call a method of the [`Policy`] of a built-in font, or of [`NoPolicy`] if
+///     there is no font.
+macro_rules! extended {
+    ($font:expr, $method:ident($($arg:expr),*)) => {
+        match $font {
+            None => NoPolicy::$method($($arg),*),
+            Some(Font::Telcontar) => Standard::$method($($arg),*),
+            Some(Font::FormalCsur) => FormalCsur::$method($($arg),*),
+            Some(Font::Alcarin) => Alcarin::$method($($arg),*),
+            Some(Font::FreeMono) => FreeMono::$method($($arg),*),
+            Some(Font::Annatar) => Annatar::$method($($arg),*),
+        }
+    };
+}
+
+
+/// A [`Policy`] which follows the rules of a [`PolicyFile`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DataPolicy {
+    file: Option<&'static PolicyFile>,
+}
+
+impl DataPolicy {
+    /// Follow the rules of a file.
+    pub const fn new(file: &'static PolicyFile) -> Self {
+        Self { file: Some(file) }
+    }
+
+    /// Return the file whose rules are followed, if there is one.
+    pub const fn file(&self) -> Option<&'static PolicyFile> {
+        self.file
+    }
+
+    /// Look up a rule in the file, falling back to the font it extends.
+    fn lookup<T>(
+        &self,
+        rule: impl FnOnce(&PolicyFile) -> Option<T>,
+        fallback: impl FnOnce(Option<Font>) -> T,
+    ) -> T {
+        match self.file {
+            Some(file) => match rule(file) {
+                Some(value) => value,
+                None => fallback(file.extends),
+            },
+            None => fallback(None),
+        }
+    }
+}
+
+impl Policy for DataPolicy {
+    fn ligates_with_ara_in(&self, base: char) -> bool {
+        self.lookup(
+            |file| file.ara.as_ref().map(|set| set.contains(base)),
+            |font| extended!(font, ligates_with_ara(base)),
+        )
+    }
+
+    fn telco_ligates_with_in(&self, base: char) -> bool {
+        self.lookup(
+            |file| file.telco.as_ref().map(|set| set.contains(base)),
+            |font| extended!(font, telco_ligates_with(base)),
+        )
+    }
+
+    fn ligature_valid_in<P: Policy>(
+        &self,
+        prev: &Glyph<Self>,
+        next: &Glyph<P>,
+        level: u8,
+    ) -> bool {
+        if level == 0 { return false; }
+
+        self.lookup(
+            |file| file.ligatures.as_ref().map(|rules| {
+                rules.iter().any(|rule| rule.joins(prev, next, level))
+            }),
+            |font| extended!(font, ligature_valid(&prev.change_policy(), next, level)),
+        )
+    }
+
+    fn nuquerna_in(&self, base: char) -> char {
+        self.lookup(
+            |file| file.nuquerna.as_ref().map(|map| *map.get(&base).unwrap_or(&base)),
+            |font| extended!(font, nuquerna(base)),
+        )
+    }
+
+    fn nuquerna_valid_in(&self, base: char) -> bool {
+        self.lookup(
+            |file| file.nuquerna.as_ref().map(|map| map.contains_key(&base)),
+            |font| extended!(font, nuquerna_valid(base)),
+        )
+    }
+
+    fn rince_in(&self, base: char, is_final: bool) -> Rince {
+        if is_final && self.rince_valid_final_in(base) {
+            Rince::Final
+        } else {
+            Rince::Basic
+        }
+    }
+
+    fn rince_valid_in(&self, base: char) -> bool {
+        self.lookup(
+            |file| file.rince.as_ref().map(|set| set.contains(base)),
+            |font| extended!(font, rince_valid(base)),
+        )
+    }
+
+    fn rince_valid_final_in(&self, base: char) -> bool {
+        self.lookup(
+            |file| file.rince_final.as_ref().map(|set| set.contains(base)),
+            |font| extended!(font, rince_valid_final(base)),
+        )
+    }
+}
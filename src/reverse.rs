@@ -0,0 +1,182 @@
+//! This module defines the reverse of transcription: Reading text written in
+//!     the Tengwar, and writing it back out in a romanized form.
+//!
+//! The first step is performed by the [`TengwarParser`], which reads codepoints
+//!     and reconstructs the [`Token`]s that would have been rendered to produce
+//!     them. The second step is defined by the [`Romanize`] trait, which is
+//!     implemented for each of the default modes, and converts these `Token`s
+//!     into Latin text.
+//!
+//! Romanization is not a perfect inverse of transcription. Some information is
+//!     lost in the Tengwar, such as the difference between `ks` and `x`, and
+//!     some settings remove information entirely, such as the elision of the
+//!     [A-tehta](TEHTA_A). However, the romanized text should always transcribe
+//!     back into the same Tengwar, using the same mode and settings, with the
+//!     exception of sequence indices, which are read back as plain tengwar.
+//!
+//! # Example
+//! ```
+//! use tengwar::{Quenya, reverse::Romanize, ToTengwar};
+//!
+//! let tengwar: String = "namárië !".to_tengwar::<Quenya>();
+//! assert_eq!(Quenya::romanize(&tengwar), "namárië !");
+//! ```
+
+mod beleriand;
+mod gondor;
+mod parser;
+mod quenya;
+mod tests;
+
+pub use parser::{find_tehta, is_tengwa, TengwarParser};
+
+use crate::{characters::*, Token};
+
+
+/// Return the Latin punctuation mark most likely to have produced a given mark
+///     from the tengwar block. This is the inverse of [`punctuation`].
+pub const fn punctuation_latin(chr: char) -> Option<char> {
+    Some(match chr {
+        DC_UNDER_LINE_V => ',',
+        PUNCT_DOT_1 => '.',
+        PUNCT_DOT_2 => ':',
+        PUNCT_DOT_3 => '⁝',
+        PUNCT_DOT_4 => '⁘',
+        PUNCT_DOT_5 => '⸭',
+
+        PUNCT_LINE_1 => '-',
+        PUNCT_LINE_2 => '=',
+
+        PUNCT_INTERR => '?',
+        PUNCT_EXCLAM | PUNCT_THORIN => '!',
+        PUNCT_PAREN => '|',
+        PUNCT_PAREN_L => '(',
+        PUNCT_PAREN_R => ')',
+
+        _ => { return None; }
+    })
+}
+
+
+/// Return the romanization of a vowel [`Tehta`], in either its short or long
+///     form.
+pub const fn vowel_latin(tehta: Tehta, long: bool) -> Option<&'static str> {
+    Some(match (tehta.base, long) {
+        (DC_OVER_DOT_3 | DC_OVER_CIRCUMFLEX, false) => "a",
+        (DC_OVER_DOT_3 | DC_OVER_CIRCUMFLEX, true) => "á",
+        (DC_OVER_ACUTE_1, false) => "e",
+        (DC_OVER_ACUTE_1, true) => "é",
+        (DC_OVER_DOT_1, false) => "i",
+        (DC_OVER_DOT_1, true) => "í",
+        (DC_OVER_HOOK_R_1, false) => "o",
+        (DC_OVER_HOOK_R_1, true) => "ó",
+        (DC_OVER_HOOK_L_1, false) => "u",
+        (DC_OVER_HOOK_L_1, true) => "ú",
+        (DC_OVER_DOT_2, false) => "y",
+        (DC_OVER_DOT_2, true) => "ý",
+        _ => { return None; }
+    })
+}
+
+
+/// Write the romanization of the [`Tehta`] of a [`Glyph`], if it has one.
+pub fn push_vowel(out: &mut String, glyph: &Glyph) {
+    if let Some(tehta) = glyph.tehta {
+        out.push_str(vowel_latin(tehta, glyph.tehta_alt).unwrap_or_default());
+    }
+}
+
+
+/// Write the romanization of a consonant, taking into account whether it is
+///     nasalized or lengthened.
+pub fn push_consonant(out: &mut String, glyph: &Glyph, latin: &str) {
+    if glyph.nasal {
+        match glyph.base {
+            Some(base) if TEMA_PARMA.contains(base) => out.push('m'),
+            _ => out.push('n'),
+        }
+    }
+
+    out.push_str(latin);
+
+    if glyph.long_cons {
+        out.push_str(latin);
+    }
+}
+
+
+/// Return `true` if an optional [`Token`] is a [`Glyph`].
+pub const fn is_glyph(token: Option<&Token>) -> bool {
+    matches!(token, Some(Token::Glyph(_)))
+}
+
+
+/// This trait defines the conversion of [`Token`]s, as read from text written
+///     in the Tengwar, back into romanized Latin text. It is implemented for
+///     each of the default modes.
+pub trait Romanize {
+    /// Write the romanization of a single [`Glyph`]. The previous and following
+    ///     [`Token`]s are provided, for any rules that depend on the position
+    ///     of the glyph within a word.
+    fn romanize_glyph(
+        glyph: &Glyph,
+        prev: Option<&Token>,
+        next: Option<&Token>,
+        out: &mut String,
+    );
+
+    /// Write the romanization of a [`Numeral`], using the notation expected by
+    ///     [`Numeral::parse`].
+    fn romanize_numeral(numeral: &Numeral, out: &mut String) {
+        use crate::characters::numeral::{PREF_DEC_IN, SUFF_ORD_IN};
+
+        if numeral.base_10 { out.push(PREF_DEC_IN); }
+        out.push_str(&numeral.value.to_string());
+        if numeral.ordinal { out.push(SUFF_ORD_IN); }
+    }
+
+    /// Write the romanization of a [`char`] that was not part of any [`Glyph`]
+    ///     or [`Numeral`]. Punctuation is converted back into Latin forms, and
+    ///     anything that would otherwise be transcribed is escaped.
+    fn romanize_char(char: char, out: &mut String) {
+        if let Some(latin) = punctuation_latin(char) {
+            out.push(latin);
+        } else {
+            if char.is_alphanumeric() || punctuation(char).is_some() {
+                out.push(ESC);
+            }
+
+            out.push(char);
+        }
+    }
+
+    /// Read text written in the Tengwar, and convert it into romanized text.
+    fn romanize(text: impl AsRef<str>) -> String {
+        let tokens: Vec<Token> = TengwarParser::new(text).collect();
+        let mut out = String::new();
+
+        for (i, token) in tokens.iter().enumerate() {
+            match token {
+                Token::Char(char) => Self::romanize_char(*char, &mut out),
+                Token::Number(numeral) => Self::romanize_numeral(numeral, &mut out),
+                Token::Rune(rune) => out.push_str(&rune.to_string()),
+                Token::Directive(_) => {}
+                Token::Glyph(glyph) => Self::romanize_glyph(
+                    glyph,
+                    i.checked_sub(1).and_then(|p| tokens.get(p)),
+                    tokens.get(i + 1),
+                    &mut out,
+                ),
+            }
+        }
+
+        out
+    }
+}
+
+
+/// Convert text written in the Tengwar back into romanized text, according to
+///     the rules of a mode that implements [`Romanize`].
+pub fn romanize<M: Romanize>(text: impl AsRef<str>) -> String {
+    M::romanize(text)
+}
@@ -0,0 +1,102 @@
+use crate::{characters::*, mode::beleriand::*, Beleriand, Token};
+use super::*;
+
+
+/// Return the romanization of a tengwa, as used in the Mode of Beleriand.
+pub const fn consonant_latin(tengwa: char, is_final: bool) -> Option<&'static str> {
+    Some(match tengwa {
+        TENGWA_TINCO => "t",
+        TENGWA_ANDO => "d",
+        TENGWA_THULE => "th",
+        TENGWA_ANTO => "dh",
+        TENGWA_NUMEN => "nn",
+        TENGWA_ORE => "n",
+
+        TENGWA_PARMA => "p",
+        TENGWA_UMBAR => "b",
+        //  A final F is written with Ampa, so a final Formen must have been
+        //      written as PH.
+        TENGWA_FORMEN if is_final => "ph",
+        TENGWA_FORMEN => "f",
+        TENGWA_AMPA if is_final => "f",
+        TENGWA_AMPA => "v",
+        TENGWA_MALTA => "mm",
+        TENGWA_VALA => "m",
+
+        TENGWA_CALMA => "c",
+        TENGWA_ANGA => "g",
+        TENGWA_AHA => "ch",
+        TENGWA_ANCA => "gh",
+        TENGWA_NOLDO => "ng",
+
+        TENGWA_WILYA => "w",
+
+        TENGWA_LAMBE => "l",
+        TENGWA_ROMEN => "r",
+        TENGWA_ALDA => "lh",
+        TENGWA_ARDA => "rh",
+        TENGWA_VALA_HOOKED => "mh",
+        TENGWA_SILME => "s",
+        TENGWA_ESSE => "ss",
+
+        TENGWA_HYARMEN => "h",
+        TENGWA_HWESTA_SINDARINWA => "hw",
+
+        _ => { return None; }
+    })
+}
+
+
+/// Return the romanization of a vowel tengwa, as used in the Mode of Beleriand.
+///     Vowels written with the short carrier have no base character.
+pub fn vowel_tengwa_latin(glyph: &Glyph) -> Option<char> {
+    let long: bool = glyph.tehta.map(|t| t.base) == Some(ANDAITH.base);
+
+    Some(match glyph.base {
+        Some(VOWEL_A) => if long { 'á' } else { 'a' },
+        Some(VOWEL_E) => if long { 'é' } else { 'e' },
+        None if !glyph.tehta_alt => if long { 'í' } else { 'i' },
+        Some(VOWEL_O) => if long { 'ó' } else { 'o' },
+        Some(VOWEL_U) => if long { 'ú' } else { 'u' },
+        Some(VOWEL_Y) => if long { 'ý' } else { 'y' },
+        Some(TENGWA_SILME) if glyph.nuquerna => if long { 'ý' } else { 'y' },
+        _ => { return None; }
+    })
+}
+
+
+impl Romanize for Beleriand {
+    fn romanize_glyph(
+        glyph: &Glyph,
+        prev: Option<&Token>,
+        next: Option<&Token>,
+        out: &mut String,
+    ) {
+        let initial: bool = !is_glyph(prev);
+        let is_final: bool = !is_glyph(next);
+
+        if let Some(vowel) = vowel_tengwa_latin(glyph) {
+            out.push(vowel);
+
+            //  Diphthongs are written with a marking above the first vowel.
+            if glyph.tehta == Some(TEHTA_Y) {
+                out.push('i');
+            } else if glyph.labial {
+                out.push('u');
+            }
+        } else if let Some(base) = glyph.base {
+            let latin: &str = consonant_latin(base, is_final).unwrap_or_default();
+            push_consonant(out, glyph, latin);
+        } else {
+            //  The extended carrier is used for a consonantal I, which can only
+            //      be written as such at the start of a word, where it is
+            //      followed by a vowel.
+            match next {
+                Some(Token::Glyph(g)) if initial && vowel_tengwa_latin(g).is_some() => {
+                    out.push('i');
+                }
+                _ => out.push('j'),
+            }
+        }
+    }
+}
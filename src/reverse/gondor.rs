@@ -0,0 +1,89 @@
+use crate::{characters::*, mode::gondor::*, Gondor, Token};
+use super::*;
+
+
+/// Return the romanization of a tengwa, as used in the Mode of Gondor.
+pub const fn consonant_latin(tengwa: char, is_final: bool) -> Option<&'static str> {
+    Some(match tengwa {
+        TENGWA_TINCO => "t",
+        TENGWA_ANDO => "d",
+        TENGWA_THULE => "th",
+        TENGWA_ANTO => "dh",
+        TENGWA_NUMEN => "n",
+        TENGWA_ORE | TENGWA_ROMEN => "r",
+
+        TENGWA_PARMA => "p",
+        TENGWA_UMBAR => "b",
+        //  A final F is written with Ampa, so a final Formen must have been
+        //      written as PH.
+        TENGWA_FORMEN if is_final => "ph",
+        TENGWA_FORMEN => "f",
+        TENGWA_AMPA if is_final => "f",
+        TENGWA_AMPA => "v",
+        TENGWA_MALTA => "m",
+
+        TENGWA_QESSE => "c",
+        TENGWA_UNGWE => "g",
+        TENGWA_HWESTA => "ch",
+        TENGWA_UNQUE => "gh",
+        TENGWA_NWALME => "ñ",
+        TENGWA_WILYA => "w",
+
+        TENGWA_LAMBE => "l",
+        TENGWA_ALDA => "lh",
+        TENGWA_ARDA => "rh",
+        TENGWA_MALTA_HOOKED => "mh",
+        TENGWA_SILME => "s",
+        TENGWA_ESSE => "ss",
+
+        TENGWA_HYARMEN => "h",
+        TENGWA_HWESTA_SINDARINWA => "hw",
+
+        _ => { return None; }
+    })
+}
+
+
+impl Romanize for Gondor {
+    fn romanize_glyph(
+        glyph: &Glyph,
+        prev: Option<&Token>,
+        next: Option<&Token>,
+        out: &mut String,
+    ) {
+        let initial: bool = !is_glyph(prev);
+        let is_final: bool = !is_glyph(next);
+
+        //  In this mode, a vowel is written above the consonant that follows it.
+        push_vowel(out, glyph);
+
+        match glyph.base {
+            //  Diphthongs are written with the first vowel above a carrier that
+            //      represents the second.
+            Some(CARRIER_DIPH_E) if glyph.tehta.is_some() => out.push('e'),
+            Some(CARRIER_DIPH_I) if glyph.tehta.is_some() => out.push('i'),
+            Some(CARRIER_DIPH_U) if glyph.tehta.is_some() => out.push('u'),
+
+            //  A consonantal I can only be written as such at the start of a
+            //      word, where it is followed by a vowel.
+            Some(CONSONANT_I) => match next {
+                Some(Token::Glyph(g)) if initial && g.tehta.is_some() => {
+                    out.push('i');
+                }
+                _ => out.push('j'),
+            }
+
+            //  X is written with Qessë and a sa-rincë.
+            Some(TENGWA_QESSE) if glyph.rince => out.push('x'),
+            Some(base) => {
+                let latin: &str = consonant_latin(base, is_final)
+                    .unwrap_or_default();
+                push_consonant(out, glyph, latin);
+
+                if glyph.labial { out.push('w'); }
+                if glyph.rince { out.push('s'); }
+            }
+            None => {}
+        }
+    }
+}
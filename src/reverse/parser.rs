@@ -0,0 +1,230 @@
+use crate::{characters::*, Token};
+use crate::characters::numeral::*;
+
+
+/// Return `true` if a [`char`] is one of the base tengwar that may begin a
+///     [`Glyph`].
+pub const fn is_tengwa(c: char) -> bool {
+    matches!(c,
+        TENGWA_TINCO..=TENGWA_TELCO
+        | TENGWA_OSSE_REV..=TENGWA_OSSE
+        | TENGWA_TELCO_LIG..=TENGWA_WAIA
+    )
+}
+
+
+/// Return the [`Tehta`] represented by a diacritic [`char`], along with whether
+///     it is the alternate "long" form of that tehta.
+pub const fn find_tehta(c: char) -> Option<(Tehta, bool)> {
+    const ALL: [Tehta; 7] = [
+        TEHTA_A, TEHTA_YANTA, TEHTA_E, TEHTA_I, TEHTA_O, TEHTA_U, TEHTA_Y,
+    ];
+
+    let mut i: usize = 0;
+
+    while i < ALL.len() {
+        let tehta: Tehta = ALL[i];
+
+        if tehta.base == c {
+            return Some((tehta, false));
+        } else if let Some(alt) = tehta.alternate {
+            if alt == c {
+                return Some((tehta, true));
+            }
+        }
+
+        i += 1;
+    }
+
+    None
+}
+
+
+/// An iterator that reads text written in the Tengwar, and yields the [`Token`]s
+///     that would have been rendered to produce it. This is the inverse of the
+///     [`Display`] implementations of [`Glyph`] and [`Numeral`].
+///
+/// Any rendering of a `Glyph` can be read, regardless of the settings that were
+///     used; The separate extended carrier of a long vowel is yielded as its
+///     own `Glyph`, and [zero-width joiners](ZWJ) are discarded.
+///
+/// [`Display`]: std::fmt::Display
+#[derive(Clone, Debug)]
+pub struct TengwarParser {
+    chars: Vec<char>,
+    head: usize,
+    /// Number of [`char`]s that will be yielded unchanged, starting at
+    ///     `chars[head]`.
+    literal: usize,
+}
+
+impl TengwarParser {
+    /// Set up a new parser over text written in the Tengwar. The text may be
+    ///     in either [`Encoding`](encoding::Encoding).
+    pub fn new(text: impl AsRef<str>) -> Self {
+        let chars = text.as_ref().chars().map(|c| encoding::Encoding::Smp.decode(c));
+        Self { chars: chars.collect(), head: 0, literal: 0 }
+    }
+
+    /// Return the [`char`] at a position relative to the read head.
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.head + offset).copied()
+    }
+
+    /// Read a [`Glyph`], beginning with a base [`char`] at the read head, and
+    ///     continuing through all diacritics that follow it.
+    fn read_glyph(&mut self, base: char) -> Glyph {
+        let mut glyph: Glyph = match base {
+            TENGWA_TELCO => Glyph::new(),
+            TENGWA_TELCO_LIG => Glyph { ligate_short: true, ..Glyph::new() },
+            TENGWA_ARA => Glyph { tehta_alt: true, ..Glyph::new() },
+            base => Glyph::new_base(base),
+        };
+
+        self.head += 1;
+
+        while let Some(next) = self.peek(0) {
+            match next {
+                MOD_NASAL => glyph.nasal = true,
+                MOD_LONG_CONS => glyph.long_cons = true,
+                MOD_LABIAL => glyph.labial = true,
+                MOD_PALATAL => glyph.palatal = true,
+                DC_INNER_DOT_1 => glyph.dot_inner = true,
+                DC_UNDER_DOT_1 => glyph.dot_under = true,
+                SA_RINCE => glyph.rince = true,
+                SA_RINCE_FINAL => {
+                    glyph.rince = true;
+                    glyph.rince_final = true;
+                }
+                c => match (find_tehta(c), glyph.tehta) {
+                    //  The same tehta written a second time is a long vowel.
+                    (Some((new, false)), Some(old)) if new == old => {
+                        glyph.tehta_alt = true;
+                        glyph.vowels = VowelStyle::Doubled;
+                    }
+                    (Some((new, alt)), None) => {
+                        glyph.tehta = Some(new);
+
+                        if alt {
+                            glyph.tehta_alt = true;
+                            glyph.vowels = VowelStyle::Unique;
+                        }
+                    }
+                    _ => break,
+                }
+            }
+
+            self.head += 1;
+        }
+
+        //  A Nuquerna variant is only chosen to make room for a tehta. Without
+        //      a tehta, the inverted character must be meant literally.
+        if glyph.tehta.is_some()
+            && (glyph.replace_base(TENGWA_SILME_NUQ, TENGWA_SILME)
+            || glyph.replace_base(TENGWA_ESSE_NUQ, TENGWA_ESSE))
+        {
+            glyph.nuquerna = true;
+        }
+
+        glyph
+    }
+
+    /// Read a [`Numeral`], beginning at the read head. Returns [`None`] if there
+    ///     is no numeral here.
+    fn read_numeral(&mut self) -> Option<Numeral> {
+        let negative: bool = self.peek(0) == Some(Numeral::PREF_NEG_OUT);
+        let mut offset: usize = negative as usize;
+
+        let mut digits: Vec<usize> = Vec::new();
+        let mut units: Option<usize> = None;
+        let mut base_10: Option<bool> = None;
+        let mut lines: bool = false;
+
+        while let Some(digit) = self.peek(offset) {
+            let Some(value) = NUMERAL.iter().position(|&n| n == digit) else {
+                break;
+            };
+
+            digits.push(value);
+            offset += 1;
+
+            while let Some(mark) = self.peek(offset) {
+                match mark {
+                    BASE_10_DOT => base_10 = Some(true),
+                    BASE_10_LINE => {
+                        base_10 = Some(true);
+                        lines = true;
+                    }
+                    BASE_12_DOT => base_10 = Some(false),
+                    BASE_12_LINE => {
+                        base_10 = Some(false);
+                        lines = true;
+                    }
+                    MOD_UNITS => units = Some(digits.len() - 1),
+                    _ => break,
+                }
+
+                offset += 1;
+            }
+        }
+
+        if digits.is_empty() {
+            return None;
+        }
+
+        let ordinal: bool = self.peek(offset) == Some(Numeral::SUFF_ORD_OUT);
+        let base_10: bool = base_10.unwrap_or(false);
+        let little_endian: bool = units.is_none_or(|i| i == 0);
+
+        if !little_endian {
+            digits.reverse();
+        }
+
+        let radix: isize = if base_10 { 10 } else { 12 };
+        let magnitude: Option<isize> = digits.iter().rev().try_fold(0, |acc: isize, &d| {
+            acc.checked_mul(radix)?.checked_add(d as isize)
+        });
+
+        //  A value too large to be held is yielded as text, digit by digit.
+        let Some(magnitude) = magnitude else {
+            self.literal = offset + ordinal as usize;
+            return None;
+        };
+
+        self.head += offset + ordinal as usize;
+
+        Some(Numeral {
+            value: if negative { -magnitude } else { magnitude },
+            base_10,
+            ordinal,
+            lines,
+            little_endian,
+        })
+    }
+}
+
+impl Iterator for TengwarParser {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let char: char = self.peek(0)?;
+
+            if 0 < self.literal {
+                self.head += 1;
+                self.literal -= 1;
+                break Some(Token::Char(char));
+            } else if char == ZWJ {
+                //  Joiners only affect rendering.
+                self.head += 1;
+            } else if let Some(numeral) = self.read_numeral() {
+                break Some(Token::Number(numeral));
+            } else if is_tengwa(char) {
+                break Some(Token::Glyph(self.read_glyph(char)));
+            } else {
+                self.head += 1;
+                break Some(Token::Char(char));
+            }
+        }
+    }
+}
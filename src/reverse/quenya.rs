@@ -0,0 +1,104 @@
+use crate::{characters::*, mode::quenya::*, Quenya, Token};
+use super::*;
+
+
+/// Return the romanization of a tengwa, as used in the Classical Mode.
+pub const fn consonant_latin(tengwa: char, initial: bool) -> Option<&'static str> {
+    Some(match tengwa {
+        TENGWA_TINCO => "t",
+        TENGWA_ANDO => "nd",
+        TENGWA_THULE => "th",
+        TENGWA_ANTO => "nt",
+        TENGWA_NUMEN => "n",
+        TENGWA_ORE | TENGWA_ROMEN => "r",
+
+        TENGWA_PARMA => "p",
+        TENGWA_UMBAR => "mb",
+        TENGWA_FORMEN => "f",
+        TENGWA_AMPA => "mp",
+        TENGWA_MALTA => "m",
+        TENGWA_VALA => "v",
+
+        TENGWA_CALMA => "c",
+        TENGWA_ANGA => "ng",
+        //  Initial H is written with Hyarmen, so an initial Aha must have been
+        //      written as a spirant.
+        TENGWA_AHA if initial => "ch",
+        TENGWA_AHA => "h",
+        TENGWA_ANCA => "nc",
+        TENGWA_NOLDO => "ñ",
+        TENGWA_ANNA => "ʒ",
+
+        TENGWA_QESSE => "qu",
+        TENGWA_UNGWE => "ngw",
+        TENGWA_HWESTA => "hw",
+        TENGWA_UNQUE => "nqu",
+        TENGWA_NWALME => "ñw",
+        TENGWA_WILYA => "w",
+
+        TENGWA_ARDA => "rd",
+        TENGWA_LAMBE => "l",
+        TENGWA_ALDA => "ld",
+        TENGWA_SILME => "s",
+        TENGWA_ESSE => "ss",
+        TENGWA_HYARMEN | TENGWA_HALLA => "h",
+
+        _ => { return None; }
+    })
+}
+
+
+impl Romanize for Quenya {
+    fn romanize_glyph(
+        glyph: &Glyph,
+        prev: Option<&Token>,
+        next: Option<&Token>,
+        out: &mut String,
+    ) {
+        let initial: bool = !is_glyph(prev);
+        let is_final: bool = !is_glyph(next);
+
+        match glyph.base {
+            //  Diphthongs are written with the first vowel above a carrier that
+            //      represents the second.
+            Some(CARRIER_DIPH_I) if glyph.tehta.is_some() => {
+                push_vowel(out, glyph);
+                out.push('i');
+            }
+            Some(CARRIER_DIPH_U) if glyph.tehta.is_some() => {
+                push_vowel(out, glyph);
+                out.push('u');
+            }
+            Some(base) => {
+                match base {
+                    //  Initial Y is written with Anna, marked as palatal.
+                    TENGWA_ANNA if glyph.palatal => out.push('y'),
+                    //  X is written with Calma and a sa-rincë.
+                    TENGWA_CALMA if glyph.rince => out.push('x'),
+                    base => {
+                        let latin: &str = consonant_latin(base, initial)
+                            .unwrap_or_default();
+                        push_consonant(out, glyph, latin);
+
+                        if glyph.palatal { out.push('y'); }
+                        if glyph.rince { out.push('s'); }
+                    }
+                }
+
+                push_vowel_final(out, glyph, is_final);
+            }
+            None => push_vowel_final(out, glyph, is_final),
+        }
+    }
+}
+
+
+/// Write the romanization of the vowel of a [`Glyph`]. A short E at the end of a
+///     word is marked with a diaeresis, by convention.
+fn push_vowel_final(out: &mut String, glyph: &Glyph, is_final: bool) {
+    if is_final && !glyph.tehta_alt && glyph.tehta == Some(TEHTA_E) {
+        out.push('ë');
+    } else {
+        push_vowel(out, glyph);
+    }
+}
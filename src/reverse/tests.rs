@@ -0,0 +1,152 @@
+#![cfg(test)]
+
+use crate::*;
+use super::*;
+
+
+const TEXTS_Q: &[&str] = &[
+    "ai! laurië lantar lassi súrinen",
+    "yéni únótimë ve rámar aldaron",
+    "Andúnë pella Vardo tellumar nu luini",
+    "ómaryo airetári.lírinen",
+    "sí man i yulma nin enquantuva?",
+    "ar hísië untúpa Calaciryo míri oialë",
+    "Ñoldo ngwalmë hwesta otso mixa hyarmen ahto",
+    "Laurelin nánë i 2@ alda, #22 -7",
+];
+const TEXTS_S: &[&str] = &[
+    "mae govannen",
+    "ennyn durin aran moria , pedo mellon a minno",
+    "im narvi hain echant , celebrimbor o eregion teithant i thiw hin",
+    "a Elbereth Gilthoniel, silivren penna míriel",
+    "na-chaered palan-díriel o galadhremmin ennorath",
+    "Fanuilos , le linnathon nef aear , sí nef aearon",
+    "le nallon sí di'nguruthos ! a tiro nin , Fanuilos :-",
+    "iant lhûg rhaw alph edhil Ivrin ylf aew nædh",
+];
+
+
+fn settings() -> Vec<TranscriberSettings> {
+    let mut all = Vec::new();
+
+    for vowels in [VowelStyle::Doubled, VowelStyle::Separate, VowelStyle::Unique] {
+        for nuquerna in [false, true] {
+            let mut settings = TranscriberSettings::new();
+            settings.vowels = vowels;
+            settings.nuquerna = nuquerna;
+            all.push(settings);
+
+            settings.alt_a = true;
+            settings.alt_rince = true;
+            settings.dot_plain = true;
+            settings.ligate_short = true;
+            settings.ligate_zwj = u8::MAX;
+            all.push(settings);
+        }
+    }
+
+    all
+}
+
+
+fn round_trip<M>(texts: &[&str])
+    where M: Romanize + TengwarMode + Default,
+{
+    for settings in settings() {
+        for &text in texts {
+            let first: String = text.to_tengwar_with::<M>(settings);
+            let latin: String = M::romanize(&first);
+            let second: String = latin.to_tengwar_with::<M>(settings);
+
+            assert_eq!(first, second,
+                "Romanization of {text:?} does not transcribe back to the same.\
+                \n  Romanized: {latin:?}\
+                \n  Settings:  {settings:?}\
+                \n  Expected:  {first}\
+                \n  Received:  {second}",
+            );
+        }
+    }
+}
+
+
+#[test]
+fn round_trip_beleriand() {
+    round_trip::<Beleriand>(TEXTS_S);
+}
+
+
+#[test]
+fn round_trip_gondor() {
+    round_trip::<Gondor>(TEXTS_S);
+}
+
+
+#[test]
+fn round_trip_quenya() {
+    round_trip::<Quenya>(TEXTS_Q);
+}
+
+
+#[test]
+fn romanization() {
+    let tengwar: String = transcribe::<Quenya>("Elen síla lúmenn' omentielvo");
+    assert_eq!(Quenya::romanize(tengwar), "elen síla lúmenn. omentielvo");
+
+    let tengwar: String = transcribe::<Quenya>("lotsë súva");
+    assert_eq!(Quenya::romanize(tengwar), "lotsë súva");
+
+    let tengwar: String = transcribe::<Gondor>("ennyn durin aran moria");
+    assert_eq!(Gondor::romanize(tengwar), "ennyn durin aran moria");
+
+    let tengwar: String = transcribe::<Beleriand>("pedo mellon a minno");
+    assert_eq!(Beleriand::romanize(tengwar), "pedo mellon a minno");
+}
+
+
+#[test]
+fn numerals() {
+    for value in [0, 1, 9, 12, 22, 144, 1000, -7, -1234] {
+        for base_10 in [false, true] {
+            for lines in [false, true] {
+                for little_endian in [false, true] {
+                    let numeral = Numeral {
+                        little_endian,
+                        ..Numeral::new(value, base_10).with_lines(lines)
+                    };
+                    let text: String = numeral.to_string();
+                    let tokens: Vec<Token> = TengwarParser::new(&text).collect();
+
+                    let [Token::Number(parsed)] = tokens.as_slice() else {
+                        panic!("Failed to read {numeral:?} from {text:?}.");
+                    };
+
+                    assert_eq!(parsed.value, value, "Wrong value from {text:?}.");
+                    assert_eq!(parsed.to_string(), text, "Wrong form of {numeral:?}.");
+                }
+            }
+        }
+    }
+}
+
+
+#[test]
+fn long_numerals() {
+    //  A numeral too large to be held is read as text, digit by digit.
+    let text: String = [NUMERAL[7]; 30].iter().collect();
+    let tokens: Vec<Token> = TengwarParser::new(&text).collect();
+
+    assert_eq!(tokens.len(), 30);
+    assert!(tokens.iter().all(|token| matches!(token, Token::Char(_))));
+    assert_eq!(Quenya::romanize(&text), text);
+}
+
+
+#[test]
+fn escapes() {
+    let tengwar: String = transcribe::<Quenya>("i \\x \\, yára");
+    let latin: String = Quenya::romanize(&tengwar);
+
+    assert_eq!(latin, "i \\x \\, yára");
+    assert_eq!(transcribe::<Quenya>(latin), tengwar);
+}
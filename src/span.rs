@@ -0,0 +1,286 @@
+//! This module defines the mapping of [`Token`]s back to the text they were
+//!     read from, and forward to the text they are rendered into.
+//!
+//! A [`Tokenizer`] normalizes its input before reading it, so the positions of
+//!     its `char`s are not the same as their positions in the original text.
+//!     To keep track of this, the input is normalized in short segments, each
+//!     beginning with a character that does not combine with the one before
+//!     it. Every normalized `char` is then given the [`Span`] of the segment it
+//!     came from. This means that a precomposed character, such as `ë`, is
+//!     mapped to the same place as its decomposed form, `e` followed by a
+//!     combining diaeresis.
+//!
+//! The span of a `Token` covers all the input that was read while constructing
+//!     it, including any [escape sequence](crate::characters::ESC) before it.
+//!     Some modes read a `char` before deciding to finish a `Token`; In this
+//!     case, the `char` is counted toward the earlier `Token`, and a `Token`
+//!     may have an empty span.
+//!
+//! A [`SpanIter`] applies the rules of a [`TokenIter`], and also finds the
+//!     range of bytes that each `Token` occupies in the rendered output.
+//!
+//! # Example
+//! ```
+//! use tengwar::{Quenya, TranscriberSettings};
+//! use tengwar::mode::Tokenizer;
+//!
+//! let input = "namárië";
+//! let mut output = String::new();
+//!
+//! let tokenizer = Tokenizer::<Quenya>::from_str(input);
+//!
+//! for mapped in tokenizer.mapped(TranscriberSettings::new()) {
+//!     assert_eq!(mapped.output.start, output.len());
+//!     output.push_str(&mapped.token.to_string());
+//!     println!("{:?} -> {:?}", &input[mapped.input.bytes], &output[mapped.output]);
+//! }
+//! ```
+
+use std::{cell::RefCell, collections::VecDeque, iter::Peekable, ops::Range, rc::Rc};
+use unicode_normalization::{char::{canonical_combining_class, compose}, UnicodeNormalization};
+use crate::{
+    characters::ZWJ,
+    mode::{TengwarMode, Tokenizer},
+    policy::{Policy, Standard},
+    trace::SharedTrace,
+    Token,
+    TokenIter,
+    TranscriberSettings,
+};
+
+
+/// A position within a text, in both bytes and [`char`]s.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Span {
+    /// The range of bytes covered, suitable for slicing a [`str`].
+    pub bytes: Range<usize>,
+    /// The range of [`char`]s covered.
+    pub chars: Range<usize>,
+}
+
+impl Span {
+    /// Create an empty span at a position.
+    pub const fn empty(byte: usize, char: usize) -> Self {
+        Self { bytes: byte..byte, chars: char..char }
+    }
+
+    /// Return `true` if the span does not cover any text.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Create a span covering this one, and everything up to the end of
+    ///     another.
+    pub fn to(&self, other: &Self) -> Self {
+        Self {
+            bytes: self.bytes.start..other.bytes.end,
+            chars: self.chars.start..other.chars.end,
+        }
+    }
+
+    /// Move the span forward, as if the text it refers to was preceded by more
+    ///     text of the given length.
+    pub fn shift(self, bytes: usize, chars: usize) -> Self {
+        Self {
+            bytes: self.bytes.start + bytes..self.bytes.end + bytes,
+            chars: self.chars.start + chars..self.chars.end + chars,
+        }
+    }
+}
+
+
+/// Normalize text, and find the [`Span`] of the original text that each
+///     normalized [`char`] was produced from.
+pub(crate) fn normalize(text: &str) -> (Vec<char>, Vec<Span>) {
+    //  The normalized form and original span of each segment.
+    let mut segments: Vec<(Vec<char>, Span)> = Vec::new();
+    let mut start: Option<(usize, usize)> = None;
+
+    let push = |segments: &mut Vec<(Vec<char>, Span)>, span: Span| {
+        let raw: &str = &text[span.bytes.clone()];
+        let first: Option<char> = raw.nfd().next();
+
+        match segments.last_mut() {
+            //  The segment combines with the end of the previous one. Join them
+            //      and normalize them together.
+            Some((prev, prev_span)) if first.zip(prev.last().copied())
+                .is_some_and(|(first, last)| compose(last, first).is_some())
+            => {
+                *prev_span = prev_span.to(&span);
+                *prev = text[prev_span.bytes.clone()].nfc().collect();
+            }
+            _ => segments.push((raw.nfc().collect(), span)),
+        }
+    };
+
+    for (n, (idx, c)) in text.char_indices().enumerate() {
+        if canonical_combining_class(c) == 0 {
+            if let Some((byte, char)) = start {
+                push(&mut segments, Span { bytes: byte..idx, chars: char..n });
+            }
+
+            start = Some((idx, n));
+        } else if start.is_none() {
+            start = Some((idx, n));
+        }
+    }
+
+    if let Some((byte, char)) = start {
+        push(&mut segments, Span {
+            bytes: byte..text.len(),
+            chars: char..text.chars().count(),
+        });
+    }
+
+    let mut chars: Vec<char> = Vec::with_capacity(text.len());
+    let mut spans: Vec<Span> = Vec::with_capacity(text.len());
+
+    for (normal, span) in segments {
+        spans.extend(std::iter::repeat_n(span, normal.len()));
+        chars.extend(normal);
+    }
+
+    //  Normalizing in segments should always be the same as normalizing all at
+    //      once, but if it is not, the text must still be read correctly.
+    if !chars.iter().copied().eq(text.nfc()) {
+        chars = text.nfc().collect();
+        spans = vec![Span { bytes: 0..text.len(), chars: 0..text.chars().count() }; chars.len()];
+    }
+
+    (chars, spans)
+}
+
+
+/// A [`Token`] with its positions in the input and output texts.
+#[derive(Clone, Debug)]
+pub struct Mapped<P: Policy = Standard> {
+    /// The Token itself.
+    pub token: Token<P>,
+    /// The position of the input text that produced the Token.
+    pub input: Span,
+    /// The range of bytes of the rendered output that the Token occupies. Any
+    ///     [zero-width joiner](ZWJ) placed after the Token is not included.
+    pub output: Range<usize>,
+}
+
+
+/// The queue of [`Span`]s of tokens that have been read by a [`TokenIter`], but
+///     not yet returned by it.
+type SpanQueue = Rc<RefCell<VecDeque<Span>>>;
+
+
+/// An iterator which passes [`Token`]s into a [`TokenIter`], and remembers their
+///     [`Span`]s for later.
+struct SpanRecorder<I> {
+    inner: I,
+    queue: SpanQueue,
+}
+
+impl<I: Iterator<Item=(Token<P>, Span)>, P: Policy> Iterator for SpanRecorder<I> {
+    type Item = Token<P>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (token, span) = self.inner.next()?;
+
+        //  A directive is consumed by the TokenIter, and never returned. Its
+        //      span is not needed.
+        if !matches!(token, Token::Directive(_)) {
+            self.queue.borrow_mut().push_back(span);
+        }
+
+        Some(token)
+    }
+}
+
+
+/// An iterator which applies the rules of a [`TokenIter`] to [`Token`]s that
+///     have [`Span`]s, and finds the position of each `Token` in the rendered
+///     output.
+///
+/// Rendering the tokens of this iterator in order, at their output positions,
+///     produces the same text as collecting the equivalent `TokenIter` into a
+///     [`String`].
+pub struct SpanIter<I: Iterator<Item=(Token<P>, Span)>, P: Policy = Standard, Q: Policy = P> {
+    inner: Peekable<TokenIter<SpanRecorder<I>, P, Q>>,
+    queue: SpanQueue,
+    /// The number of bytes of output rendered so far.
+    len: usize,
+}
+
+impl<I: Iterator<Item=(Token<P>, Span)>, P: Policy> SpanIter<I, P, P> {
+    /// Construct a SpanIter around an arbitrary Iterator of [`Token`]s and
+    ///     their [`Span`]s.
+    pub fn new(iter: I, settings: TranscriberSettings) -> Self {
+        Self::build(iter, settings, None)
+    }
+
+    /// Construct a SpanIter which records the rules it applies in a [`Trace`].
+    ///
+    /// [`Trace`]: crate::trace::Trace
+    pub fn traced(iter: I, settings: TranscriberSettings, trace: SharedTrace) -> Self {
+        Self::build(iter, settings, Some(trace))
+    }
+
+    fn build(iter: I, settings: TranscriberSettings, trace: Option<SharedTrace>) -> Self {
+        let queue: SpanQueue = Default::default();
+        let recorder = SpanRecorder { inner: iter, queue: queue.clone() };
+        let mut inner = TokenIter::new(recorder).with_settings(settings);
+
+        if let Some(trace) = trace {
+            inner = inner.with_trace(trace);
+        }
+
+        Self {
+            inner: inner.peekable(),
+            queue,
+            len: 0,
+        }
+    }
+}
+
+impl<I, P, Q> Iterator for SpanIter<I, P, Q> where
+    I: Iterator<Item=(Token<P>, Span)>,
+    P: Policy,
+    Q: Policy,
+{
+    type Item = Mapped<Q>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token: Token<Q> = self.inner.next()?;
+        let input: Span = self.queue.borrow_mut().pop_front().unwrap_or_default();
+        let start: usize = self.len;
+
+        self.len += token.to_string().len();
+        let output: Range<usize> = start..self.len;
+
+        //  Account for a joiner, in the same way as when collecting a String.
+        if let (Token::Glyph(current), Some(Token::Glyph(next))) = (&token, self.inner.peek()) {
+            if current.ligates_with(next) {
+                self.len += ZWJ.len_utf8();
+            }
+        }
+
+        Some(Mapped { token, input, output })
+    }
+}
+
+
+/// An iterator over the [`Token`]s of a [`Tokenizer`], along with their
+///     [`Span`]s in its input text.
+pub struct Spanned<M: TengwarMode, P: Policy = Standard> {
+    tokenizer: Tokenizer<M, P>,
+}
+
+impl<M: TengwarMode, P: Policy> Spanned<M, P> {
+    pub(crate) const fn new(tokenizer: Tokenizer<M, P>) -> Self {
+        Self { tokenizer }
+    }
+}
+
+impl<M: TengwarMode, P: Policy> Iterator for Spanned<M, P> {
+    type Item = (Token<P>, Span);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.tokenizer.next_spanned()
+    }
+}
@@ -1,6 +1,8 @@
 use std::fmt::{Display, Formatter, Write};
 use crate::{
-    characters::{Glyph, Numeral, ZWJ},
+    characters::{encoding::WriteEncoded, Glyph, Numeral, ZWJ},
+    cirth::Rune,
+    iter::Directive,
     policy::{Policy, Standard},
 };
 
@@ -16,6 +18,11 @@ pub enum Token<P: Policy = Standard> {
     Glyph(Glyph<P>),
     /// A numeric value.
     Number(Numeral),
+    /// A specified certh of the Cirth.
+    Rune(Rune),
+    /// A change to the settings of a [`TokenIter`](crate::TokenIter), which is
+    ///     applied to all following tokens. This is not written to the output.
+    Directive(Directive),
     // /// UTF-8 text data.
     // String(Cow<'static, str>),
 }
@@ -27,6 +34,20 @@ impl<P: Policy> Token<P> {
             Self::Glyph(glyph) => Token::Glyph(glyph.change_policy()),
             Self::Char(char) => Token::Char(char),
             Self::Number(number) => Token::Number(number),
+            Self::Rune(rune) => Token::Rune(rune),
+            Self::Directive(directive) => Token::Directive(directive),
+        }
+    }
+
+    /// Switch any [`Glyph`] in the token to use a different [`Policy`],
+    ///     following the rules of a value of it, if one is given.
+    pub const fn with_policy<Q: Policy>(self, policy: Option<Q>) -> Token<Q> {
+        match self {
+            Self::Glyph(glyph) => Token::Glyph(glyph.with_policy(policy)),
+            Self::Char(char) => Token::Char(char),
+            Self::Number(number) => Token::Number(number),
+            Self::Rune(rune) => Token::Rune(rune),
+            Self::Directive(directive) => Token::Directive(directive),
         }
     }
 
@@ -36,6 +57,8 @@ impl<P: Policy> Token<P> {
             Self::Char(_) => None,
             Self::Glyph(g) => Some(g),
             Self::Number(_) => None,
+            Self::Rune(_) => None,
+            Self::Directive(_) => None,
         }
     }
 
@@ -45,6 +68,19 @@ impl<P: Policy> Token<P> {
             Self::Char(_) => None,
             Self::Glyph(_) => None,
             Self::Number(n) => Some(n),
+            Self::Rune(_) => None,
+            Self::Directive(_) => None,
+        }
+    }
+
+    /// Return a reference to the [`Rune`], if there is one.
+    pub const fn rune(&self) -> Option<&Rune> {
+        match self {
+            Self::Char(_) => None,
+            Self::Glyph(_) => None,
+            Self::Number(_) => None,
+            Self::Rune(r) => Some(r),
+            Self::Directive(_) => None,
         }
     }
 }
@@ -52,9 +88,11 @@ impl<P: Policy> Token<P> {
 impl<P: Policy> Display for Token<P> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Char(ch) => f.write_char(*ch),
+            Self::Char(ch) => f.write_encoded(*ch),
             Self::Glyph(g) => g.fmt(f),
             Self::Number(n) => n.fmt(f),
+            Self::Rune(r) => r.fmt(f),
+            Self::Directive(_) => Ok(()),
             // Self::String(s) => f.write_str(s),
         }
     }
@@ -0,0 +1,320 @@
+//! This module defines a record of the steps taken to produce each [`Token`],
+//!     which can be used to explain why a transcription looks the way it does.
+//!
+//! A [`Trace`] is shared between a [`Tokenizer`] and a [`TokenIter`], each of
+//!     which adds [`Event`]s to it as they work:
+//!
+//! 1. The `Tokenizer` records every chunk of input given to the mode, and the
+//!    [`ParseAction`] that the mode returned for it, along with the other ways
+//!    it can complete a `Token`, such as by passing a `char` through.
+//! 2. The `Tokenizer` records each completed `Token`, and then any change made
+//!    to it by [`TengwarMode::finalize`].
+//! 3. The `TokenIter` records each of its rules that changes the rendering of a
+//!    `Token`, such as the alternate A-tehta or the Nuquernë variants.
+//!
+//! These events are then grouped by `Token` with [`Trace::explain`].
+//!
+//! [`TengwarMode::finalize`]: crate::TengwarMode::finalize
+//! [`Tokenizer`]: crate::mode::Tokenizer
+//! [`TokenIter`]: crate::TokenIter
+//!
+//! # Example
+//! ```
+//! use tengwar::{mode::Tokenizer, trace::Trace, Quenya, TokenIter, TranscriberSettings};
+//!
+//! let mut settings = TranscriberSettings::new();
+//! settings.nuquerna = true;
+//!
+//! let trace = Trace::shared();
+//! let tokenizer = Tokenizer::<Quenya>::from_str("essë").with_trace(trace.clone());
+//! let _: String = TokenIter::new(tokenizer)
+//!     .with_settings(settings)
+//!     .with_trace(trace.clone())
+//!     .collect();
+//!
+//! let explained = trace.borrow().explain();
+//! assert_eq!(explained.len(), 2);
+//! assert_eq!(explained[1].rules[0].0, "nuquerna");
+//! ```
+
+use std::{cell::RefCell, fmt::{Display, Formatter}, rc::Rc};
+use crate::{
+    characters::*,
+    mode::ParseAction,
+    policy::Policy,
+    Token,
+};
+
+
+/// A [`Trace`] which can be shared between the stages of transcription.
+pub type SharedTrace = Rc<RefCell<Trace>>;
+
+
+/// The way that a single step of a `Tokenizer` was resolved.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Action {
+    /// The mode returned [`ParseAction::MatchedNone`].
+    MatchedNone,
+    /// The mode returned [`ParseAction::MatchedPart`].
+    MatchedPart(usize),
+    /// The mode returned [`ParseAction::MatchedToken`].
+    MatchedToken(usize),
+    /// The mode returned [`ParseAction::Skip`].
+    Skip(usize),
+    /// The mode returned [`ParseAction::Escape`].
+    Escape { len_seq: usize, n_skip: usize },
+    /// The Token in progress was finished by `TengwarMode::finish_current`.
+    Finish,
+    /// A Token was found by `TengwarMode::find_secondary`.
+    Secondary(usize),
+    /// An inline [`Directive`](crate::Directive) was read.
+    Directive(usize),
+    /// A [`char`] was passed through unchanged.
+    Pass,
+}
+
+impl Action {
+    /// Describe the result of a call to `TengwarMode::process`.
+    pub const fn of<P: Policy>(action: &ParseAction<P>) -> Self {
+        match action {
+            ParseAction::MatchedNone => Self::MatchedNone,
+            ParseAction::MatchedPart(len) => Self::MatchedPart(*len),
+            ParseAction::MatchedToken { len, .. } => Self::MatchedToken(*len),
+            ParseAction::Skip(len) => Self::Skip(*len),
+            &ParseAction::Escape { len_seq, n_skip } => Self::Escape { len_seq, n_skip },
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MatchedNone => f.write_str("none"),
+            Self::MatchedPart(len) => write!(f, "part {len}"),
+            Self::MatchedToken(len) => write!(f, "token {len}"),
+            Self::Skip(len) => write!(f, "skip {len}"),
+            Self::Escape { len_seq, n_skip } => write!(f, "escape {len_seq}+{n_skip}"),
+            Self::Finish => f.write_str("finish"),
+            Self::Secondary(len) => write!(f, "secondary {len}"),
+            Self::Directive(len) => write!(f, "directive {len}"),
+            Self::Pass => f.write_str("pass"),
+        }
+    }
+}
+
+
+/// A single event in the transcription of a text.
+#[derive(Clone, Debug)]
+pub enum Event {
+    /// A step of a `Tokenizer`, with the chunk of input it considered.
+    Step { chunk: String, action: Action },
+    /// A `Tokenizer` completed a [`Token`].
+    Token { index: usize, token: Token },
+    /// A [`Token`] was changed by `TengwarMode::finalize`.
+    Finalize { index: usize, before: Token, after: Token },
+    /// A [`Token`] was changed by a rule of a `TokenIter`.
+    Rule { index: usize, rule: &'static str, before: Token, after: Token },
+}
+
+
+/// A record of [`Event`]s, shared by the stages of transcription.
+#[derive(Clone, Debug, Default)]
+pub struct Trace {
+    events: Vec<Event>,
+    tokens: usize,
+}
+
+impl Trace {
+    /// Create a new, empty trace, which can be shared.
+    pub fn shared() -> SharedTrace {
+        Default::default()
+    }
+
+    /// Return all the events recorded so far, in order.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Return the number of Tokens completed so far.
+    pub const fn tokens(&self) -> usize {
+        self.tokens
+    }
+
+    /// Record a step of a `Tokenizer`.
+    pub fn step(&mut self, chunk: &[char], action: Action) {
+        self.events.push(Event::Step { chunk: chunk.iter().collect(), action });
+    }
+
+    /// Record a [`Token`] completed by a `Tokenizer`, and return its index.
+    pub fn token<P: Policy>(&mut self, token: &Token<P>) -> usize {
+        let index: usize = self.tokens;
+        self.tokens += 1;
+        self.events.push(Event::Token { index, token: token.change_policy() });
+        index
+    }
+
+    /// Record a change to a [`Token`] by `TengwarMode::finalize`. Nothing is
+    ///     recorded if its rendering did not change.
+    pub fn finalize<P: Policy>(
+        &mut self,
+        index: usize,
+        before: &Token<P>,
+        after: &Token<P>,
+    ) {
+        if changed(before, after) {
+            self.events.push(Event::Finalize {
+                index,
+                before: before.change_policy(),
+                after: after.change_policy(),
+            });
+        }
+    }
+
+    /// Record a change to a [`Token`] by a rule of a `TokenIter`. Nothing is
+    ///     recorded if its rendering did not change.
+    pub fn rule<P: Policy>(
+        &mut self,
+        index: usize,
+        rule: &'static str,
+        before: &Token<P>,
+        after: &Token<P>,
+    ) {
+        if changed(before, after) {
+            self.events.push(Event::Rule {
+                index,
+                rule,
+                before: before.change_policy(),
+                after: after.change_policy(),
+            });
+        }
+    }
+
+    /// Group the recorded events by the [`Token`] they apply to. Steps of the
+    ///     `Tokenizer` are given to the next Token it completed.
+    pub fn explain(&self) -> Vec<Explanation> {
+        let mut explained: Vec<Explanation> = Vec::with_capacity(self.tokens);
+        let mut steps: Vec<(String, Action)> = Vec::new();
+
+        for event in &self.events {
+            match event {
+                Event::Step { chunk, action } => steps.push((chunk.clone(), *action)),
+                Event::Token { token, .. } => explained.push(Explanation {
+                    token: *token,
+                    steps: std::mem::take(&mut steps),
+                    finalized: None,
+                    rules: Vec::new(),
+                }),
+                Event::Finalize { index, after, .. } => {
+                    if let Some(exp) = explained.get_mut(*index) {
+                        exp.finalized = Some(*after);
+                    }
+                }
+                Event::Rule { index, rule, after, .. } => {
+                    if let Some(exp) = explained.get_mut(*index) {
+                        exp.rules.push((rule, *after));
+                    }
+                }
+            }
+        }
+
+        explained
+    }
+}
+
+
+/// The events that produced a single [`Token`].
+#[derive(Clone, Debug)]
+pub struct Explanation {
+    /// The Token, as it was completed by the `Tokenizer`.
+    pub token: Token,
+    /// The steps taken by the `Tokenizer` to complete the Token, with the
+    ///     chunk of input considered by each one.
+    pub steps: Vec<(String, Action)>,
+    /// The Token after it was changed by `TengwarMode::finalize`, if it was
+    ///     changed.
+    pub finalized: Option<Token>,
+    /// The rules of a `TokenIter` that changed the Token, in order, with the
+    ///     Token after each change.
+    pub rules: Vec<(&'static str, Token)>,
+}
+
+impl Explanation {
+    /// Return the Token after all recorded changes.
+    pub fn result(&self) -> Token {
+        match self.rules.last() {
+            Some((_, token)) => *token,
+            None => self.finalized.unwrap_or(self.token),
+        }
+    }
+}
+
+
+/// Return `true` if two Tokens are not rendered the same way.
+fn changed<P: Policy>(before: &Token<P>, after: &Token<P>) -> bool {
+    before.to_string() != after.to_string()
+}
+
+
+/// Return the name of a tehta, if it is one of the standard vowels.
+fn tehta_name(tehta: &Tehta) -> Option<&'static str> {
+    [
+        (TEHTA_A, "a"), (TEHTA_E, "e"), (TEHTA_I, "i"), (TEHTA_O, "o"),
+        (TEHTA_U, "u"), (TEHTA_Y, "y"), (TEHTA_YANTA, "yanta"),
+    ].into_iter().find_map(|(t, name)| (t == *tehta).then_some(name))
+}
+
+
+/// Describe a [`Token`] in words, for display to a user.
+pub fn describe<P: Policy>(token: &Token<P>) -> String {
+    match token {
+        Token::Char(c) => format!("{c:?}"),
+        Token::Number(n) => format!("numeral {}", n.value),
+        Token::Rune(r) => format!("certh U+{:04X}", r.base as u32),
+        Token::Directive(d) => format!("{{{d}}}"),
+        Token::Glyph(glyph) => {
+            let mut text: String = match glyph.base.map(|_| glyph.base_nuq()) {
+                Some(base) => match tengwa_name(base) {
+                    Some(name) => name.into(),
+                    None => format!("U+{:04X}", base as u32),
+                },
+                None => "carrier".into(),
+            };
+
+            if let Some(tehta) = &glyph.tehta {
+                let name: &str = tehta_name(tehta).unwrap_or("tehta");
+
+                match (glyph.tehta_hidden, glyph.tehta_alt) {
+                    (true, _) => text.push_str(&format!(" + ({name})")),
+                    (false, true) => text.push_str(&format!(" + long {name}")),
+                    (false, false) => text.push_str(&format!(" + {name}")),
+                }
+            }
+
+            for (flag, name) in [
+                (glyph.nasal, "nasal"),
+                (glyph.labial, "labial"),
+                (glyph.palatal, "palatal"),
+                (glyph.long_cons, "long"),
+                (glyph.rince, "rincë"),
+                (glyph.dot_inner, "inner dot"),
+                (glyph.dot_under, "dot"),
+            ] {
+                if flag {
+                    text.push_str(" + ");
+                    text.push_str(name);
+                }
+            }
+
+            text
+        }
+    }
+}
+
+
+/// Add steps of a `Tokenizer` to a trace, if there is one.
+pub(crate) fn record_step(trace: &Option<SharedTrace>, chunk: &[char], action: Action) {
+    if let Some(trace) = trace {
+        trace.borrow_mut().step(chunk, action);
+    }
+}
+
@@ -0,0 +1,232 @@
+//! This module defines the [`Transform`] trait, for changes that a [`TokenIter`]
+//!     makes to each [`Token`] after it is read, and the built-in transforms
+//!     which apply the [`TranscriberSettings`].
+//!
+//! Every `TokenIter` holds a list of transforms, which begins with the result
+//!     of [`defaults`]. For each `Token`, the transforms are applied in order,
+//!     and each one can see the previous `Token`, as it was returned, and the
+//!     next `Token`, as it was read. More transforms can be added to the list,
+//!     to make changes that are specific to a project, without replacing the
+//!     iterator. A transform can be any type that implements the trait, or any
+//!     closure that takes a `Token` and its [`Context`].
+//!
+//! [`TokenIter`]: crate::TokenIter
+//!
+//! # Example
+//! ```
+//! use tengwar::{characters::{*, encoding::Encoding}, transform::Context, Quenya, TengwarMode, Token};
+//!
+//! //  Use Ossë, rather than Vala.
+//! let osse = |token: &mut Token, _: &Context| {
+//!     if let Token::Glyph(glyph) = token {
+//!         glyph.replace_base(TENGWA_VALA, TENGWA_OSSE);
+//!     }
+//! };
+//!
+//! let text: String = Quenya::default_transcriber("vanwa")
+//!     .with_transform(osse)
+//!     .collect();
+//! let text: String = Encoding::Csur.recode(&text);
+//!
+//! assert!(text.contains(TENGWA_OSSE));
+//! assert!(!text.contains(TENGWA_VALA));
+//! ```
+
+use crate::{
+    policy::{Policy, Standard},
+    Token,
+    TranscriberSettings,
+};
+
+
+/// The tokens around the one being transformed, and the settings in effect.
+#[derive(Clone, Copy, Debug)]
+pub struct Context<'c, P: Policy = Standard> {
+    /// The previous Token, after all transforms were applied to it.
+    pub prev: Option<&'c Token<P>>,
+    /// The next Token, before any transforms are applied to it.
+    pub next: Option<&'c Token<P>>,
+    /// The settings of the iterator, including any changes made by inline
+    ///     directives.
+    pub settings: &'c TranscriberSettings,
+}
+
+
+/// A change made to each [`Token`] by a [`TokenIter`](crate::TokenIter).
+pub trait Transform<P: Policy = Standard> {
+    /// The name of the transform, as it is recorded in a [`Trace`].
+    ///
+    /// [`Trace`]: crate::trace::Trace
+    fn name(&self) -> &'static str { "custom" }
+
+    /// Returns `true` if this is one of the built-in transforms, which are
+    ///     replaced by their counterparts when the [`Policy`] of a
+    ///     [`TokenIter`](crate::TokenIter) is changed.
+    #[doc(hidden)]
+    fn builtin(&self) -> bool { false }
+
+    /// Change a Token, in the context of the tokens around it.
+    fn apply(&self, token: &mut Token<P>, context: &Context<P>);
+}
+
+impl<P: Policy, F: Fn(&mut Token<P>, &Context<P>)> Transform<P> for F {
+    fn apply(&self, token: &mut Token<P>, context: &Context<P>) {
+        self(token, context)
+    }
+}
+
+
+/// Define built-in transforms that apply only to [`Glyph`]s.
+///
+/// [`Glyph`]: crate::Glyph
+macro_rules! glyph_transforms {
+    ($(
+        $(#[$attr:meta])*
+        $name:ident = $label:literal,
+        |$glyph:ident, $context:ident| $body:block
+    )*) => {$(
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+        pub struct $name;
+
+        impl<P: Policy> Transform<P> for $name {
+            fn name(&self) -> &'static str { $label }
+
+            fn builtin(&self) -> bool { true }
+
+            #[allow(unused_variables)]
+            fn apply(&self, token: &mut Token<P>, $context: &Context<P>) {
+                if let Token::Glyph($glyph) = token $body
+            }
+        }
+    )*};
+}
+
+glyph_transforms! {
+    /// Set the level of [zero-width joiner](crate::characters::ZWJ) ligation.
+    LigateZwj = "ligate_zwj", |glyph, context| {
+        glyph.ligate_zwj = context.settings.ligate_zwj;
+    }
+
+    /// Set whether to use the inverted "nuquerna" variants.
+    Nuquerna = "nuquerna", |glyph, context| {
+        glyph.nuquerna = context.settings.nuquerna;
+    }
+
+    /// Set the style of long vowels.
+    Vowels = "vowels", |glyph, context| {
+        glyph.vowels = context.settings.vowels;
+    }
+
+    /// Mark a glyph with a dot if it does not carry a vowel.
+    DotPlain = "dot_plain", |glyph, context| {
+        if context.settings.dot_plain && !glyph.carries_tehta() {
+            glyph.dot_under = true;
+        }
+    }
+
+    /// Hide the A-tehta, except where the settings keep it.
+    ElideA = "elide_a", |glyph, context| {
+        let settings: &TranscriberSettings = context.settings;
+
+        if settings.elide_a && glyph.tehta_is_a() {
+            if settings.keep_a_long && glyph.tehta_alt {
+                //  We want to shorten long vowels, and this one is long.
+                glyph.tehta_alt = false;
+
+                /*if glyph.base.is_some() { // TODO
+                    glyph.tehta_alt = false;
+                } else {
+                    glyph.tehta_hidden = true;
+                }*/
+            } else if settings.keep_a_init {
+                //  We want to keep an initial occurrence.
+                if matches!(context.prev, Some(Token::Glyph(_))) {
+                    //  This is not an initial occurrence. Elide it.
+                    glyph.tehta_hidden = true;
+                }
+            } else {
+                glyph.tehta_hidden = true;
+            }
+        }
+    }
+
+    /// Use the alternate form of the A-tehta.
+    AltA = "alt_a", |glyph, context| {
+        if context.settings.alt_a {
+            glyph.set_alt_a();
+        }
+    }
+
+    /// Use the alternate form of the sa-rincë, at the end of a word.
+    RinceFinal = "rince_final", |glyph, context| {
+        glyph.rince_final = match context.next {
+            Some(Token::Glyph(_)) => false,
+            _ => context.settings.alt_rince,
+        };
+    }
+
+    /// Ligate a short carrier with the glyph after it.
+    LigateShort = "ligate_short", |glyph, context| {
+        glyph.ligate_short = match context.next {
+            Some(Token::Glyph(next)) => context.settings.ligate_short
+                // && glyph.is_short_carrier()
+                && next.telco_ligates(),
+            _ => false,
+        };
+    }
+}
+
+
+/// A [`Transform`] for the tokens of one [`Policy`], applied to the tokens of
+///     another. The tokens are shown to it as if they were of its own policy.
+struct Retarget<P: Policy>(Box<dyn Transform<P>>);
+
+impl<P: Policy + 'static, Q: Policy> Transform<Q> for Retarget<P> {
+    fn name(&self) -> &'static str { self.0.name() }
+
+    fn apply(&self, token: &mut Token<Q>, context: &Context<Q>) {
+        let prev: Option<Token<P>> = context.prev.copied().map(Token::change_policy);
+        let next: Option<Token<P>> = context.next.copied().map(Token::change_policy);
+        let mut inner: Token<P> = token.change_policy();
+
+        self.0.apply(&mut inner, &Context {
+            prev: prev.as_ref(),
+            next: next.as_ref(),
+            settings: context.settings,
+        });
+
+        *token = inner.change_policy();
+    }
+}
+
+
+/// Move a [`Transform`] to the tokens of another [`Policy`]. A built-in
+///     transform is replaced by the same one for the new policy, so that it
+///     follows the rules of that policy. Any other transform is kept, and is
+///     shown the tokens as if they were of its original policy.
+pub(crate) fn change_policy<P: Policy + 'static, Q: Policy>(
+    transform: Box<dyn Transform<P>>,
+) -> Box<dyn Transform<Q>> {
+    let builtin: bool = transform.builtin();
+
+    match defaults::<Q>().into_iter().find(|new| builtin && new.name() == transform.name()) {
+        Some(new) => new,
+        None => Box::new(Retarget(transform)),
+    }
+}
+
+
+/// Return the built-in transforms, in the order they are applied by default.
+pub fn defaults<P: Policy>() -> Vec<Box<dyn Transform<P>>> {
+    vec![
+        Box::new(LigateZwj),
+        Box::new(Nuquerna),
+        Box::new(Vowels),
+        Box::new(DotPlain),
+        Box::new(ElideA),
+        Box::new(AltA),
+        Box::new(RinceFinal),
+        Box::new(LigateShort),
+    ]
+}
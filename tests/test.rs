@@ -104,3 +104,781 @@ fn test_transcribe() {
 
     assert_eq!(token_iter.count(), tokens_saved.len());
 }
+
+
+#[test]
+fn test_directives() {
+    use tengwar::characters::encoding::Encoding;
+
+    let with = |f: fn(&mut TranscriberSettings)| {
+        let mut settings = TranscriberSettings::new();
+        f(&mut settings);
+        settings
+    };
+    let directed = |input: &str, settings: TranscriberSettings| -> String {
+        Tokenizer::<Quenya>::from_str(input)
+            .with_directives()
+            .into_transcriber()
+            .with_settings(settings)
+            .into_string()
+    };
+    let none = TranscriberSettings::new();
+
+    //  Test changing settings from within the text.
+    assert_eq!(
+        directed("{+alt_a}vala", none),
+        "vala".to_tengwar_with::<Quenya>(with(|s| s.alt_a = true)),
+    );
+    assert_eq!(
+        directed("vala {+alt_a}vala {-alt_a}vala", none),
+        "vala ".to_tengwar::<Quenya>()
+            + &"vala ".to_tengwar_with::<Quenya>(with(|s| s.alt_a = true))
+            + &"vala".to_tengwar::<Quenya>(),
+    );
+    assert_eq!(
+        directed("{long=unique}téléré", none),
+        "téléré".to_tengwar_with::<Quenya>(with(|s| s.vowels = VowelStyle::Unique)),
+    );
+    assert_eq!(
+        directed("{-nuquerna}lotsë {+Nuquerna}lotsë", none),
+        "lotsë ".to_tengwar::<Quenya>()
+            + &"lotsë".to_tengwar_with::<Quenya>(with(|s| s.nuquerna = true)),
+    );
+
+    //  Directives are only read when enabled.
+    assert!(Tokenizer::<Quenya>::from_str("{+alt_a}vala")
+        .all(|token| !matches!(token, Token::Directive(_))));
+    assert_ne!(
+        "{+alt_a}vala".to_tengwar::<Quenya>(),
+        "vala".to_tengwar_with::<Quenya>(with(|s| s.alt_a = true)),
+    );
+
+    //  Directives are produced by the Tokenizer, but not by the Transcriber.
+    let tokens: Vec<Token> = Tokenizer::<Quenya>::from_str("{+alt_a}vala")
+        .with_directives()
+        .collect();
+    assert!(matches!(tokens[0], Token::Directive(Directive::AltA(true))));
+    assert!(Tokenizer::<Quenya>::from_str("{+alt_a}vala")
+        .with_directives()
+        .into_transcriber()
+        .all(|token| !matches!(token, Token::Directive(_))));
+
+    //  A directive that ends a word does not change the word.
+    let mut ts = Tokenizer::<Quenya>::from_str("vala{+alt_a}")
+        .with_directives()
+        .into_transcriber();
+    assert_eq!(ts.by_ref().count(), 2);
+    assert!(ts.settings.alt_a);
+
+    //  A directive within a word does not divide it, and a directive within a
+    //      glyph takes effect after it.
+    let glyphs = |input: &str| -> Vec<String> {
+        Tokenizer::<Quenya>::from_str(input)
+            .with_directives()
+            .filter(|token| !matches!(token, Token::Directive(_)))
+            .map(|token| token.to_string())
+            .collect()
+    };
+    assert_eq!(glyphs("ar{+dot_plain}a"), glyphs("ara"));
+    assert_eq!(glyphs("an{-nuquerna}dor"), glyphs("andor"));
+    assert_eq!(
+        directed("t{+alt_a}ata", none),
+        "ta".to_tengwar::<Quenya>() + &"ta".to_tengwar_with::<Quenya>(with(|s| s.alt_a = true)),
+    );
+
+    //  A final F is found at the end of its word, even after a directive.
+    let gondor = |input: &str| -> String {
+        Tokenizer::<Gondor>::from_str(input).with_directives().into_transcriber().into_string()
+    };
+    assert!(Encoding::Csur.recode(&gondor("ala{-dot_plain}f")).contains(TENGWA_AMPA));
+    assert_eq!(gondor("ala{-dot_plain}f"), "alaf".to_tengwar::<Gondor>());
+    assert_eq!(gondor("al{-dot_plain}firin"), "alfirin".to_tengwar::<Gondor>());
+
+    //  A directive does not hide the token after it from the rules.
+    let short = with(|s| s.ligate_short = true);
+    assert_ne!("ita".to_tengwar_with::<Quenya>(short), "ita".to_tengwar::<Quenya>());
+    assert_eq!(directed("i{-dot_plain}ta", short), "ita".to_tengwar_with::<Quenya>(short));
+
+    //  Malformed directives are passed through unchanged, and escaped ones are
+    //      passed through as text.
+    assert!(directed("{+alt_b}vala", none).starts_with("{+alt_b}"));
+    assert!(directed("{bogus=1}vala", none).starts_with("{bogus=1}"));
+    assert!(directed("{alt_a=maybe}", none).starts_with("{alt_a=maybe}"));
+    assert!(directed("\\{+alt_a}vala", none).starts_with("{+"));
+
+    //  Parsing and display.
+    assert_eq!("+alt_a".parse(), Ok(Directive::AltA(true)));
+    assert_eq!("-dot-plain".parse(), Ok(Directive::DotPlain(false)));
+    assert_eq!("long=u".parse(), Ok(Directive::Vowels(VowelStyle::Unique)));
+    assert_eq!("ligate_zwj=2".parse(), Ok(Directive::LigateZwj(2)));
+    assert_eq!("+ligate_zwj".parse(), Ok(Directive::LigateZwj(u8::MAX)));
+    assert!("alt_a".parse::<Directive>().is_err());
+    assert!("+vowels".parse::<Directive>().is_err());
+
+    for text in ["+alt_a", "-nuquerna", "vowels=unique", "ligate_zwj=3"] {
+        assert_eq!(text.parse::<Directive>().unwrap().to_string(), text);
+    }
+}
+
+
+#[test]
+fn test_spans() {
+    use tengwar::span::{Mapped, Span};
+
+    fn check(input: &str, mapped: &[Mapped], settings: TranscriberSettings) {
+        let output: String = Quenya::default_transcriber(input)
+            .with_settings(settings)
+            .collect();
+        let mut end: usize = 0;
+
+        for item in mapped {
+            assert!(end <= item.input.bytes.start, "{input:?}: {item:?}");
+            assert_eq!(&output[item.output.clone()], item.token.to_string());
+            assert_eq!(
+                input[item.input.bytes.clone()].chars().count(),
+                item.input.chars.len(),
+            );
+            end = item.input.bytes.end;
+        }
+
+        assert_eq!(end, input.len(), "{input:?}");
+        assert_eq!(mapped.last().unwrap().output.end, output.len());
+    }
+
+    let spans = |input: &str| -> Vec<(String, Span)> {
+        Tokenizer::<Quenya>::from_str(input).spanned()
+            .map(|(token, span)| (token.to_string(), span))
+            .collect()
+    };
+
+    //  Each token maps to the letters that produced it.
+    let composed = "namárië";
+    let mapped: Vec<Mapped> = Tokenizer::<Quenya>::from_str(composed)
+        .mapped(TranscriberSettings::new())
+        .collect();
+    let letters: Vec<&str> = mapped.iter().map(|m| &composed[m.input.bytes.clone()]).collect();
+    assert_eq!(letters, ["na", "má", "ri", "ë"]);
+    check(composed, &mapped, TranscriberSettings::new());
+
+    //  Decomposed input maps to the same letters.
+    let decomposed = "nama\u{301}rie\u{308}";
+    let mapped: Vec<Mapped> = Tokenizer::<Quenya>::from_str(decomposed)
+        .mapped(TranscriberSettings::new())
+        .collect();
+    let letters: Vec<&str> = mapped.iter().map(|m| &decomposed[m.input.bytes.clone()]).collect();
+    assert_eq!(letters, ["na", "ma\u{301}", "ri", "e\u{308}"]);
+    assert_eq!(mapped[3].input, Span { bytes: 8..11, chars: 7..9 });
+    check(decomposed, &mapped, TranscriberSettings::new());
+
+    //  Characters that compose across segments are kept together.
+    assert_eq!(spans("\u{1100}\u{1161}\u{11A8}"), [
+        ("\u{AC01}".into(), Span { bytes: 0..9, chars: 0..3 }),
+    ]);
+
+    //  Escape sequences are counted toward the escaped token.
+    assert_eq!(spans("a\\n"), [
+        (Quenya::transcribe::<String>("a"), Span { bytes: 0..1, chars: 0..1 }),
+        ("n".into(), Span { bytes: 1..3, chars: 1..3 }),
+    ]);
+
+    //  Output positions account for joiners and directives.
+    let mut settings = TranscriberSettings::new();
+    settings.ligate_zwj = u8::MAX;
+    for input in ["lotsë súva", "{+alt_a}namárië {-alt_a}namárië", "hrívë"] {
+        let mapped: Vec<Mapped> = Tokenizer::<Quenya>::from_str(input)
+            .mapped(settings)
+            .collect();
+        check(input, &mapped, settings);
+    }
+
+    //  Positions in marked-up text are relative to the whole input.
+    let markup = Markup::new(Box::new(Gondor::default()));
+    let input = "mellon {q:namárië}";
+    let mapped: Vec<Mapped> = markup.boxed_mapped(input, TranscriberSettings::new()).collect();
+    let letters: Vec<&str> = mapped.iter().map(|m| &input[m.input.bytes.clone()]).collect();
+    assert_eq!(letters, ["m", "ell", "on", " ", "na", "má", "ri", "ë"]);
+    assert_eq!(
+        mapped.iter().map(|m| m.token.to_string()).collect::<String>(),
+        markup.transcribe_with(input, TranscriberSettings::new()),
+    );
+}
+
+
+#[test]
+fn test_trace() {
+    use tengwar::trace::{Action, Explanation, Trace};
+
+    let explain = |input: &str, settings: TranscriberSettings| -> Vec<Explanation> {
+        let trace = Trace::shared();
+        let _: String = TokenIter::new(
+            Tokenizer::<Quenya>::from_str(input).with_directives().with_trace(trace.clone()),
+        ).with_settings(settings).with_trace(trace.clone()).collect();
+        let explained = trace.borrow().explain();
+        explained
+    };
+
+    //  Steps are given to the token they produced.
+    let explained = explain("nat", TranscriberSettings::new());
+    assert_eq!(explained.len(), 2);
+    assert_eq!(explained[0].steps.last(), Some(&("a".into(), Action::MatchedToken(1))));
+    assert_eq!(explained[1].steps.last(), Some(&("".into(), Action::Finish)));
+
+    //  Rules are recorded only where they change a token.
+    let mut settings = TranscriberSettings::new();
+    settings.alt_a = true;
+    settings.dot_plain = true;
+    let explained = explain("nat", settings);
+    let rules: Vec<Vec<&str>> = explained.iter()
+        .map(|exp| exp.rules.iter().map(|(rule, _)| *rule).collect())
+        .collect();
+    assert_eq!(rules, [vec!["alt_a"], vec!["dot_plain"]]);
+    assert!(explained.iter().all(|exp| exp.finalized.is_none()));
+
+    //  Directives are counted, and take effect from the next token.
+    let explained = explain("na{+alt_a}na", TranscriberSettings::new());
+    assert_eq!(explained.len(), 3);
+    assert!(matches!(explained[1].token, Token::Directive(_)));
+    assert!(explained[0].rules.is_empty());
+    assert_eq!(explained[2].rules[0].0, "alt_a");
+
+    //  Changes made by the mode are recorded.
+    let trace = Trace::shared();
+    let _: String = Gondor::default().boxed_explained("ered", TranscriberSettings::new(), &trace)
+        .map(|mapped| mapped.token)
+        .collect();
+    let explained = trace.borrow().explain();
+    let Some(Token::Glyph(glyph)) = explained[0].finalized else { panic!() };
+    assert_eq!(glyph.base, Some(TENGWA_ROMEN));
+
+    //  Tokens from several modes are recorded in order.
+    let markup = Markup::new(Box::new(Gondor::default()));
+    let trace = Trace::shared();
+    let mapped: Vec<_> = markup
+        .boxed_explained("mellon {q:namárië}", TranscriberSettings::new(), &trace)
+        .collect();
+    let explained = trace.borrow().explain();
+    assert_eq!(explained.len(), mapped.len());
+
+    for (exp, mapped) in explained.iter().zip(&mapped) {
+        assert_eq!(exp.result().to_string(), mapped.token.to_string());
+    }
+}
+
+
+#[test]
+fn test_transforms() {
+    use tengwar::{characters::encoding::Encoding, trace::Trace, transform::{Context, Transform}};
+
+    /// Use Ossë, rather than Vala, at the start of a word.
+    struct InitialOsse;
+
+    impl Transform for InitialOsse {
+        fn name(&self) -> &'static str { "initial_osse" }
+
+        fn apply(&self, token: &mut Token, context: &Context) {
+            if let Token::Glyph(glyph) = token {
+                if !matches!(context.prev, Some(Token::Glyph(_))) {
+                    glyph.replace_base(TENGWA_VALA, TENGWA_OSSE);
+                }
+            }
+        }
+    }
+
+    /// Use Ossë at the start of a word, under the name of a built-in transform.
+    struct Shadow;
+
+    impl Transform for Shadow {
+        fn name(&self) -> &'static str { "alt_a" }
+
+        fn apply(&self, token: &mut Token, context: &Context) {
+            InitialOsse.apply(token, context);
+        }
+    }
+
+    let glyphs = |text: String| -> Vec<char> {
+        Encoding::Csur.recode(&text).chars().filter(|c| [TENGWA_VALA, TENGWA_OSSE].contains(c)).collect()
+    };
+
+    //  A transform sees the tokens around it.
+    let text: String = Quenya::default_transcriber("vanya avar")
+        .with_transform(InitialOsse)
+        .collect();
+    assert_eq!(glyphs(text), [TENGWA_OSSE, TENGWA_VALA]);
+
+    //  Transforms are kept when the policy is changed.
+    let text: String = Quenya::default_transcriber("vanya avar")
+        .with_transform(InitialOsse)
+        .set_policy::<tengwar::policy::FreeMono>()
+        .collect();
+    assert_eq!(glyphs(text), [TENGWA_OSSE, TENGWA_VALA]);
+
+    //  A custom transform is kept even if it has the name of a built-in one.
+    let text: String = Quenya::default_transcriber("vanya avar")
+        .with_transform(Shadow)
+        .set_policy::<tengwar::policy::FreeMono>()
+        .collect();
+    assert_eq!(glyphs(text), [TENGWA_OSSE, TENGWA_VALA]);
+
+    //  Without any transforms, settings have no effect.
+    let mut settings = TranscriberSettings::new();
+    settings.alt_a = true;
+    let mut transcriber = Quenya::default_transcriber("namárië").with_settings(settings);
+    transcriber.transforms.clear();
+    assert_eq!(transcriber.collect::<String>(), Quenya::transcribe::<String>("namárië"));
+
+    //  A built-in transform can be removed by its name.
+    let mut transcriber = Quenya::default_transcriber("namárië").with_settings(settings);
+    transcriber.transforms.retain(|t| t.name() != "alt_a");
+    assert_eq!(transcriber.collect::<String>(), Quenya::transcribe::<String>("namárië"));
+
+    //  Transforms are applied in order, after the built-in transforms.
+    let trace = Trace::shared();
+    let _: String = TokenIter::new(Tokenizer::<Quenya>::from_str("vala").with_trace(trace.clone()))
+        .with_transform(InitialOsse)
+        .with_transform(|token: &mut Token, _: &Context| {
+            if let Token::Glyph(glyph) = token {
+                glyph.replace_base(TENGWA_OSSE, TENGWA_VALA);
+            }
+        })
+        .with_trace(trace.clone())
+        .collect();
+    let explained = trace.borrow().explain();
+    let rules: Vec<&str> = explained[0].rules.iter().map(|(rule, _)| *rule).collect();
+    assert_eq!(rules, ["initial_osse", "custom"]);
+}
+
+
+#[test]
+fn test_neighbors() {
+    use tengwar::policy::Policy;
+
+    /// The Classical Mode, marking the first and last glyphs of each word.
+    #[derive(Default)]
+    struct Edges(Quenya);
+
+    impl TengwarMode for Edges {
+        const LOOKBEHIND: usize = 2;
+        const LOOKAHEAD: usize = 2;
+
+        fn finalize_in<P: Policy>(&self, token: &mut Token<P>, neighbors: &Neighbors<P>) {
+            assert!(neighbors.before.len() <= 2);
+            assert!(neighbors.after.len() <= 2);
+
+            if let Token::Glyph(glyph) = token {
+                glyph.dot_inner = neighbors.word_initial();
+                glyph.dot_under = neighbors.word_final();
+            }
+        }
+
+        fn finish_current<P: Policy>(&mut self) -> Option<Token<P>> {
+            self.0.finish_current()
+        }
+
+        fn process<P: Policy>(&mut self, chunk: &[char]) -> ParseAction<P> {
+            self.0.process(chunk)
+        }
+    }
+
+    let edges = |input: &str| -> Vec<(bool, bool)> {
+        Tokenizer::<Edges>::from_str(input)
+            .with_directives()
+            .filter_map(|token| token.glyph().map(|g| (g.dot_inner, g.dot_under)))
+            .collect()
+    };
+
+    //  Each word is bounded by the tokens around it.
+    assert_eq!(edges("namárië"), [
+        (true, false), (false, false), (false, false), (false, true),
+    ]);
+    assert_eq!(edges("ar, ve"), [(true, false), (false, true), (true, true)]);
+
+    //  Directives do not divide words.
+    assert_eq!(edges("na{+alt_a}ma"), edges("nama"));
+
+    //  A word may be found from the tokens around it.
+    let neighbors: Neighbors = Neighbors {
+        before: &[Token::Char(' '), Token::Glyph(Glyph::new_base(TENGWA_TINCO))],
+        after: &[],
+    };
+    assert_eq!(neighbors.prev(0).and_then(Token::glyph).and_then(|g| g.base), Some(TENGWA_TINCO));
+    assert!(!neighbors.word_initial());
+    assert!(neighbors.word_final());
+    assert_eq!(neighbors.word_before().count(), 1);
+}
+
+
+#[test]
+fn test_encoding() {
+    use tengwar::{characters::encoding::{self, Encoding}, reverse::Romanize};
+
+    let csur: String = Encoding::Csur.recode(&Quenya::transcribe::<String>("namárië !"));
+    let smp: String = Encoding::Smp.recode(&csur);
+
+    //  Every character of the Tengwar is moved, and nothing else.
+    assert_eq!(Encoding::detect(&csur), Some(Encoding::Csur));
+    assert_eq!(Encoding::detect(&smp), Some(Encoding::Smp));
+    assert_eq!(Encoding::detect("namárië"), None);
+    assert_eq!(smp.chars().count(), csur.chars().count());
+    assert!(smp.chars().all(|c| !Encoding::Csur.block().unwrap().contains(&c)));
+    assert!(smp.contains(' '));
+
+    //  Conversion works in both directions, and on mixed text.
+    assert_eq!(Encoding::Csur.recode(&smp), csur);
+    assert_eq!(Encoding::Smp.recode(&smp), smp);
+    assert_eq!(Encoding::Csur.recode(&format!("{csur}{smp}")), csur.repeat(2));
+    assert_eq!(Encoding::Smp.decode(Encoding::Smp.encode(TENGWA_ANTO)), TENGWA_ANTO);
+    assert_eq!(Encoding::Smp.encode('a'), 'a');
+
+    //  A codepoint of the CSUR block that has no proposed codepoint is kept.
+    assert_eq!(encoding::smp(TENGWA_TINCO), Some('\u{16080}'));
+    assert_eq!(encoding::smp('\u{E07F}'), None);
+    assert_eq!(Encoding::Smp.encode('\u{E07F}'), '\u{E07F}');
+    assert_eq!(Encoding::Smp.decode('\u{160FF}'), '\u{160FF}');
+
+    //  Text in either encoding can be read back.
+    assert_eq!(Quenya::romanize(&smp), Quenya::romanize(&csur));
+}
+
+
+#[test]
+fn test_fonts() {
+    use tengwar::{characters::encoding::Encoding, mode::DynMode, policy::*};
+
+    //  The output is compared in the CSUR block.
+    let render = |font: Font, input: &str, settings: TranscriberSettings| -> String {
+        Encoding::Csur.recode(&font.render(Quenya::default().boxed_tokenizer(input), settings))
+    };
+
+    //  The standard policy is used for Tengwar Telcontar.
+    let settings = TranscriberSettings { ligate_zwj: 3, ..Default::default() };
+    assert_eq!(
+        render(Font::Telcontar, "istar", settings),
+        Encoding::Csur.recode(&Quenya::default_transcriber("istar").with_settings(settings).into_string()),
+    );
+
+    //  FreeMono forms no ligatures, regardless of the settings.
+    assert!(render(Font::Telcontar, "istar", settings).contains(ZWJ));
+    assert!(!render(Font::FreeMono, "istar", settings).contains(ZWJ));
+    assert!(!Font::FreeMono.settings().ligate_short);
+
+    //  Tengwar Formal and Alcarin allow the final rincë on the left-bow témar.
+    let settings = TranscriberSettings { alt_rince: true, ..Default::default() };
+    assert!(!render(Font::Telcontar, "lucs", settings).contains(SA_RINCE_FINAL));
+    assert!(render(Font::FormalCsur, "lucs", settings).contains(SA_RINCE_FINAL));
+    assert!(render(Font::Alcarin, "lucs", settings).contains(SA_RINCE_FINAL));
+    assert!(FormalCsur::rince_valid_final(TENGWA_QESSE));
+    assert!(!FreeMono::rince_valid_final(TENGWA_TINCO));
+
+    //  A font keeps the transforms of an iterator, under its own rules.
+    let iter = TokenIter::new(Quenya::default().boxed_tokenizer("vanya istar"))
+        .with_settings(TranscriberSettings { ligate_zwj: 3, ..Default::default() })
+        .with_transform(|token: &mut Token, _: &tengwar::transform::Context| {
+            if let Token::Glyph(glyph) = token {
+                glyph.replace_base(TENGWA_VALA, TENGWA_OSSE);
+            }
+        });
+    let text: String = Encoding::Csur.recode(&Font::FreeMono.render_iter(iter));
+    assert!(text.contains(TENGWA_OSSE));
+    assert!(!text.contains(ZWJ));
+
+    //  Every font keeps the nuquernë variants.
+    for font in [Font::Telcontar, Font::FormalCsur, Font::Alcarin, Font::FreeMono, Font::Annatar] {
+        assert!(render(font, "si", font.settings()).contains(TENGWA_SILME_NUQ));
+    }
+
+    //  The fonts of the Dan Smith layout expect it.
+    assert_eq!(Font::Annatar.encoding(), Encoding::DanSmith);
+    assert_eq!(Font::Telcontar.encoding(), Encoding::DEFAULT);
+}
+
+
+#[test]
+#[cfg(feature = "policy-custom")]
+fn test_policy_file() {
+    use tengwar::policy::{data::*, Font, Policy, Standard};
+
+    let file = PolicyFile::from_toml_str(concat!(
+        "extends = \"free-mono\"\n",
+        "ara = \"\\uE000\"\n",
+        "rince = \"\\uE000\\uE001\"\n",
+        "[nuquerna]\n",
+        "\"\\uE024\" = \"\\uE025\"\n",
+        "[[ligature]]\n",
+        "before = \"\\uE024\"\n",
+        "after = \"\\uE000\"\n",
+        "level = 2\n",
+    )).unwrap();
+
+    assert_eq!(file.extends, Some(Font::FreeMono));
+    assert!(file.telco.is_none());
+
+    //  Without a file, there are no rules.
+    let empty = DataPolicy::default();
+    assert!(!empty.rince_valid_in(TENGWA_TINCO));
+    assert!(!empty.nuquerna_valid_in(TENGWA_SILME));
+
+    //  Rules defined by the file replace those of the font.
+    let policy = DataPolicy::new(Box::leak(Box::new(file.clone())));
+    assert!(policy.ligates_with_ara_in(TENGWA_TINCO));
+    assert!(!policy.ligates_with_ara_in(TENGWA_PARMA));
+    assert!(policy.rince_valid_in(TENGWA_PARMA));
+    assert!(!policy.rince_valid_in(TENGWA_CALMA));
+    assert_eq!(policy.nuquerna_in(TENGWA_SILME), TENGWA_SILME_NUQ);
+    assert!(!policy.nuquerna_valid_in(TENGWA_ESSE));
+
+    //  Rules not defined by the file are taken from the font.
+    assert!(!policy.telco_ligates_with_in(TENGWA_TINCO));
+    assert!(Standard::telco_ligates_with(TENGWA_TINCO));
+
+    //  By its type alone, the policy has no rules.
+    assert!(!DataPolicy::rince_valid(TENGWA_PARMA));
+
+    let transcriber = |policy: DataPolicy, text: &str| {
+        Tokenizer::<Quenya, DataPolicy>::from_str(text).with_policy(policy).into_transcriber()
+    };
+
+    //  A sa-rincë is only placed where the file allows it.
+    let text: String = transcriber(policy, "otso").collect();
+    assert!(text.contains(SA_RINCE));
+    let text: String = transcriber(policy, "ocso").collect();
+    assert!(!text.contains(SA_RINCE));
+
+    //  Each policy follows its own file.
+    let mut other = PolicyFile::new(Some(Font::Telcontar));
+    other.rince = Some(TENGWA_CALMA.to_string());
+    let text: String = transcriber(DataPolicy::new(Box::leak(Box::new(other))), "ocso").collect();
+    assert!(text.contains(SA_RINCE));
+
+    //  The rules of the file are kept through a mode selected at runtime.
+    let tokens = Quenya::default().boxed_tokenizer_in("ocso", policy, false);
+    let text: String = TokenIter::new(tokens).collect();
+    assert!(!text.contains(SA_RINCE));
+
+    //  Ligatures are formed from the level given.
+    let settings = |ligate_zwj: u8| TranscriberSettings { ligate_zwj, ..Default::default() };
+    let text = |level: u8| -> String {
+        transcriber(policy, "ista").with_settings(settings(level)).collect()
+    };
+    assert!(!text(1).contains(ZWJ));
+    assert!(text(2).contains(ZWJ));
+
+    //  The file can be written back out.
+    let text: String = file.to_toml_string().unwrap();
+    let again = PolicyFile::from_toml_str(&text).unwrap();
+    assert_eq!(again.ligatures, file.ligatures);
+    assert_eq!(again.nuquerna, file.nuquerna);
+
+    let err = PolicyFile::from_toml_str("extends = \"comic-sans\"\n").unwrap_err();
+    assert!(err.to_string().contains("line 1"), "{err}");
+}
+
+
+#[test]
+fn test_dan_smith() {
+    use tengwar::characters::{dan_smith::{self, Width}, encoding::Encoding};
+
+    //  Each tehta is placed to suit the width of the tengwa before it.
+    assert_eq!(Width::of(TENGWA_TINCO), Width::Narrow);
+    assert_eq!(Width::of(TENGWA_ANDO), Width::Wide);
+    assert_eq!(Width::of(TENGWA_TELCO), Width::Carrier);
+
+    let text: String = [TENGWA_TINCO, TEHTA_A.base, TENGWA_ANDO, TEHTA_A.base].iter().collect();
+    assert_eq!(dan_smith::encode(&text), "1D2E");
+    assert_eq!(dan_smith::encode(&Encoding::Smp.recode(&text)), "1D2E");
+
+    let text: String = [CARRIER_SHORT, TEHTA_E.base, TENGWA_NUMEN, TEHTA_E.base].iter().collect();
+    assert_eq!(dan_smith::encode(&text), "`V5R");
+
+    //  Doubled tehtar are written twice, and joiners are removed.
+    let text: String = [TENGWA_ORE, DC_OVER_HOOK_R_2, ZWJ, CARRIER_LONG].iter().collect();
+    assert_eq!(dan_smith::encode(&text), "6HH~");
+
+    //  Numerals and punctuation are converted, and anything else is kept.
+    assert_eq!(dan_smith::encode(&NUMERAL[..10].iter().collect::<String>()), "ðñòóôõö÷øù");
+    assert_eq!(dan_smith::encode(&format!("{PUNCT_DOT_1} {DC_UNDER_RING}")), format!("= {DC_UNDER_RING}"));
+
+    let csur: String = Encoding::Csur.recode(&Quenya::transcribe::<String>("namárië !"));
+    let ascii: String = Encoding::DanSmith.recode(&csur);
+
+    assert!(ascii.chars().all(|c| Encoding::detect(&c.to_string()).is_none()));
+    assert!(ascii.contains(' '));
+    assert_eq!(Encoding::DanSmith.recode(&Encoding::Smp.recode(&csur)), ascii);
+
+    //  Every key of a tehta is imported as the same character.
+    assert_eq!(dan_smith::decode("1#1E1D1C"), dan_smith::decode("1D1D1D1D"));
+    assert_eq!(Encoding::DanSmith.import(&ascii), csur);
+    assert_eq!(Encoding::Csur.import(&csur), csur);
+
+    //  The import is lossy, converting plain text that uses the keys as well.
+    let text: String = [
+        TENGWA_MALTA, TENGWA_SILME_NUQ, TENGWA_WILYA, TENGWA_HWESTA, TENGWA_HWESTA_SINDARINWA,
+        ' ', TENGWA_TINCO,
+    ].iter().collect();
+    assert_eq!(dan_smith::decode("tinco 1"), text);
+    assert_eq!(dan_smith::decode("\tP\n"), "\tP\n");
+}
+
+
+/// Build a minimal font file, with a glyph for each of some codepoints, and a
+///     ligature for each of some pairs of them joined by a zero-width joiner.
+#[cfg(feature = "font-coverage")]
+fn build_font(chars: &[char], ligatures: &[(char, char)]) -> Vec<u8> {
+    fn push(buf: &mut Vec<u8>, values: &[u16]) {
+        for value in values { buf.extend(value.to_be_bytes()); }
+    }
+
+    let mut chars: Vec<char> = chars.iter().copied().chain([ZWJ]).collect();
+    chars.sort();
+    chars.dedup();
+
+    let glyph = |c: char| chars.iter().position(|&x| x == c).unwrap() as u16 + 1;
+    let count = chars.len() as u16;
+
+    let mut head: Vec<u8> = vec![0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0x5F, 0x0F, 0x3C, 0xF5];
+    push(&mut head, &[0, 1000]);
+    head.resize(54, 0);
+
+    let mut hhea: Vec<u8> = vec![0, 1, 0, 0];
+    hhea.resize(34, 0);
+    push(&mut hhea, &[1]);
+
+    let mut maxp: Vec<u8> = vec![0, 0, 0x50, 0];
+    push(&mut maxp, &[count + 1]);
+
+    //  A format 12 character map, with one group for each codepoint.
+    let mut cmap: Vec<u8> = Vec::new();
+    push(&mut cmap, &[0, 1, 3, 10, 0, 12, 12, 0, 0, 16 + 12 * count, 0, 0, 0, count]);
+
+    for &c in &chars {
+        let code = c as u32;
+        push(&mut cmap, &[(code >> 16) as u16, code as u16, (code >> 16) as u16, code as u16]);
+        push(&mut cmap, &[0, glyph(c)]);
+    }
+
+    //  A single lookup of ligature substitutions, grouped by the first glyph.
+    let mut firsts: Vec<u16> = ligatures.iter().map(|&(lhs, _)| glyph(lhs)).collect();
+    firsts.sort();
+    firsts.dedup();
+
+    let n = firsts.len() as u16;
+    let mut subtable: Vec<u8> = Vec::new();
+    let mut sets: Vec<u8> = Vec::new();
+    let sets_start: u16 = 6 + 2 * n + 4 + 2 * n;
+
+    push(&mut subtable, &[1, 6 + 2 * n, n]);
+
+    for &first in &firsts {
+        let rhs: Vec<u16> = ligatures.iter()
+            .filter(|&&(lhs, _)| glyph(lhs) == first)
+            .map(|&(_, rhs)| glyph(rhs))
+            .collect();
+        let k = rhs.len() as u16;
+
+        push(&mut subtable, &[sets_start + sets.len() as u16]);
+        push(&mut sets, &[k]);
+
+        for i in 0..k {
+            push(&mut sets, &[2 + 2 * k + 8 * i]);
+        }
+
+        for rhs in rhs {
+            push(&mut sets, &[0, 3, glyph(ZWJ), rhs]);
+        }
+    }
+
+    push(&mut subtable, &[1, n]);
+    push(&mut subtable, &firsts);
+    subtable.extend(sets);
+
+    let mut gsub: Vec<u8> = Vec::new();
+    push(&mut gsub, &[1, 0, 10, 12, 14, 0, 0, 1, 4, 4, 0, 1, 8]);
+    gsub.extend(subtable);
+
+    //  Tables must be listed in order of their tags.
+    let tables: [(&[u8; 4], Vec<u8>); 5] = [
+        (b"GSUB", gsub),
+        (b"cmap", cmap),
+        (b"head", head),
+        (b"hhea", hhea),
+        (b"maxp", maxp),
+    ];
+
+    let mut font: Vec<u8> = vec![0, 1, 0, 0];
+    push(&mut font, &[tables.len() as u16, 0, 0, 0]);
+
+    let mut data: Vec<u8> = Vec::new();
+    let start = 12 + 16 * tables.len();
+
+    for (tag, table) in tables {
+        let offset = (start + data.len()) as u32;
+
+        font.extend(tag);
+        font.extend([0; 4]);
+        font.extend(offset.to_be_bytes());
+        font.extend((table.len() as u32).to_be_bytes());
+
+        data.extend(&table);
+        data.resize(data.len().next_multiple_of(4), 0);
+    }
+
+    font.extend(data);
+    font
+}
+
+
+#[test]
+#[cfg(feature = "font-coverage")]
+fn test_font_coverage() {
+    use tengwar::policy::{coverage::*, Standard};
+
+    let all: Vec<char> = FontCoverage::all_codepoints().collect();
+    let basic: Vec<char> = all.iter().copied()
+        .filter(|&c| c != DC_OVER_HOOK_L_2 && c != CARRIER_SHORT_LIG && c != SA_RINCE_FINAL)
+        .collect();
+
+    //  Every codepoint is read from the character map.
+    let coverage = FontCoverage::from_data(&build_font(&basic, &[])).unwrap();
+
+    assert_eq!(coverage.codepoints.len(), all.len() - 3);
+    assert_eq!(coverage.missing().collect::<Vec<_>>(), [CARRIER_SHORT_LIG, DC_OVER_HOOK_L_2, SA_RINCE_FINAL]);
+    assert!(coverage.ligatures.is_empty());
+    assert_eq!(coverage.ligate_zwj::<Standard>(), 0);
+
+    //  Settings needing a missing codepoint are disabled.
+    let settings = coverage.settings();
+
+    assert!(!settings.ligate_short);
+    assert!(settings.nuquerna);
+    assert_eq!(settings.vowels, VowelStyle::Separate);
+
+    let wanted = TranscriberSettings { alt_rince: true, vowels: VowelStyle::Doubled, ..settings };
+    assert!(!coverage.restrict(wanted).alt_rince);
+    assert_eq!(coverage.restrict(wanted).vowels, VowelStyle::Doubled);
+
+    //  Ligatures are read from the substitution table, and each level needs
+    //      the levels below it.
+    let ara = (TENGWA_TINCO, CARRIER_LONG);
+    let silme = (TENGWA_SILME, TENGWA_PARMA);
+    let regular = (TENGWA_CALMA, TENGWA_TINCO);
+
+    let coverage = FontCoverage::from_data(&build_font(&all, &[ara, silme])).unwrap();
+
+    assert_eq!(coverage.ligatures, [ara, silme].into());
+    assert_eq!(coverage.ligation::<Standard>(1).0, 1);
+    assert_eq!(coverage.ligation::<Standard>(2).0, 1);
+    assert_eq!(coverage.ligation::<Standard>(3).0, 0);
+    assert_eq!(coverage.ligate_zwj::<Standard>(), 2);
+
+    let settings = coverage.settings();
+
+    assert!(settings.ligate_short);
+    assert_eq!(settings.ligate_zwj, 2);
+    assert_eq!(settings.vowels, VowelStyle::Unique);
+
+    let coverage = FontCoverage::from_data(&build_font(&all, &[silme, regular])).unwrap();
+    assert_eq!(coverage.ligate_zwj::<Standard>(), 0);
+
+    //  A file that is not a font cannot be read.
+    assert!(matches!(FontCoverage::from_data(b"not a font"), Err(FontFileError::Parse(_))));
+    assert!(matches!(FontCoverage::from_path("/nonexistent/font.ttf"), Err(FontFileError::Io(_))));
+}